@@ -1,4 +1,4 @@
-use crate::eval::ExpressionEvaluator;
+use crate::eval::{match_arms, ExpressionEvaluator};
 use crate::value::implement::*;
 use berg_parser::{
     Ast, AstIndex, BlockIndex, ExpressionPosition, ExpressionToken, ExpressionTreeWalker,
@@ -112,6 +112,38 @@ impl BlockRef {
             .evaluate()
     }
 
+    ///
+    /// Runs `match subject <this block>`, where this block holds the arms.
+    /// Each match runs in its own copy of the block, so the fields the patterns
+    /// bind don't leak from one match to the next.
+    ///
+    /// Returns `None` if no arm matches.
+    ///
+    pub fn run_match(&self, subject: &BergVal) -> Result<Option<BergVal>, EvalException> {
+        let (scope, ast, expression) = {
+            let block = self.0.borrow();
+            let ast = block.ast();
+            let scope = Self::new(
+                block.expression,
+                block.index,
+                block.parent.clone(),
+                empty_tuple().ok(),
+            );
+            {
+                let mut scope_block = scope.0.borrow_mut();
+                scope_block.state = BlockState::Running;
+                scope_block.fields.resize(
+                    ast.blocks[block.index].scope_count.into(),
+                    BlockFieldValue::NotDeclared,
+                );
+            }
+            (scope, ast, block.expression)
+        };
+        let result = match_arms::run_match(&scope, &ast, expression, subject);
+        scope.0.borrow_mut().state = BlockState::Complete(empty_tuple().ok());
+        result
+    }
+
     fn take_result(&self, replace_with: BlockState) -> BergResult {
         self.ensure_evaluated()?;
         let mut block = self.0.borrow_mut();
@@ -177,31 +209,26 @@ impl BlockRef {
         };
 
         // Run the block
-        let expression = ExpressionEvaluator::new(self, &ast, expression);
-        println!("{}----------------------------------------", self.indent());
-        println!("{} Block evaluating {:?}", self.indent(), expression);
-        if let Some(input) = &self.0.borrow().input {
-            println!("{} Input: {}", self.indent(), input.display());
+        let tracer = ast.root.tracer();
+        if let Some(tracer) = tracer {
+            tracer.trace(&TraceEvent::BlockStart {
+                block: ExpressionRef::from(self).traced(),
+            });
+        }
+        let result = ExpressionEvaluator::new(self, &ast, expression)
+            .evaluate_block(ast.blocks[index].boundary);
+        if let Some(tracer) = tracer {
+            tracer.trace(&TraceEvent::BlockComplete {
+                block: ExpressionRef::from(self).traced(),
+                result: result.display(),
+            });
         }
-        let result = expression.evaluate_block(ast.blocks[index].boundary);
 
         // Stash the result and return
         self.0.borrow_mut().state = BlockState::Complete(result);
-        println!("{} Block state after evaluation: {}", self.indent(), self);
-        println!("{}________________________________________", self.indent());
-        println!();
         Ok(())
     }
 
-    fn indent(&self) -> String {
-        let block = self.0.borrow();
-        let ast = block.ast();
-        let expression = ExpressionEvaluator::new(self, &ast, block.expression);
-        let mut result = "  ".repeat(expression.depth());
-        result.push('|');
-        result
-    }
-
     pub fn local_field(&self, index: FieldIndex, ast: &Ast) -> EvalResult {
         use BlockFieldValue::*;
         let block = self.0.borrow();
@@ -286,10 +313,15 @@ impl BlockRef {
 
         // Get the value *before* we mutably borrow, so that next_val can read
         // current field values if it needs to.
-        let next = self.next_input();
-        let name = ast.identifier_string(ast.fields[field_index].name);
-        println!("{} Received argument {} = {:?}", self.indent(), name, next);
-        let value = next?.map(Val).unwrap_or(NotSet);
+        let next = self.next_input()?;
+        if let Some(tracer) = self.ast().root.tracer() {
+            tracer.trace(&TraceEvent::FieldDeclared {
+                block: ExpressionRef::from(self).traced(),
+                name: ast.identifier_string(ast.fields[field_index].name),
+                value: next.as_ref().map(|value| value.display()),
+            });
+        }
+        let value = next.map(Val).unwrap_or(NotSet);
         self.0.borrow_mut().fields[block_field_index] = value;
 
         Ok(())
@@ -309,13 +341,13 @@ impl BlockRef {
                 .parent
                 .set_local_field(field_index, value, ast);
         }
-        println!(
-            "{}Set {} to {:?}: {}",
-            self.indent(),
-            ast.identifier_string(ast.fields[field_index].name),
-            value,
-            self
-        );
+        if let Some(tracer) = self.ast().root.tracer() {
+            tracer.trace(&TraceEvent::FieldSet {
+                block: ExpressionRef::from(self).traced(),
+                name: ast.identifier_string(ast.fields[field_index].name),
+                value: value.display(),
+            });
+        }
         {
             let mut block = self.0.borrow_mut();
             let index: usize = (field_index - scope_start).into();
@@ -324,10 +356,39 @@ impl BlockRef {
         Ok(())
     }
 
+    ///
+    /// Sets the field with the given name, if this block (rather than one of
+    /// its parents) has one. Blocks only have fields for names they use, so
+    /// there is nothing to set if the block never mentions the name.
+    ///
+    pub fn set_local_field_named(
+        &self,
+        name: IdentifierIndex,
+        value: BergVal,
+    ) -> Result<(), EvalException> {
+        let ast = self.ast();
+        let index = {
+            let block = self.0.borrow();
+            ast.blocks[block.index].local_field_index(block.index, name, &ast)
+        };
+        match index {
+            Some(index) => self.set_local_field(index, value, &ast),
+            None => Ok(()),
+        }
+    }
+
     pub fn ast(&self) -> AstRef {
         self.0.borrow().ast()
     }
 
+    fn has_public_field(&self, name: IdentifierIndex) -> bool {
+        let ast = self.ast();
+        let block = self.0.borrow();
+        ast.blocks[block.index]
+            .public_field_index(block.index, name, &ast)
+            .is_ok()
+    }
+
     pub fn field_error<T>(
         &self,
         error: FieldError,
@@ -428,28 +489,14 @@ impl ObjectValue for BlockRef {
     where
         Self: Sized,
     {
-        println!(
-            "{}====> get {} on {}",
-            self.indent(),
-            self.ast().identifier_string(name),
-            self
-        );
         // Always try to get the field from the inner result first
-        let current = self.clone_result();
-        println!("{}got from {:?}", self.indent(), self);
-        let current = current?;
-        println!("{}current = {}", self.indent(), current);
+        let current = self.clone_result()?;
         match current.field(name) {
             // If we couldn't find the field on the inner value, see if our block has the field
             Err(EvalException::Thrown(
                 BergVal::CompilerError(ref error),
                 ExpressionPosition::Expression,
             )) if error.code() == CompilerErrorCode::NoSuchPublicField => {
-                println!(
-                    "{}====> no such public {} on current",
-                    self.indent(),
-                    self.ast().identifier_string(name)
-                );
                 let ast = self.ast();
                 let index = {
                     let block = self.0.borrow();
@@ -459,26 +506,7 @@ impl ObjectValue for BlockRef {
                 };
                 self.local_field(index, &ast)
             }
-            Ok(value) => {
-                println!(
-                    "{}====> got {:?} for {} on {}",
-                    self.indent(),
-                    value,
-                    self.ast().identifier_string(name),
-                    self
-                );
-                Ok(value)
-            }
-            Err(error) => {
-                println!(
-                    "{}====> error {} for {} on {}",
-                    self.indent(),
-                    error,
-                    self.ast().identifier_string(name),
-                    self
-                );
-                Err(error)
-            } // result => result,
+            result => result,
         }
     }
 
@@ -502,6 +530,41 @@ impl ObjectValue for BlockRef {
         // Set the field.
         self.set_local_field(index, value, &ast)
     }
+
+    ///
+    /// `object["name"]` is the field `name`, just like `object.name`. Any other
+    /// index is looked up in the block's result, so `{ 1, 2 }[0]` is `1`.
+    ///
+    fn index(self, index: BergVal) -> EvalResult
+    where
+        Self: Sized,
+    {
+        match index {
+            BergVal::String(ref name) => {
+                let name = self.ast().identifiers.get(name);
+                match name {
+                    Some(name) => self.field(name),
+                    // If nobody ever wrote the name, there's no field by that name.
+                    None => CompilerError::IndexOutOfRange(Box::new(self), Box::new(index)).err(),
+                }
+            }
+            index => self.clone_result().index(index),
+        }
+    }
+
+    fn set_index(&mut self, index: BergVal, value: BergVal) -> Result<(), EvalException> {
+        match index {
+            BergVal::String(ref name) => {
+                let name = self.ast().identifiers.get(name);
+                match name {
+                    Some(name) => self.set_field(name, value),
+                    None => CompilerError::IndexOutOfRange(Box::new(self.clone()), Box::new(index))
+                        .err(),
+                }
+            }
+            index => default_set_index(self, index, value),
+        }
+    }
 }
 
 impl OperableValue for BlockRef {
@@ -532,6 +595,16 @@ impl OperableValue for BlockRef {
                 };
                 self.apply(input)?.ok()
             }
+            // Objects with a <=> field can be compared: a <=> b calls a.<=>(b),
+            // and a < b comes from that.
+            COMPARE if self.has_public_field(COMPARE) => {
+                self.field(COMPARE)?.infix(FOLLOWED_BY, right)
+            }
+            GREATER_THAN | LESS_THAN | GREATER_EQUAL | LESS_EQUAL
+                if self.has_public_field(COMPARE) =>
+            {
+                default_infix(self, operator, right)
+            }
             _ => self.clone_result().infix(operator, right),
         }
     }
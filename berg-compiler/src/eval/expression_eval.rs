@@ -1,14 +1,21 @@
 use crate::eval::BlockRef;
 use crate::value::implement::*;
-use berg_parser::identifiers::APPLY;
+use berg_parser::identifiers::{APPLY, INDEX, INTERPOLATE};
 use berg_parser::{
     Ast, AstIndex, ErrorTermError, ExpressionBoundary, ExpressionBoundaryError, ExpressionToken,
-    ExpressionTreeWalker, IdentifierIndex, OperatorToken, RawErrorTermError, TermToken, Token,
+    ExpressionTreeWalker, FloatParts, IdentifierIndex, LiteralIndex, OperatorToken,
+    RawErrorTermError, TermToken, Token,
 };
-use num::BigRational;
+use num::{BigInt, BigRational, Zero};
 use std::fmt;
 use std::str::FromStr;
 
+///
+/// The largest exponent allowed in a float literal like `1e100`. Beyond this,
+/// the exact rational gets too big to be practical.
+///
+const MAX_EXPONENT: u64 = 0xFFFF;
+
 #[derive(Copy, Clone)]
 pub struct ExpressionEvaluator<'a>(ExpressionTreeWalker<'a, &'a BlockRef>);
 
@@ -63,9 +70,8 @@ impl<'a> ExpressionEvaluator<'a> {
         Self(self.0.right_expression())
     }
     fn evaluate_local(self) -> Result<EvalVal, Exception> {
-        let indent = "  ".repeat(self.depth());
-        println!("{}Evaluating {} ...", indent, self);
         use ErrorTermError::*;
+        use ExpressionBoundary::{InterpolatedString, Interpolation, SquareBrackets};
         use ExpressionBoundaryError::*;
         use ExpressionToken::*;
         use OperatorToken::*;
@@ -84,6 +90,27 @@ impl<'a> ExpressionEvaluator<'a> {
                             .unwrap()
                             .ok()
                     }
+                    // 0x1F, 0o17, 0b101
+                    HexadecimalLiteral(literal) => self.evaluate_radix_integer(literal, 16),
+                    OctalLiteral(literal) => self.evaluate_radix_integer(literal, 8),
+                    BinaryLiteral(literal) => self.evaluate_radix_integer(literal, 2),
+                    // 12.34e56
+                    FloatLiteral(literal, parts) => {
+                        self.float_value(literal, parts).map(EvalVal::from)
+                    }
+                    // 12i, 1.2e3i
+                    ImaginaryLiteral(literal, parts) => self
+                        .float_value(literal, parts)
+                        .map(|value| BigComplex::new(BigRational::zero(), value).into()),
+                    // "abc"
+                    StringLiteral(literal) => self.ast().string_value(literal).into_owned().ok(),
+                    // The "abc" in "abc#{...}"
+                    StringFragment(literal) => {
+                        self.ast().string_fragment_value(literal).into_owned().ok()
+                    }
+                    // sql:
+                    //     SELECT * FROM table
+                    TextBlock(literal) => self.evaluate_text_block(literal),
                     // VariableName
                     // TODO: make it so we don't have to clone, ya?
                     FieldReference(field) => {
@@ -103,14 +130,77 @@ impl<'a> ExpressionEvaluator<'a> {
                     ErrorTerm(UnsupportedCharacters, literal) => {
                         self.throw(CompilerError::UnsupportedCharacters(literal))
                     }
+                    ErrorTerm(MissingExponent, literal) => {
+                        self.throw(CompilerError::MissingExponent(literal))
+                    }
+                    ErrorTerm(UppercaseHexadecimalPrefix, literal) => {
+                        self.throw(CompilerError::UppercaseHexadecimalPrefix(literal))
+                    }
+                    ErrorTerm(UppercaseOctalPrefix, literal) => {
+                        self.throw(CompilerError::UppercaseOctalPrefix(literal))
+                    }
+                    ErrorTerm(UppercaseBinaryPrefix, literal) => {
+                        self.throw(CompilerError::UppercaseBinaryPrefix(literal))
+                    }
+                    ErrorTerm(DigitOutOfRange, literal) => {
+                        self.throw(CompilerError::DigitOutOfRange(literal))
+                    }
+                    ErrorTerm(ImaginaryWithUppercaseI, literal) => {
+                        self.throw(CompilerError::ImaginaryWithUppercaseI(literal))
+                    }
+                    ErrorTerm(DenormalizedIdentifier, literal) => {
+                        self.throw(CompilerError::DenormalizedIdentifier(literal))
+                    }
+                    ErrorTerm(IncompleteGrapheme, literal) => {
+                        self.throw(CompilerError::IncompleteGrapheme(literal))
+                    }
+                    ErrorTerm(UnclosedString, literal) => {
+                        self.throw(CompilerError::UnclosedString(literal))
+                    }
+                    ErrorTerm(IdentifierTooLarge, literal) => {
+                        self.throw(CompilerError::IdentifierTooLarge(literal))
+                    }
+                    ErrorTerm(InconsistentIndentCharacters, literal) => {
+                        self.throw(CompilerError::InconsistentIndentCharacters(literal))
+                    }
+                    ErrorTerm(UnclosedBlockComment, literal) => {
+                        self.throw(CompilerError::UnclosedBlockComment(literal))
+                    }
+                    ErrorTerm(DoubledDigitSeparator, literal) => {
+                        self.throw(CompilerError::DoubledDigitSeparator(literal))
+                    }
+                    ErrorTerm(TrailingDigitSeparator, literal) => {
+                        self.throw(CompilerError::TrailingDigitSeparator(literal))
+                    }
+                    ErrorTerm(LeadingDigitSeparator, literal) => {
+                        self.throw(CompilerError::LeadingDigitSeparator(literal))
+                    }
+                    ErrorTerm(DigitSeparatorNextToRadixPrefix, literal) => {
+                        self.throw(CompilerError::DigitSeparatorNextToRadixPrefix(literal))
+                    }
+                    ErrorTerm(MissingRadixDigits, literal) => {
+                        self.throw(CompilerError::MissingRadixDigits(literal))
+                    }
                     RawErrorTerm(InvalidUtf8, raw_literal) => {
                         self.throw(CompilerError::InvalidUtf8(raw_literal))
                     }
+                    RawErrorTerm(InvalidUtf8InString, raw_literal) => {
+                        self.throw(CompilerError::InvalidUtf8InString(raw_literal))
+                    }
+                    RawErrorTerm(LineTooLong, raw_literal) => {
+                        self.throw(CompilerError::LineTooLong(raw_literal))
+                    }
                 },
 
                 // A<op>
                 PrefixOperator(operator) => self.evaluate_prefix(operator),
 
+                // "a#{b}c"
+                Open(None, InterpolatedString, _) => self.evaluate_interpolated_string(),
+
+                // [1, 2, 3]
+                Open(None, SquareBrackets, _) => self.evaluate_list(),
+
                 // (...), {...}
                 Open(None, boundary, delta) => {
                     if boundary.is_block() {
@@ -124,8 +214,16 @@ impl<'a> ExpressionEvaluator<'a> {
                 }
 
                 // ( and { syntax errors
+                Open(Some(OpenWithoutClose), Interpolation, _) => {
+                    self.throw(CompilerError::UnclosedInterpolation)
+                }
+                // Report any unclosed #{ inside the string before the string itself.
+                Open(Some(OpenWithoutClose), InterpolatedString, _) => self
+                    .evaluate_interpolated_string()
+                    .and_then(|_| self.throw(CompilerError::UnclosedInterpolatedString)),
                 Open(Some(OpenWithoutClose), ..) => self.throw(CompilerError::OpenWithoutClose),
                 Open(Some(CloseWithoutOpen), ..) => self.throw(CompilerError::CloseWithoutOpen),
+                Open(Some(MultipleUndent), ..) => self.throw(CompilerError::MultipleUndent),
             },
             Token::Operator(token) => match token {
                 //
@@ -134,6 +232,8 @@ impl<'a> ExpressionEvaluator<'a> {
 
                 // A <op> B
                 InfixOperator(APPLY) => self.evaluate_apply(),
+                InfixOperator(INDEX) => self.evaluate_index(),
+                InfixOperator(INTERPOLATE) => self.evaluate_interpolate(),
                 InfixOperator(operator) => self.evaluate_infix(operator),
                 // A <op>= B
                 InfixAssignment(operator) => self.evaluate_infix_assign(operator),
@@ -149,7 +249,12 @@ impl<'a> ExpressionEvaluator<'a> {
             },
         };
         let result = result.map_err(|e| e.at_location(self));
-        println!("{}Evaluated {} to {}", indent, self, result.display());
+        if let (Ok(value), Some(tracer)) = (&result, self.scope().ast().root.tracer()) {
+            tracer.trace(&TraceEvent::ExpressionEvaluated {
+                expression: ExpressionRef::from(self).traced(),
+                value: value.display(),
+            });
+        }
         result
     }
 
@@ -157,6 +262,60 @@ impl<'a> ExpressionEvaluator<'a> {
         Err(E::from(error.at_location(*self)))
     }
 
+    ///
+    /// Hands a text block to the DSL registered for it. Errors the DSL finds
+    /// point at the place in the block they came from.
+    ///
+    fn evaluate_text_block(self, literal: LiteralIndex) -> EvalResult {
+        let (name, text, text_start) = self.ast().text_block_parts(literal);
+        let start = self.ast().token_ranges[self.root_index()].start + text_start;
+        let text = DslText::new(name, text, start);
+        let root = self.scope().ast().root.clone();
+        let handler = match root.dsl(name) {
+            Some(handler) => handler,
+            None => return self.throw(CompilerError::UnknownDsl(name.into())),
+        };
+        match handler.run(&root, &text)? {
+            Ok(value) => value.ok(),
+            Err(error) => self.throw(CompilerError::InvalidDslText(
+                error.message,
+                text.source_range(error.range),
+            )),
+        }
+    }
+
+    fn evaluate_radix_integer(self, literal: LiteralIndex, radix: u32) -> EvalResult {
+        // Skip the 0x / 0o / 0b
+        let digits = &self.ast().literal_string(literal)[2..];
+        let value = BigInt::parse_bytes(digits.as_bytes(), radix).unwrap();
+        BigRational::from_integer(value).ok()
+    }
+
+    fn float_value(
+        self,
+        literal: LiteralIndex,
+        parts: FloatParts,
+    ) -> Result<BigRational, EvalException> {
+        let (mantissa, fraction, exponent) = parts.split(self.ast().literal_string(literal));
+        let exponent = match exponent {
+            "" => 0,
+            exponent => match i64::from_str(exponent) {
+                Ok(exponent) if exponent.unsigned_abs() <= MAX_EXPONENT => exponent,
+                _ => return self.throw(CompilerError::ExponentTooLarge(literal)),
+            },
+        };
+
+        // 12.34e56 = 1234e54
+        let digits = BigInt::from_str(&[mantissa, fraction].concat()).unwrap();
+        let exponent = exponent - fraction.len() as i64;
+        let scale = num::pow(BigInt::from(10), exponent.unsigned_abs() as usize);
+        if exponent < 0 {
+            BigRational::new(digits, scale).ok()
+        } else {
+            BigRational::from_integer(digits * scale).ok()
+        }
+    }
+
     fn evaluate_inner(self, boundary: ExpressionBoundary) -> EvalResult {
         let result = self.inner_expression().evaluate_local();
         if boundary.is_required() {
@@ -166,12 +325,60 @@ impl<'a> ExpressionEvaluator<'a> {
         }
     }
 
+    fn evaluate_interpolated_string(self) -> EvalResult {
+        Self::interpolated_text(self.inner_expression().evaluate_local())?.ok()
+    }
+
+    fn evaluate_interpolate(self) -> EvalResult {
+        let left = Self::interpolated_text(self.left_expression().evaluate_local())?;
+        let right = Self::interpolated_text(self.right_expression().evaluate_local())?;
+        (left + &right).ok()
+    }
+
+    ///
+    /// The text of a piece of an interpolated string. Strings are used as-is;
+    /// everything else (including block results) is converted to text the
+    /// same way it is displayed.
+    ///
+    fn interpolated_text(value: Result<EvalVal, Exception>) -> Result<String, EvalException> {
+        match value?.lazy_val()?.evaluate()? {
+            BergVal::String(string) => Ok(string),
+            value => Ok(value.to_string()),
+        }
+    }
+
     fn evaluate_apply(self) -> EvalResult {
         let left = self.left_expression().evaluate_local();
         let right = RightOperand::from(self.right_expression().inner_expression());
         left.infix(APPLY, right)
     }
 
+    ///
+    /// `[...]` is a list: always a tuple, even with just one value or none.
+    ///
+    fn evaluate_list(self) -> EvalResult {
+        use EvalVal::*;
+        match self.inner_expression().evaluate_local()? {
+            MissingExpression => empty_tuple().ok(),
+            PartialTuple(values) | TrailingComma(values) => BergVal::from(values).ok(),
+            value => BergVal::from(vec![value.lazy_val()?]).ok(),
+        }
+    }
+
+    ///
+    /// `a[i]` refers to whatever is at index `i` in `a`, so that it can be
+    /// assigned to as well as read. The index is whatever is in the brackets:
+    /// `i` for `a[i]`, and the tuple `(i,j)` for `a[i,j]`.
+    ///
+    fn evaluate_index(self) -> EvalResult {
+        let object = self.left_expression().evaluate_local()?;
+        let index = match RightOperand::from(self.right_expression()).lazy_val()? {
+            BergVal::Tuple(list) if list.len() == 1 => list.into_iter().next().unwrap(),
+            list => list,
+        };
+        AssignmentTarget::IndexReference(Box::new(object), index).ok()
+    }
+
     fn evaluate_infix(self, operator: IdentifierIndex) -> EvalResult {
         let left = self.left_expression().evaluate_local();
         let right = RightOperand::from(self.right_expression());
@@ -0,0 +1,299 @@
+use crate::eval::{BlockRef, ExpressionEvaluator};
+use crate::value::implement::*;
+use berg_parser::identifiers::{
+    COLON, COMMA, EQUAL_TO, FAT_ARROW, NEWLINE_SEQUENCE, SEMICOLON, UNDERSCORE,
+};
+use berg_parser::{
+    AstExpressionTree, AstIndex, ExpressionBoundary, ExpressionToken, IdentifierIndex,
+    OperatorToken, TermToken, Token,
+};
+
+///
+/// Runs the arms of `match subject { pattern => result ... }`, returning the
+/// result of the first arm whose pattern matches, or `None` if none do.
+///
+/// `scope` is a fresh run of the arms block: fields bound by the patterns
+/// (`:x`) are set there by name, so the result can use them.
+///
+/// Patterns are:
+///
+/// - `_`, which matches anything.
+/// - `:name`, which matches anything and binds it to `name`.
+/// - `(a, b)` or `[a, b]`, which match a tuple of the same length whose
+///   values match `a` and `b`.
+/// - `{ :name; size: pattern }`, which match an object with a `name` field
+///   (binding it to `name`) and a `size` field matching `pattern`.
+/// - A range like `1..10`, which matches anything in the range.
+/// - Anything else is a value, which matches anything `==` to it.
+///
+/// Arms after one that matches everything, or with the same pattern as an
+/// earlier arm, can never match; they are reported as warnings.
+///
+pub fn run_match(
+    scope: &BlockRef,
+    ast: &AstRef,
+    arms_block: AstIndex,
+    subject: &BergVal,
+) -> Result<Option<BergVal>, EvalException> {
+    let mut arms = vec![];
+    flatten(
+        AstExpressionTree::new(ast, arms_block).inner_expression(),
+        &[NEWLINE_SEQUENCE, SEMICOLON],
+        &mut arms,
+    );
+    check_arms(ast, &arms)?;
+
+    for arm in arms {
+        if matches(scope, ast, arm.left_expression(), subject)? {
+            let result = ExpressionEvaluator::new(scope, ast, arm.right_expression().root_index());
+            return result.lazy_val().map(Some);
+        }
+    }
+    Ok(None)
+}
+
+///
+/// Makes sure every arm is `pattern => result`, and warns about arms that can
+/// never match.
+///
+fn check_arms(ast: &AstRef, arms: &[AstExpressionTree]) -> Result<(), EvalException> {
+    let mut catch_all: Option<AstExpressionTree> = None;
+    let mut patterns: Vec<String> = vec![];
+    for arm in arms {
+        if arm.token() != Token::Operator(OperatorToken::InfixOperator(FAT_ARROW)) {
+            let location = ExpressionRef::new(ast.clone(), arm.root_index());
+            return Err(CompilerError::MatchArmWithoutArrow
+                .at_location(location)
+                .into());
+        }
+
+        let pattern = arm.left_expression().skip_implicit_groups();
+        let source = pattern.to_string();
+        let location = ExpressionRef::new(ast.clone(), arm.root_index());
+        if let Some(catch_all) = catch_all {
+            ast.root.warn(
+                &location,
+                format_args!(
+                    "match arm '{}' can never match, because '{}' before it matches everything.",
+                    arm, catch_all
+                ),
+            );
+        } else if patterns.contains(&source) {
+            ast.root.warn(
+                &location,
+                format_args!(
+                    "match arm '{}' can never match, because an earlier arm has the same pattern.",
+                    arm
+                ),
+            );
+        }
+
+        if catch_all.is_none() && is_irrefutable(ast, pattern) {
+            catch_all = Some(pattern);
+        }
+        patterns.push(source);
+    }
+    Ok(())
+}
+
+///
+/// Whether the pattern matches everything (`_` or `:name`).
+///
+fn is_irrefutable(ast: &AstRef, pattern: AstExpressionTree) -> bool {
+    use ExpressionToken::*;
+    match pattern.token() {
+        Token::Expression(Term(TermToken::FieldReference(field))) => {
+            ast.fields[field].name == UNDERSCORE
+        }
+        Token::Expression(PrefixOperator(COLON)) => {
+            field_name(ast, pattern.right_expression()).is_some()
+        }
+        _ => false,
+    }
+}
+
+fn matches(
+    scope: &BlockRef,
+    ast: &AstRef,
+    pattern: AstExpressionTree,
+    subject: &BergVal,
+) -> Result<bool, EvalException> {
+    use ExpressionBoundary::*;
+    use ExpressionToken::*;
+    let pattern = pattern.skip_implicit_groups();
+    match pattern.token() {
+        // _
+        Token::Expression(Term(TermToken::FieldReference(field)))
+            if ast.fields[field].name == UNDERSCORE =>
+        {
+            Ok(true)
+        }
+        // :name
+        Token::Expression(PrefixOperator(COLON)) => {
+            match field_name(ast, pattern.right_expression()) {
+                Some(name) => {
+                    scope.set_local_field_named(name, subject.clone())?;
+                    Ok(true)
+                }
+                None => matches_value(scope, ast, pattern, subject),
+            }
+        }
+        // (a, b), [a, b]
+        Token::Expression(Open(None, boundary @ Parentheses, _))
+        | Token::Expression(Open(None, boundary @ SquareBrackets, _)) => {
+            matches_tuple(scope, ast, pattern, boundary, subject)
+        }
+        // { :a; b: pattern }
+        Token::Expression(Open(None, CurlyBraces, _)) => {
+            matches_object(scope, ast, pattern, subject)
+        }
+        // The pattern in `b: pattern` is in an automatic block.
+        Token::Expression(Open(None, AutoBlock, _)) => {
+            matches(scope, ast, pattern.inner_expression(), subject)
+        }
+        _ => matches_value(scope, ast, pattern, subject),
+    }
+}
+
+fn matches_value(
+    scope: &BlockRef,
+    ast: &AstRef,
+    pattern: AstExpressionTree,
+    subject: &BergVal,
+) -> Result<bool, EvalException> {
+    match ExpressionEvaluator::new(scope, ast, pattern.root_index()).lazy_val()? {
+        BergVal::Range(range) => Ok(range.contains(subject)),
+        value => subject
+            .clone()
+            .infix(EQUAL_TO, value.into())
+            .into_native::<bool>(),
+    }
+}
+
+fn matches_tuple(
+    scope: &BlockRef,
+    ast: &AstRef,
+    pattern: AstExpressionTree,
+    boundary: ExpressionBoundary,
+    subject: &BergVal,
+) -> Result<bool, EvalException> {
+    let inner = pattern.inner_expression().skip_implicit_groups();
+    let elements = match inner.token() {
+        Token::Operator(OperatorToken::InfixOperator(COMMA)) => {
+            let mut elements = vec![];
+            flatten(inner, &[COMMA], &mut elements);
+            elements
+        }
+        Token::Expression(ExpressionToken::Term(TermToken::MissingExpression)) => vec![],
+        // (pattern) is just the pattern; [pattern] is a list with one value.
+        _ if boundary == ExpressionBoundary::Parentheses => {
+            return matches(scope, ast, inner, subject);
+        }
+        _ => vec![inner],
+    };
+
+    let values = match subject.clone().evaluate()? {
+        BergVal::Tuple(values) if values.len() == elements.len() => values,
+        _ => return Ok(false),
+    };
+    for (element, value) in elements.into_iter().zip(values) {
+        if !matches(scope, ast, element, &value)? {
+            return Ok(false);
+        }
+    }
+    Ok(true)
+}
+
+fn matches_object(
+    scope: &BlockRef,
+    ast: &AstRef,
+    pattern: AstExpressionTree,
+    subject: &BergVal,
+) -> Result<bool, EvalException> {
+    use ExpressionToken::*;
+    let mut entries = vec![];
+    flatten(
+        pattern.inner_expression(),
+        &[NEWLINE_SEQUENCE, SEMICOLON, COMMA],
+        &mut entries,
+    );
+    for entry in entries {
+        match entry.token() {
+            // :name
+            Token::Expression(PrefixOperator(COLON)) => {
+                let name = field_name(ast, entry.right_expression())
+                    .ok_or_else(|| invalid_field_pattern(ast, entry))?;
+                match field_value(subject, name)? {
+                    Some(value) => scope.set_local_field_named(name, value)?,
+                    None => return Ok(false),
+                }
+            }
+            // name: pattern
+            Token::Operator(OperatorToken::InfixOperator(COLON)) => {
+                let name = field_name(ast, entry.left_expression())
+                    .ok_or_else(|| invalid_field_pattern(ast, entry))?;
+                match field_value(subject, name)? {
+                    Some(value) if matches(scope, ast, entry.right_expression(), &value)? => {}
+                    _ => return Ok(false),
+                }
+            }
+            _ => return Err(invalid_field_pattern(ast, entry)),
+        }
+    }
+    Ok(true)
+}
+
+fn field_name(ast: &AstRef, expression: AstExpressionTree) -> Option<IdentifierIndex> {
+    match expression.skip_implicit_groups().token() {
+        Token::Expression(ExpressionToken::Term(TermToken::FieldReference(field))) => {
+            Some(ast.fields[field].name)
+        }
+        Token::Expression(ExpressionToken::Term(TermToken::RawIdentifier(name))) => Some(name),
+        _ => None,
+    }
+}
+
+///
+/// The value of `subject.name`, or `None` if the subject has no such public
+/// field.
+///
+fn field_value(subject: &BergVal, name: IdentifierIndex) -> Result<Option<BergVal>, EvalException> {
+    match subject.clone().field(name) {
+        Ok(value) => value.lazy_val().map(Some),
+        Err(error) => match error.code() {
+            Some(CompilerErrorCode::NoSuchPublicField) | Some(CompilerErrorCode::PrivateField) => {
+                Ok(None)
+            }
+            _ => Err(error),
+        },
+    }
+}
+
+fn invalid_field_pattern(ast: &AstRef, entry: AstExpressionTree) -> EvalException {
+    let location = ExpressionRef::new(ast.clone(), entry.root_index());
+    CompilerError::InvalidFieldPattern
+        .at_location(location)
+        .into()
+}
+
+///
+/// Splits `a; b; c` (or whichever separators are given) into its parts,
+/// ignoring empty ones like the one after a trailing `;`.
+///
+fn flatten<'a>(
+    expression: AstExpressionTree<'a>,
+    separators: &[IdentifierIndex],
+    into: &mut Vec<AstExpressionTree<'a>>,
+) {
+    let expression = expression.skip_implicit_groups();
+    match expression.token() {
+        Token::Operator(OperatorToken::InfixOperator(operator))
+            if separators.contains(&operator) =>
+        {
+            flatten(expression.left_expression(), separators, into);
+            flatten(expression.right_expression(), separators, into);
+        }
+        Token::Expression(ExpressionToken::Term(TermToken::MissingExpression)) => {}
+        _ => into.push(expression),
+    }
+}
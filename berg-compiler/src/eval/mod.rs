@@ -1,11 +1,18 @@
 mod block;
 mod expression_eval;
+mod match_arms;
 
 pub use self::block::BlockRef;
 pub use self::expression_eval::ExpressionEvaluator;
 
+use crate::value::implement::ExpressionRef;
 use crate::value::*;
 
 pub fn evaluate_ast(ast: AstRef) -> BergResult {
+    // A source that couldn't be loaded has no tokens to evaluate.
+    if let Some(error) = &ast.error {
+        let error = CompilerError::SourceLoadError(error.clone());
+        return Err(error.at_location(ExpressionRef::new(ast.clone(), 0.into())));
+    }
     BlockRef::from_ast(ast.clone())?.evaluate()
 }
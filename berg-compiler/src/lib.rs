@@ -1,14 +1,18 @@
 // Turn on all warnings
 #![warn(clippy::all)]
 
-use value::RootRef;
+use value::{AstRef, RootRef, SourceSpec};
 
 // Explicitly expose just the interfaces we want to expose
 pub use crate::value::{
-    BergResult, BergVal, BergValue, CompilerErrorCode, EvalException, Exception,
+    BergResult, BergVal, BergValue, CompilerErrorCode, DslError, DslHandler, DslResult, DslText,
+    ErrorLocation, EvalException, Exception, JsonTracer, NoTracer, TextTracer, TraceEvent,
+    TracedExpression, Tracer,
 };
 
-use crate::eval::evaluate_ast;
+pub use berg_parser::ColumnUnit;
+
+use berg_parser::Ast;
 use std::borrow::Cow;
 use std::path::Path;
 #[macro_use]
@@ -20,14 +24,94 @@ pub(crate) mod value;
 pub mod test;
 
 pub fn evaluate_file(path: impl Into<Cow<'static, Path>>) -> impl BergValue {
-    let ast = RootRef::from_env().parse_file(path);
-    evaluate_ast(ast)
+    evaluate_file_with_tracer(path, NoTracer)
 }
 
 pub fn evaluate_bytes(
     name: impl Into<Cow<'static, str>>,
     buffer: impl Into<Cow<'static, [u8]>>,
 ) -> impl BergValue {
-    let ast = RootRef::from_env().parse_bytes(name, buffer);
-    evaluate_ast(ast)
+    evaluate_bytes_with_tracer(name, buffer, NoTracer)
+}
+
+///
+/// Evaluates a file, sending evaluation events to the given tracer.
+///
+pub fn evaluate_file_with_tracer(
+    path: impl Into<Cow<'static, Path>>,
+    tracer: impl Tracer + 'static,
+) -> BergResult {
+    evaluate_file_with_columns(path, tracer, ColumnUnit::Bytes)
+}
+
+///
+/// Evaluates a buffer, sending evaluation events to the given tracer.
+///
+pub fn evaluate_bytes_with_tracer(
+    name: impl Into<Cow<'static, str>>,
+    buffer: impl Into<Cow<'static, [u8]>>,
+    tracer: impl Tracer + 'static,
+) -> BergResult {
+    evaluate_bytes_with_columns(name, buffer, tracer, ColumnUnit::Bytes)
+}
+
+///
+/// Evaluates a file like [`evaluate_file_with_tracer()`], counting columns in
+/// `columns` in the locations it reports.
+///
+pub fn evaluate_file_with_columns(
+    path: impl Into<Cow<'static, Path>>,
+    tracer: impl Tracer + 'static,
+    columns: ColumnUnit,
+) -> BergResult {
+    evaluate_file_with_args(path, vec![], tracer, columns)
+}
+
+///
+/// Evaluates a file like [`evaluate_file_with_columns()`], with `args` as the
+/// program's arguments (the tuple `args` in the program).
+///
+pub fn evaluate_file_with_args(
+    path: impl Into<Cow<'static, Path>>,
+    args: Vec<String>,
+    tracer: impl Tracer + 'static,
+    columns: ColumnUnit,
+) -> BergResult {
+    let ast = RootRef::from_env_with_args(Box::new(tracer), columns, args).parse_file(path);
+    eval::evaluate_ast(ast)
+}
+
+///
+/// Evaluates a buffer like [`evaluate_bytes_with_tracer()`], counting columns
+/// in `columns` in the locations it reports.
+///
+pub fn evaluate_bytes_with_columns(
+    name: impl Into<Cow<'static, str>>,
+    buffer: impl Into<Cow<'static, [u8]>>,
+    tracer: impl Tracer + 'static,
+    columns: ColumnUnit,
+) -> BergResult {
+    let ast = RootRef::from_env_with_columns(Box::new(tracer), columns).parse_bytes(name, buffer);
+    eval::evaluate_ast(ast)
+}
+
+///
+/// Evaluates an AST that was made some other way than parsing a file or
+/// buffer, like with [`berg_parser::AstBuilder`].
+///
+pub fn evaluate_ast(name: impl Into<Cow<'static, str>>, ast: Ast) -> BergResult {
+    evaluate_ast_with_tracer(name, ast, NoTracer)
+}
+
+///
+/// Evaluates an AST like [`evaluate_ast()`], sending evaluation events to the
+/// given tracer.
+///
+pub fn evaluate_ast_with_tracer(
+    name: impl Into<Cow<'static, str>>,
+    ast: Ast,
+    tracer: impl Tracer + 'static,
+) -> BergResult {
+    let root = RootRef::from_env_with_tracer(Box::new(tracer));
+    eval::evaluate_ast(AstRef::new(root, SourceSpec::Memory(name.into()), ast))
 }
@@ -1,8 +1,9 @@
 use crate::eval::evaluate_ast;
 use crate::value::*;
 use berg_parser::identifiers::*;
-use berg_parser::{ByteIndex, ByteRange, LineColumnRange};
+use berg_parser::{Ast, ByteIndex, ByteRange, LineColumnRange, OperatorTable};
 use berg_util::{BoundedRange, IntoRange};
+use std::borrow::Cow;
 use std::fmt;
 use std::io;
 use std::ops::{Range, RangeFrom, RangeInclusive, RangeTo};
@@ -23,7 +24,29 @@ pub use CompilerErrorCode::*;
 /// ```
 ///
 pub fn expect<T: AsRef<[u8]> + ?Sized>(source: &'static T) -> ExpectBerg {
-    ExpectBerg(source.as_ref())
+    ExpectBerg(source.as_ref(), OperatorTable::default(), vec![])
+}
+
+///
+/// Run Berg source and fully evaluate the result (iterating blocks and
+/// tuples), the same way [`ExpectBerg::to_yield()`] does.
+///
+/// Used by tests that generate their source at runtime, like the YAML spec
+/// tests.
+///
+pub fn evaluate_source(source: impl Into<Cow<'static, [u8]>>) -> BergResult {
+    let ast = test_root(&OperatorTable::default(), &[]).parse_bytes("test.rs", source);
+    evaluate_ast(ast).and_then(ExpectBerg::evaluate_all)
+}
+
+///
+/// Fully evaluate an AST that wasn't parsed from source (like one made with
+/// [`berg_parser::AstBuilder`]), the same way [`ExpectBerg::to_yield()`] does.
+///
+pub fn evaluate_built_ast(ast: Ast) -> BergResult {
+    let root = test_root(&ast.operators, &[]);
+    let ast = AstRef::new(root, SourceSpec::Memory("test.rs".into()), ast);
+    evaluate_ast(ast).and_then(ExpectBerg::evaluate_all)
 }
 
 ///
@@ -40,7 +63,11 @@ pub fn expect<T: AsRef<[u8]> + ?Sized>(source: &'static T) -> ExpectBerg {
 /// ```
 ///
 #[derive(Debug)]
-pub struct ExpectBerg(pub &'static [u8]);
+pub struct ExpectBerg(
+    pub &'static [u8],
+    pub OperatorTable,
+    pub Vec<(&'static str, DslHandler)>,
+);
 
 ///
 /// An expected value.
@@ -160,6 +187,45 @@ pub struct ExpectedErrorRangeBefore<T: ExpectedErrorRange>(T);
 pub struct ExpectLine(usize);
 
 impl ExpectBerg {
+    ///
+    /// Declares operators for the source, so it groups them accordingly.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use berg_compiler::test::*;
+    /// use berg_parser::{Associativity, OperatorTable, Precedence, RelativePrecedence};
+    /// let mut operators = OperatorTable::default();
+    /// operators.declare(
+    ///     "|>",
+    ///     RelativePrecedence::LooserThan(Precedence::Or),
+    ///     Associativity::Left,
+    /// );
+    /// expect("1 + 2 |> 3").with_operators(operators).to_error(UnsupportedOperator, 6..=7);
+    /// ```
+    ///
+    pub fn with_operators(self, operators: OperatorTable) -> Self {
+        ExpectBerg(self.0, operators, self.2)
+    }
+
+    ///
+    /// Registers a DSL for the source, so `name:` followed by an indented
+    /// block is handed to `handler`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use berg_compiler::test::*;
+    /// use berg_compiler::DslHandler;
+    /// let shout = DslHandler::native(|text| Ok(text.text().to_uppercase().into()));
+    /// expect("shout:\n    hello").with_dsl("shout", shout).to_yield("HELLO");
+    /// ```
+    ///
+    pub fn with_dsl(mut self, name: &'static str, handler: DslHandler) -> Self {
+        self.2.push((name, handler));
+        self
+    }
+
     ///
     /// Test that the given value is returned when the Berg source is compiled and run.
     ///
@@ -242,7 +308,7 @@ impl ExpectBerg {
         println!("Source:");
         println!("{}", String::from_utf8_lossy(self.0));
         println!();
-        let ast = test_root().parse_bytes("test.rs", self.0);
+        let ast = test_root(&self.1, &self.2).parse_bytes("test.rs", self.0);
         let expected_range = ast
             .char_data
             .range(&expected_range.into_error_range(self.0.as_ref()));
@@ -278,8 +344,75 @@ impl ExpectBerg {
         )
     }
 
+    ///
+    /// Test that an error with the given `code` and location is produced in
+    /// the source of the Berg DSL handler registered as `name`, rather than
+    /// in the source being tested. `expected_range` is in the handler's
+    /// source.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use berg_compiler::test::*;
+    /// use berg_compiler::DslHandler;
+    /// expect("q:\n    hello")
+    ///     .with_dsl("q", DslHandler::berg("1 / 0"))
+    ///     .to_error_in_dsl("q", DivideByZero, 4);
+    /// ```
+    ///
+    #[allow(clippy::wrong_self_convention)]
+    pub fn to_error_in_dsl(
+        self,
+        name: &str,
+        expected_value: impl ExpectedValue,
+        expected_range: impl ExpectedErrorRange,
+    ) {
+        println!("Source:");
+        println!("{}", String::from_utf8_lossy(self.0));
+        println!();
+        let actual = match evaluate_ast(self.parse()).and_then(Self::evaluate_all) {
+            Ok(value) => panic!(
+                "No error produced by {}: expected {} in the {} DSL, got value {}",
+                self, expected_value, name, value
+            ),
+            Err(error) => error,
+        };
+        let ast = match actual.location() {
+            ErrorLocation::SourceExpression(ast, _) | ErrorLocation::SourceRange(ast, _) => ast,
+            location => panic!("Error from {} has no source: {:?}", self, location),
+        };
+        assert_eq!(
+            ast.source.name(),
+            format!("{} DSL", name),
+            "Error from {} is in the wrong source: {}",
+            self,
+            actual.value
+        );
+        let expected_range = ast
+            .char_data
+            .range(&expected_range.into_error_range(&ast.to_bytes()));
+        let actual_range = actual.location().range();
+        assert!(
+            expected_value
+                .clone()
+                .matches(actual.value.clone())
+                .unwrap_or_else(|e| panic!("Unexpected error: {}", e)),
+            "Wrong error returned from {}! expected: {} at {}, actual: {} at {}.",
+            self,
+            expected_value,
+            expected_range,
+            actual.value,
+            actual_range
+        );
+        assert_eq!(
+            expected_range, actual_range,
+            "Wrong error range in the {} DSL from {}!",
+            name, self
+        )
+    }
+
     fn parse(&self) -> AstRef {
-        let ast = test_root().parse_bytes("test.rs", self.0);
+        let ast = test_root(&self.1, &self.2).parse_bytes("test.rs", self.0);
         assert_eq!(
             self.0,
             ast.to_bytes().as_slice(),
@@ -343,14 +476,19 @@ impl ExpectBerg {
     }
 }
 
-fn test_root() -> RootRef {
+fn test_root(operators: &OperatorTable, dsls: &[(&'static str, DslHandler)]) -> RootRef {
     // Steal "source"
     let out: Vec<u8> = vec![];
     let err: Vec<u8> = vec![];
     let root = SourceRoot::new_error(io::Error::other(
         "SYSTEM ERROR: no relative path--this error should be impossible to trigger",
-    ));
-    RootRef::new(root, Box::new(out), Box::new(err))
+    ))
+    .with_operators(operators.clone());
+    let root = RootRef::new(root, Box::new(out), Box::new(err));
+    for (name, handler) in dsls {
+        root.register_dsl(*name, handler.clone());
+    }
+    root
 }
 
 impl fmt::Display for ExpectBerg {
@@ -1,4 +1,5 @@
 use crate::eval::BlockRef;
+use crate::value::complex::ComplexDisplay;
 use crate::value::implement::*;
 use berg_parser::ExpressionPosition;
 use num::BigRational;
@@ -20,6 +21,10 @@ pub enum BergVal {
     ///
     BigRational(BigRational),
     ///
+    /// Complex number value (`1 + 2i`), with rational parts.
+    ///
+    Complex(Box<BigComplex>),
+    ///
     /// Block value.
     ///
     /// Operations on a block generally operate on the block's result value.
@@ -33,8 +38,12 @@ pub enum BergVal {
     CaughtException(CaughtException),
     /// compiler error
     CompilerError(CompilerError),
+    /// "abc"
+    String(String),
     /// [ 1, 2, 3 ]
     Tuple(Tuple),
+    /// 1..10, "a"..."z"
+    Range(Box<Range>),
 }
 
 pub type BergResult = Result<BergVal, Exception>;
@@ -46,8 +55,11 @@ impl BergVal {
     pub fn is_single_primitive(&self) -> bool {
         use BergVal::*;
         match self {
-            Boolean(_) | BigRational(_) | CaughtException(_) | CompilerError(_) => true,
-            BlockRef(_) | Tuple(_) => false,
+            Boolean(_) | BigRational(_) | Complex(_) | CaughtException(_) | CompilerError(_)
+            | String(_) => {
+                true
+            }
+            BlockRef(_) | Tuple(_) | Range(_) => false,
         }
     }
 }
@@ -77,10 +89,13 @@ impl Value for BergVal {
         match self {
             Boolean(value) => value.lazy_val(),
             BigRational(value) => value.lazy_val(),
+            Complex(value) => (*value).lazy_val(),
             BlockRef(value) => value.lazy_val(),
             CaughtException(value) => value.lazy_val(),
             CompilerError(value) => value.lazy_val(),
             Tuple(value) => value.lazy_val(),
+            Range(value) => (*value).lazy_val(),
+            String(value) => value.lazy_val(),
         }
     }
     fn eval_val(self) -> EvalResult
@@ -91,10 +106,13 @@ impl Value for BergVal {
         match self {
             Boolean(value) => value.eval_val(),
             BigRational(value) => value.eval_val(),
+            Complex(value) => (*value).eval_val(),
             BlockRef(value) => value.eval_val(),
             CaughtException(value) => value.eval_val(),
             CompilerError(value) => value.eval_val(),
             Tuple(value) => value.eval_val(),
+            Range(value) => (*value).eval_val(),
+            String(value) => value.eval_val(),
         }
     }
 
@@ -103,10 +121,13 @@ impl Value for BergVal {
         match self {
             Boolean(value) => value.into_native(),
             BigRational(value) => value.into_native(),
+            Complex(value) => (*value).into_native(),
             BlockRef(value) => value.into_native(),
             CaughtException(value) => value.into_native(),
             CompilerError(value) => value.into_native(),
             Tuple(value) => value.into_native(),
+            Range(value) => (*value).into_native(),
+            String(value) => value.into_native(),
         }
     }
 
@@ -115,10 +136,13 @@ impl Value for BergVal {
         match self {
             Boolean(value) => value.try_into_native(),
             BigRational(value) => value.try_into_native(),
+            Complex(value) => (*value).try_into_native(),
             BlockRef(value) => value.try_into_native(),
             CaughtException(value) => value.try_into_native(),
             CompilerError(value) => value.try_into_native(),
             Tuple(value) => value.try_into_native(),
+            Range(value) => (*value).try_into_native(),
+            String(value) => value.try_into_native(),
         }
     }
 
@@ -133,10 +157,13 @@ impl IteratorValue for BergVal {
         match self {
             Boolean(value) => value.next_val(),
             BigRational(value) => value.next_val(),
+            Complex(value) => (*value).next_val(),
             BlockRef(value) => value.next_val(),
             CaughtException(value) => value.next_val(),
             CompilerError(value) => value.next_val(),
             Tuple(value) => value.next_val(),
+            Range(value) => (*value).next_val(),
+            String(value) => value.next_val(),
         }
     }
 }
@@ -150,10 +177,13 @@ impl ObjectValue for BergVal {
         match self {
             Boolean(value) => value.field(name),
             BigRational(value) => value.field(name),
+            Complex(value) => (*value).field(name),
             BlockRef(value) => value.field(name),
             CaughtException(value) => value.field(name),
             CompilerError(value) => value.field(name),
             Tuple(value) => value.field(name),
+            Range(value) => (*value).field(name),
+            String(value) => value.field(name),
         }
     }
 
@@ -166,10 +196,50 @@ impl ObjectValue for BergVal {
         match self {
             Boolean(value) => value.set_field(name, field_value),
             BigRational(value) => value.set_field(name, field_value),
+            Complex(value) => value.set_field(name, field_value),
             BlockRef(value) => value.set_field(name, field_value),
             CaughtException(value) => value.set_field(name, field_value),
             CompilerError(value) => value.set_field(name, field_value),
             Tuple(value) => value.set_field(name, field_value),
+            Range(value) => value.set_field(name, field_value),
+            String(value) => value.set_field(name, field_value),
+        }
+    }
+
+    fn index(self, index: BergVal) -> EvalResult
+    where
+        Self: Sized,
+    {
+        use BergVal::*;
+        match self {
+            Boolean(value) => value.index(index),
+            BigRational(value) => value.index(index),
+            Complex(value) => (*value).index(index),
+            BlockRef(value) => value.index(index),
+            CaughtException(value) => value.index(index),
+            CompilerError(value) => value.index(index),
+            Tuple(value) => value.index(index),
+            Range(value) => (*value).index(index),
+            String(value) => value.index(index),
+        }
+    }
+
+    fn set_index(
+        &mut self,
+        index: BergVal,
+        element_value: BergVal,
+    ) -> Result<(), EvalException> {
+        use BergVal::*;
+        match self {
+            Boolean(value) => value.set_index(index, element_value),
+            BigRational(value) => value.set_index(index, element_value),
+            Complex(value) => value.set_index(index, element_value),
+            BlockRef(value) => value.set_index(index, element_value),
+            CaughtException(value) => value.set_index(index, element_value),
+            CompilerError(value) => value.set_index(index, element_value),
+            Tuple(value) => value.set_index(index, element_value),
+            Range(value) => value.set_index(index, element_value),
+            String(value) => value.set_index(index, element_value),
         }
     }
 }
@@ -187,10 +257,13 @@ impl OperableValue for BergVal {
         match self {
             Boolean(value) => value.infix(operator, right),
             BigRational(value) => value.infix(operator, right),
+            Complex(value) => (*value).infix(operator, right),
             BlockRef(value) => value.infix(operator, right),
             CaughtException(value) => value.infix(operator, right),
             CompilerError(value) => value.infix(operator, right),
             Tuple(value) => value.infix(operator, right),
+            Range(value) => (*value).infix(operator, right),
+            String(value) => value.infix(operator, right),
         }
     }
 
@@ -206,10 +279,13 @@ impl OperableValue for BergVal {
         match self {
             Boolean(value) => value.infix_assign(operator, right),
             BigRational(value) => value.infix_assign(operator, right),
+            Complex(value) => (*value).infix_assign(operator, right),
             BlockRef(value) => value.infix_assign(operator, right),
             CaughtException(value) => value.infix_assign(operator, right),
             CompilerError(value) => value.infix_assign(operator, right),
             Tuple(value) => value.infix_assign(operator, right),
+            Range(value) => (*value).infix_assign(operator, right),
+            String(value) => value.infix_assign(operator, right),
         }
     }
 
@@ -221,10 +297,13 @@ impl OperableValue for BergVal {
         match self {
             Boolean(value) => value.postfix(operator),
             BigRational(value) => value.postfix(operator),
+            Complex(value) => (*value).postfix(operator),
             BlockRef(value) => value.postfix(operator),
             CaughtException(value) => value.postfix(operator),
             CompilerError(value) => value.postfix(operator),
             Tuple(value) => value.postfix(operator),
+            Range(value) => (*value).postfix(operator),
+            String(value) => value.postfix(operator),
         }
     }
 
@@ -236,10 +315,13 @@ impl OperableValue for BergVal {
         match self {
             Boolean(value) => value.prefix(operator),
             BigRational(value) => value.prefix(operator),
+            Complex(value) => (*value).prefix(operator),
             BlockRef(value) => value.prefix(operator),
             CaughtException(value) => value.prefix(operator),
             CompilerError(value) => value.prefix(operator),
             Tuple(value) => value.prefix(operator),
+            Range(value) => (*value).prefix(operator),
+            String(value) => value.prefix(operator),
         }
     }
 
@@ -251,10 +333,13 @@ impl OperableValue for BergVal {
         match self {
             Boolean(value) => value.subexpression_result(boundary),
             BigRational(value) => value.subexpression_result(boundary),
+            Complex(value) => (*value).subexpression_result(boundary),
             BlockRef(value) => value.subexpression_result(boundary),
             CaughtException(value) => value.subexpression_result(boundary),
             CompilerError(value) => value.subexpression_result(boundary),
             Tuple(value) => value.subexpression_result(boundary),
+            Range(value) => (*value).subexpression_result(boundary),
+            String(value) => value.subexpression_result(boundary),
         }
     }
 }
@@ -266,10 +351,13 @@ impl fmt::Debug for BergVal {
         match self {
             Boolean(value) => write!(f, "{}", value)?,
             BigRational(value) => write!(f, "{}", value)?,
+            Complex(value) => write!(f, "{}", ComplexDisplay(value))?,
             BlockRef(value) => write!(f, "{}", value)?,
             CaughtException(value) => write!(f, "{}", value)?,
             CompilerError(value) => write!(f, "{}", value)?,
             Tuple(value) => write!(f, "{}", value)?,
+            Range(value) => write!(f, "{}", value)?,
+            String(value) => write!(f, "{:?}", value)?,
         }
         write!(f, ")")
     }
@@ -281,10 +369,13 @@ impl fmt::Display for BergVal {
         match self {
             Boolean(value) => write!(f, "{}", value),
             BigRational(value) => write!(f, "{}", value),
+            Complex(value) => write!(f, "{}", ComplexDisplay(value)),
             BlockRef(value) => write!(f, "{}", value),
             CaughtException(value) => write!(f, "{}", value),
             CompilerError(value) => write!(f, "{}", value),
             Tuple(value) => write!(f, "{}", value),
+            Range(value) => write!(f, "{}", value),
+            String(value) => write!(f, "{}", value),
         }
     }
 }
@@ -300,8 +391,11 @@ impl EvaluatableValue for BergVal {
         match self {
             Boolean(value) => value.evaluate(),
             BigRational(value) => value.evaluate(),
+            Complex(value) => (*value).evaluate(),
             BlockRef(value) => value.evaluate(),
             Tuple(value) => value.evaluate(),
+            Range(value) => (*value).evaluate(),
+            String(value) => value.evaluate(),
             CaughtException(value) => value.evaluate(),
             CompilerError(value) => value.evaluate(),
         }
@@ -405,6 +499,26 @@ impl<V: ObjectValue + Clone, E: ObjectValue + Clone> ObjectValue
             Err(v) => v.set_field(name, value),
         }
     }
+
+    fn index(self, index: BergVal) -> EvalResult
+    where
+        Self: Sized,
+    {
+        match self {
+            Ok(v) => v.index(index),
+            Err(v) => v.index(index),
+        }
+    }
+
+    fn set_index(&mut self, index: BergVal, value: BergVal) -> Result<(), EvalException>
+    where
+        Self: Clone,
+    {
+        match self {
+            Ok(v) => v.set_index(index, value),
+            Err(v) => v.set_index(index, value),
+        }
+    }
 }
 
 impl<V: OperableValue, E: OperableValue> OperableValue for Result<V, E> {
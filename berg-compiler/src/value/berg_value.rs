@@ -115,6 +115,18 @@ pub trait ObjectValue: Value {
     ) -> Result<(), EvalException>
     where
         Self: Clone;
+    ///
+    /// Get the value at `index`, as in `a[index]`.
+    ///
+    fn index(self, index: BergVal) -> EvalResult
+    where
+        Self: Sized;
+    ///
+    /// Set the value at `index`, as in `a[index] = value`.
+    ///
+    fn set_index(&mut self, index: BergVal, value: BergVal) -> Result<(), EvalException>
+    where
+        Self: Clone;
 }
 
 pub trait OperableValue: Value {
@@ -330,9 +342,10 @@ pub mod implement {
         right: RightOperand<impl EvaluatableValue>,
     ) -> EvalResult {
         use berg_parser::identifiers::{
-            COLON, COMMA, DOT, EQUAL_TO, EXCLAMATION_POINT, NEWLINE_SEQUENCE, NOT_EQUAL_TO,
-            SEMICOLON,
+            COLON, COMMA, COMPARE, DOT, EQUAL_TO, EXCLAMATION_POINT, GREATER_EQUAL, GREATER_THAN,
+            IN, LESS_EQUAL, LESS_THAN, NEWLINE_SEQUENCE, NOT_EQUAL_TO, SEMICOLON,
         };
+        use std::cmp::Ordering;
         match operator {
             COMMA => {
                 let left = left.lazy_val()?;
@@ -364,7 +377,6 @@ pub mod implement {
                         (None, None) => return true.ok(),
                         (Some(_), None) | (None, Some(_)) => return false.ok(),
                         (Some(left), Some(right)) => {
-                            println!("EQUAL {} == {}", left, right);
                             if left.infix(EQUAL_TO, right.into()).into_native::<bool>()? {
                                 left_next = left_next.tail.next_val()?;
                                 right_next = right_next.tail.next_val()?;
@@ -376,6 +388,51 @@ pub mod implement {
                 }
             }
             NOT_EQUAL_TO => left.infix(EQUAL_TO, right)?.prefix(EXCLAMATION_POINT),
+            // Anything with an ordering implements <=>, and the rest come from that.
+            GREATER_THAN | LESS_THAN | GREATER_EQUAL | LESS_EQUAL => {
+                let ordering = match left.infix(COMPARE, right) {
+                    // true > false should complain about >, not <=>.
+                    Err(EvalException::Thrown(
+                        BergVal::CompilerError(CompilerError::UnsupportedOperator(
+                            value,
+                            fixity,
+                            COMPARE,
+                        )),
+                        position,
+                    )) => {
+                        return Err(EvalException::Thrown(
+                            CompilerError::UnsupportedOperator(value, fixity, operator).into(),
+                            position,
+                        ))
+                    }
+                    result => result.into_native::<Ordering>()?,
+                };
+                match operator {
+                    GREATER_THAN => ordering == Ordering::Greater,
+                    LESS_THAN => ordering == Ordering::Less,
+                    GREATER_EQUAL => ordering != Ordering::Less,
+                    LESS_EQUAL => ordering != Ordering::Greater,
+                    _ => unreachable!(),
+                }
+                .ok()
+            }
+            // x in 1..10, x in (1,2,3)
+            IN => {
+                let left = left.lazy_val()?;
+                match right.lazy_val()? {
+                    BergVal::Range(range) => range.contains(&left).ok(),
+                    right => {
+                        let mut next = right.next_val()?;
+                        while let Some(value) = next.head {
+                            if left.clone().infix(EQUAL_TO, value.into()).into_native::<bool>()? {
+                                return true.ok();
+                            }
+                            next = next.tail.next_val()?;
+                        }
+                        false.ok()
+                    }
+                }
+            }
             DOT => {
                 let left = left.lazy_val()?;
                 match right.try_into_native::<IdentifierIndex>()? {
@@ -454,4 +511,91 @@ pub mod implement {
     ) -> Result<(), EvalException> {
         CompilerError::NoSuchPublicFieldOnValue(Box::new(object.clone().lazy_val()?), name).err()
     }
+
+    pub fn default_index(object: impl Value, _index: BergVal) -> EvalResult {
+        use berg_parser::identifiers::INDEX;
+        CompilerError::UnsupportedOperator(Box::new(object.lazy_val()?), Fixity::Infix, INDEX)
+            .err()
+    }
+
+    #[allow(clippy::needless_pass_by_value)]
+    pub fn default_set_index(
+        object: &mut (impl Value + Clone),
+        _index: BergVal,
+        _value: BergVal,
+    ) -> Result<(), EvalException> {
+        use berg_parser::identifiers::INDEX;
+        CompilerError::UnsupportedOperator(
+            Box::new(object.clone().lazy_val()?),
+            Fixity::Infix,
+            INDEX,
+        )
+        .err()
+    }
+
+    ///
+    /// Where an index like `a[1]`, `a[1,3]` or `a[1..3]` points in a sequence.
+    ///
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub enum SequenceIndex {
+        /// `a[i]`: a single value.
+        At(usize),
+        /// `a[start,end]`: the values from `start` up to (but not including) `end`.
+        Slice(std::ops::Range<usize>),
+    }
+
+    ///
+    /// Figure out where `index` points in a sequence of `len` values.
+    ///
+    /// Indexes start at 0, and negative indexes count back from the end, so
+    /// `-1` is the last value. A 2-tuple or a range is a slice: `a[1,3]` and
+    /// `a[1...3]` are the values at 1 and 2, and `a[1..3]` includes 3.
+    ///
+    /// Returns `Ok(None)` if the index is outside the sequence.
+    ///
+    pub fn sequence_index(
+        index: &BergVal,
+        len: usize,
+    ) -> Result<Option<SequenceIndex>, EvalException> {
+        // Turn a possibly-negative position into an offset from the start,
+        // allowing `len` itself when it's the end of a slice.
+        fn offset(position: isize, len: usize, allow_end: bool) -> Option<usize> {
+            let offset = if position < 0 {
+                len.checked_sub(position.unsigned_abs())?
+            } else {
+                position as usize
+            };
+            if offset < len || (allow_end && offset == len) {
+                Some(offset)
+            } else {
+                None
+            }
+        }
+
+        let (start, end) = match index {
+            BergVal::Range(range) => match range.integer_bounds() {
+                // a[1..-1] goes up to and including the last value.
+                Some((start, end, true)) => (offset(start, len, true), offset(end, len, false).map(|end| end + 1)),
+                Some((start, end, false)) => (offset(start, len, true), offset(end, len, true)),
+                None => {
+                    return CompilerError::BadOperandType(Box::new(index.clone()), "integer range")
+                        .err();
+                }
+            },
+            BergVal::Tuple(tuple) if tuple.len() == 2 => {
+                let mut bounds = tuple.into_iter();
+                let start = bounds.next().unwrap().clone().into_native::<isize>()?;
+                let end = bounds.next().unwrap().clone().into_native::<isize>()?;
+                (offset(start, len, true), offset(end, len, true))
+            }
+            index => {
+                let index = index.clone().into_native::<isize>()?;
+                return Ok(offset(index, len, false).map(SequenceIndex::At));
+            }
+        };
+        match (start, end) {
+            (Some(start), Some(end)) if start <= end => Ok(Some(SequenceIndex::Slice(start..end))),
+            _ => Ok(None),
+        }
+    }
 }
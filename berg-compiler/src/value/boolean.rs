@@ -62,8 +62,23 @@ impl ObjectValue for bool {
     ) -> Result<(), EvalException> {
         default_set_field(self, name, value)
     }
+
+    fn index(self, index: BergVal) -> EvalResult
+    where
+        Self: Sized,
+    {
+        default_index(self, index)
+    }
+
+    fn set_index(&mut self, index: BergVal, value: BergVal) -> Result<(), EvalException> {
+        default_set_index(self, index, value)
+    }
 }
 
+///
+/// Booleans have no ordering: `true <=> false` (and `<`, `>`, etc.) is an
+/// unsupported operator, the same as for complex numbers.
+///
 impl OperableValue for bool {
     fn infix(
         self,
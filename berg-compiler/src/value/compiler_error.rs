@@ -4,7 +4,8 @@ use super::implement::*;
 use crate::eval::BlockRef;
 use berg_parser::identifiers::ERROR_CODE;
 use berg_parser::{
-    ExpressionPosition, FieldIndex, Fixity, IdentifierIndex, LiteralIndex, RawLiteralIndex,
+    ByteRange, ExpressionPosition, FieldIndex, Fixity, IdentifierIndex, LiteralIndex,
+    MAX_IDENTIFIER_LENGTH, MAX_LINE_LENGTH, RawLiteralIndex,
 };
 use std::rc::Rc;
 use std::{fmt, io};
@@ -25,11 +26,35 @@ pub enum CompilerError {
     InvalidUtf8(RawLiteralIndex),
     UnsupportedCharacters(LiteralIndex),
     IdentifierStartsWithNumber(LiteralIndex),
+    UnclosedString(LiteralIndex),
+    InvalidUtf8InString(RawLiteralIndex),
+    MissingExponent(LiteralIndex),
+    ExponentTooLarge(LiteralIndex),
+    UppercaseHexadecimalPrefix(LiteralIndex),
+    UppercaseOctalPrefix(LiteralIndex),
+    UppercaseBinaryPrefix(LiteralIndex),
+    DigitOutOfRange(LiteralIndex),
+    ImaginaryWithUppercaseI(LiteralIndex),
+    DenormalizedIdentifier(LiteralIndex),
+    IncompleteGrapheme(LiteralIndex),
+    IdentifierTooLarge(LiteralIndex),
+    InconsistentIndentCharacters(LiteralIndex),
+    UnclosedBlockComment(LiteralIndex),
+    DoubledDigitSeparator(LiteralIndex),
+    TrailingDigitSeparator(LiteralIndex),
+    LeadingDigitSeparator(LiteralIndex),
+    DigitSeparatorNextToRadixPrefix(LiteralIndex),
+    MissingRadixDigits(LiteralIndex),
+    LineTooLong(RawLiteralIndex),
     MissingOperand,
     AssignmentTargetMustBeIdentifier,
     RightSideOfDotMustBeIdentifier,
     OpenWithoutClose,
+    UnclosedInterpolation,
+    /// `"a#{1}b`: reported with the same code as an unclosed plain string.
+    UnclosedInterpolatedString,
     CloseWithoutOpen,
+    MultipleUndent,
     UnsupportedOperator(Box<dyn BergValue>, Fixity, IdentifierIndex),
     DivideByZero,
     NoSuchField(FieldIndex),
@@ -49,6 +74,11 @@ pub enum CompilerError {
     ForeachWithoutInput,
     ForeachWithoutBlock,
     ForeachBlockMustBeBlock,
+    MatchWithoutSubject,
+    MatchWithoutArms,
+    MatchArmsMustBeBlock,
+    MatchArmWithoutArrow,
+    InvalidFieldPattern,
     TryWithoutBlock,
     TryBlockMustBeBlock,
     TryWithoutCatchOrFinally,
@@ -70,6 +100,10 @@ pub enum CompilerError {
     NoSuchPublicFieldOnRoot(IdentifierIndex),
     ImmutableFieldOnRoot(FieldIndex),
     ImmutableFieldOnValue(Box<dyn BergValue>, IdentifierIndex),
+    IndexOutOfRange(Box<dyn BergValue>, Box<dyn BergValue>),
+    NoMatchingArm(Box<dyn BergValue>),
+    UnknownDsl(String),
+    InvalidDslText(String, ByteRange),
 
     // These are control values--only errors if nobody catches them.
     BreakOutsideLoop,
@@ -119,6 +153,26 @@ pub enum CompilerErrorCode {
     InvalidUtf8 = 201,
     UnsupportedCharacters,
     IdentifierStartsWithNumber,
+    UnclosedString,
+    InvalidUtf8InString,
+    MissingExponent,
+    ExponentTooLarge,
+    UppercaseHexadecimalPrefix,
+    UppercaseOctalPrefix,
+    UppercaseBinaryPrefix,
+    DigitOutOfRange,
+    ImaginaryWithUppercaseI,
+    DenormalizedIdentifier,
+    IncompleteGrapheme,
+    IdentifierTooLarge,
+    InconsistentIndentCharacters,
+    UnclosedBlockComment,
+    DoubledDigitSeparator,
+    TrailingDigitSeparator,
+    LeadingDigitSeparator,
+    DigitSeparatorNextToRadixPrefix,
+    MissingRadixDigits,
+    LineTooLong,
 
     // Compile errors related to structure (parser)
     MissingOperand = 301,
@@ -140,6 +194,11 @@ pub enum CompilerErrorCode {
     ForeachWithoutInput,
     ForeachWithoutBlock,
     ForeachBlockMustBeBlock,
+    MatchWithoutSubject,
+    MatchWithoutArms,
+    MatchArmsMustBeBlock,
+    MatchArmWithoutArrow,
+    InvalidFieldPattern,
     TryWithoutBlock,
     TryBlockMustBeBlock,
     TryWithoutCatchOrFinally,
@@ -151,6 +210,8 @@ pub enum CompilerErrorCode {
     FinallyBlockMustBeBlock,
     FinallyWithoutResult,
     ThrowWithoutException,
+    UnclosedInterpolation,
+    MultipleUndent,
 
     // Compile errors related to type (checker)
     UnsupportedOperator = 1001,
@@ -162,10 +223,33 @@ pub enum CompilerErrorCode {
     CircularDependency,
     PrivateField,
     ImmutableField,
+    IndexOutOfRange,
+    NoMatchingArm,
+    UnknownDsl,
+    InvalidDslText,
     BreakOutsideLoop,
     ContinueOutsideLoop,
 }
 
+impl CompilerErrorCode {
+    ///
+    /// Whether the source couldn't be loaded (see [`SourceLoadError`]).
+    ///
+    pub fn is_source_load_error(self) -> bool {
+        (self as usize) < CompilerErrorCode::InvalidUtf8 as usize
+    }
+
+    ///
+    /// Whether the error is in how the source is written (its characters or
+    /// structure), rather than in what it does when it runs.
+    ///
+    pub fn is_syntax_error(self) -> bool {
+        let code = self as usize;
+        code >= CompilerErrorCode::InvalidUtf8 as usize
+            && code < CompilerErrorCode::UnsupportedOperator as usize
+    }
+}
+
 impl BergValue for CompilerError {}
 
 impl EvaluatableValue for CompilerError {
@@ -232,6 +316,17 @@ impl ObjectValue for CompilerError {
             _ => default_set_field(self, name, value),
         }
     }
+
+    fn index(self, index: BergVal) -> EvalResult
+    where
+        Self: Sized,
+    {
+        default_index(self, index)
+    }
+
+    fn set_index(&mut self, index: BergVal, value: BergVal) -> Result<(), EvalException> {
+        default_set_index(self, index, value)
+    }
 }
 
 impl OperableValue for CompilerError {
@@ -321,10 +416,32 @@ impl fmt::Display for CompilerErrorCode {
             InvalidUtf8 => "InvalidUtf8",
             UnsupportedCharacters => "UnsupportedCharacters",
             IdentifierStartsWithNumber => "IdentifierStartsWithNumber",
+            UnclosedString => "UnclosedString",
+            InvalidUtf8InString => "InvalidUtf8InString",
+            MissingExponent => "MissingExponent",
+            ExponentTooLarge => "ExponentTooLarge",
+            UppercaseHexadecimalPrefix => "UppercaseHexadecimalPrefix",
+            UppercaseOctalPrefix => "UppercaseOctalPrefix",
+            UppercaseBinaryPrefix => "UppercaseBinaryPrefix",
+            DigitOutOfRange => "DigitOutOfRange",
+            ImaginaryWithUppercaseI => "ImaginaryWithUppercaseI",
+            DenormalizedIdentifier => "DenormalizedIdentifier",
+            IncompleteGrapheme => "IncompleteGrapheme",
+            IdentifierTooLarge => "IdentifierTooLarge",
+            InconsistentIndentCharacters => "InconsistentIndentCharacters",
+            UnclosedBlockComment => "UnclosedBlockComment",
+            DoubledDigitSeparator => "DoubledDigitSeparator",
+            TrailingDigitSeparator => "TrailingDigitSeparator",
+            LeadingDigitSeparator => "LeadingDigitSeparator",
+            DigitSeparatorNextToRadixPrefix => "DigitSeparatorNextToRadixPrefix",
+            MissingRadixDigits => "MissingRadixDigits",
+            LineTooLong => "LineTooLong",
             MissingOperand => "MissingOperand",
             AssignmentTargetMustBeIdentifier => "AssignmentTargetMustBeIdentifier",
             RightSideOfDotMustBeIdentifier => "RightSideOfDotMustBeIdentifier",
             OpenWithoutClose => "OpenWithoutClose",
+            UnclosedInterpolation => "UnclosedInterpolation",
+            MultipleUndent => "MultipleUndent",
             CloseWithoutOpen => "CloseWithoutOpen",
             UnsupportedOperator => "UnsupportedOperator",
             DivideByZero => "DivideByZero",
@@ -335,6 +452,7 @@ impl fmt::Display for CompilerErrorCode {
             CircularDependency => "CircularDependency",
             PrivateField => "PrivateField",
             ImmutableField => "ImmutableField",
+            IndexOutOfRange => "IndexOutOfRange",
             IfWithoutBlock => "IfWithoutBlock",
             IfWithoutCondition => "IfWithoutCondition",
             IfBlockMustBeBlock => "IfBlockMustBeBlock",
@@ -349,6 +467,14 @@ impl fmt::Display for CompilerErrorCode {
             ForeachWithoutInput => "ForeachWithoutInput",
             ForeachWithoutBlock => "ForeachWithoutBlock",
             ForeachBlockMustBeBlock => "ForeachBlockMustBeBlock",
+            MatchWithoutSubject => "MatchWithoutSubject",
+            MatchWithoutArms => "MatchWithoutArms",
+            MatchArmsMustBeBlock => "MatchArmsMustBeBlock",
+            MatchArmWithoutArrow => "MatchArmWithoutArrow",
+            InvalidFieldPattern => "InvalidFieldPattern",
+            NoMatchingArm => "NoMatchingArm",
+            UnknownDsl => "UnknownDsl",
+            InvalidDslText => "InvalidDslText",
             BreakOutsideLoop => "BreakOutsideLoop",
             ContinueOutsideLoop => "ContinueOutsideLoop",
             TryWithoutBlock => "TryWithoutBlock",
@@ -398,10 +524,33 @@ impl CompilerError {
             InvalidUtf8(..) => CompilerErrorCode::InvalidUtf8,
             UnsupportedCharacters(..) => CompilerErrorCode::UnsupportedCharacters,
             IdentifierStartsWithNumber(..) => CompilerErrorCode::IdentifierStartsWithNumber,
+            UnclosedString(..) => CompilerErrorCode::UnclosedString,
+            InvalidUtf8InString(..) => CompilerErrorCode::InvalidUtf8InString,
+            MissingExponent(..) => CompilerErrorCode::MissingExponent,
+            ExponentTooLarge(..) => CompilerErrorCode::ExponentTooLarge,
+            UppercaseHexadecimalPrefix(..) => CompilerErrorCode::UppercaseHexadecimalPrefix,
+            UppercaseOctalPrefix(..) => CompilerErrorCode::UppercaseOctalPrefix,
+            UppercaseBinaryPrefix(..) => CompilerErrorCode::UppercaseBinaryPrefix,
+            DigitOutOfRange(..) => CompilerErrorCode::DigitOutOfRange,
+            ImaginaryWithUppercaseI(..) => CompilerErrorCode::ImaginaryWithUppercaseI,
+            DenormalizedIdentifier(..) => CompilerErrorCode::DenormalizedIdentifier,
+            IncompleteGrapheme(..) => CompilerErrorCode::IncompleteGrapheme,
+            IdentifierTooLarge(..) => CompilerErrorCode::IdentifierTooLarge,
+            InconsistentIndentCharacters(..) => CompilerErrorCode::InconsistentIndentCharacters,
+            UnclosedBlockComment(..) => CompilerErrorCode::UnclosedBlockComment,
+            DoubledDigitSeparator(..) => CompilerErrorCode::DoubledDigitSeparator,
+            TrailingDigitSeparator(..) => CompilerErrorCode::TrailingDigitSeparator,
+            LeadingDigitSeparator(..) => CompilerErrorCode::LeadingDigitSeparator,
+            DigitSeparatorNextToRadixPrefix(..) => CompilerErrorCode::DigitSeparatorNextToRadixPrefix,
+            MissingRadixDigits(..) => CompilerErrorCode::MissingRadixDigits,
+            LineTooLong(..) => CompilerErrorCode::LineTooLong,
             MissingOperand => CompilerErrorCode::MissingOperand,
             AssignmentTargetMustBeIdentifier => CompilerErrorCode::AssignmentTargetMustBeIdentifier,
             RightSideOfDotMustBeIdentifier => CompilerErrorCode::RightSideOfDotMustBeIdentifier,
             OpenWithoutClose => CompilerErrorCode::OpenWithoutClose,
+            UnclosedInterpolation => CompilerErrorCode::UnclosedInterpolation,
+            UnclosedInterpolatedString => CompilerErrorCode::UnclosedString,
+            MultipleUndent => CompilerErrorCode::MultipleUndent,
             CloseWithoutOpen => CompilerErrorCode::CloseWithoutOpen,
             IfWithoutCondition => CompilerErrorCode::IfWithoutCondition,
             IfWithoutBlock => CompilerErrorCode::IfWithoutBlock,
@@ -419,6 +568,11 @@ impl CompilerError {
             ForeachWithoutInput => CompilerErrorCode::ForeachWithoutInput,
             ForeachWithoutBlock => CompilerErrorCode::ForeachWithoutBlock,
             ForeachBlockMustBeBlock => CompilerErrorCode::ForeachBlockMustBeBlock,
+            MatchWithoutSubject => CompilerErrorCode::MatchWithoutSubject,
+            MatchWithoutArms => CompilerErrorCode::MatchWithoutArms,
+            MatchArmsMustBeBlock => CompilerErrorCode::MatchArmsMustBeBlock,
+            MatchArmWithoutArrow => CompilerErrorCode::MatchArmWithoutArrow,
+            InvalidFieldPattern => CompilerErrorCode::InvalidFieldPattern,
             TryWithoutBlock => CompilerErrorCode::TryWithoutBlock,
             TryBlockMustBeBlock => CompilerErrorCode::TryBlockMustBeBlock,
             TryWithoutCatchOrFinally => CompilerErrorCode::TryWithoutCatchOrFinally,
@@ -444,6 +598,10 @@ impl CompilerError {
             ImmutableFieldOnValue(..) | ImmutableFieldOnRoot(..) => {
                 CompilerErrorCode::ImmutableField
             }
+            IndexOutOfRange(..) => CompilerErrorCode::IndexOutOfRange,
+            NoMatchingArm(..) => CompilerErrorCode::NoMatchingArm,
+            UnknownDsl(..) => CompilerErrorCode::UnknownDsl,
+            InvalidDslText(..) => CompilerErrorCode::InvalidDslText,
             BadOperandType(..) => CompilerErrorCode::BadOperandType,
         }
     }
@@ -467,22 +625,44 @@ impl CompilerError {
                 SourceRange(expression.ast, range)
             }
 
-            OpenWithoutClose => {
+            OpenWithoutClose | UnclosedInterpolation | UnclosedInterpolatedString => {
                 let range =
                     expression.ast.token_ranges[expression.expression().open_operator()].clone();
                 SourceRange(expression.ast, range)
             }
 
-            CloseWithoutOpen => {
+            CloseWithoutOpen | MultipleUndent => {
                 let range =
                     expression.ast.token_ranges[expression.expression().close_operator()].clone();
                 SourceRange(expression.ast, range)
             }
 
+            InvalidDslText(_, range) => SourceRange(expression.ast, range.clone()),
+
             // Expression errors
             InvalidUtf8(..)
             | UnsupportedCharacters(..)
             | IdentifierStartsWithNumber(..)
+            | UnclosedString(..)
+            | InvalidUtf8InString(..)
+            | MissingExponent(..)
+            | ExponentTooLarge(..)
+            | UppercaseHexadecimalPrefix(..)
+            | UppercaseOctalPrefix(..)
+            | UppercaseBinaryPrefix(..)
+            | DigitOutOfRange(..)
+            | ImaginaryWithUppercaseI(..)
+            | DenormalizedIdentifier(..)
+            | IncompleteGrapheme(..)
+            | IdentifierTooLarge(..)
+            | InconsistentIndentCharacters(..)
+            | UnclosedBlockComment(..)
+            | DoubledDigitSeparator(..)
+            | TrailingDigitSeparator(..)
+            | LeadingDigitSeparator(..)
+            | DigitSeparatorNextToRadixPrefix(..)
+            | MissingRadixDigits(..)
+            | LineTooLong(..)
             | AssignmentTargetMustBeIdentifier
             | NoSuchField(..)
             | NoSuchPublicField(..)
@@ -492,6 +672,9 @@ impl CompilerError {
             | CircularDependency
             | ImmutableFieldOnValue(..)
             | ImmutableFieldOnRoot(..)
+            | IndexOutOfRange(..)
+            | NoMatchingArm(..)
+            | UnknownDsl(..)
             | PrivateField(..)
             | BadOperandType(..)
             | DivideByZero
@@ -510,6 +693,11 @@ impl CompilerError {
             | ForeachWithoutInput
             | ForeachWithoutBlock
             | ForeachBlockMustBeBlock
+            | MatchWithoutSubject
+            | MatchWithoutArms
+            | MatchArmsMustBeBlock
+            | MatchArmWithoutArrow
+            | InvalidFieldPattern
             | BreakOutsideLoop
             | ContinueOutsideLoop
             | TryWithoutBlock
@@ -545,6 +733,125 @@ impl CompilerError {
                 }
                 write!(f, "'")
             }
+            InvalidUtf8InString(raw_literal) => write!(
+                f,
+                "Invalid UTF-8 bytes in string! Strings must be valid UTF-8. String: '{}'",
+                String::from_utf8_lossy(expression.ast.raw_literal_string(raw_literal))
+            ),
+            UnclosedString(_) | UnclosedInterpolatedString => write!(
+                f,
+                "Unclosed string. Put a \" at the end to fix this; it is possible, however, that a previous string is the problem. You may need to scan the file. Sorry about that."
+            ),
+            MissingExponent(_) => write!(
+                f,
+                "Empty exponent. If you meant the \"e\" to have an exponent, add some numbers."
+            ),
+            ExponentTooLarge(literal) => write!(
+                f,
+                "The exponent in '{}' is too large! Perhaps you meant a smaller number?",
+                expression.ast.literal_string(literal)
+            ),
+            UppercaseHexadecimalPrefix(literal) => write!(
+                f,
+                "Hexadecimal literals start with a lowercase '0x', but '{}' starts with '0X'! Change the 'X' to lowercase.",
+                expression.ast.literal_string(literal)
+            ),
+            UppercaseOctalPrefix(literal) => write!(
+                f,
+                "Octal literals start with a lowercase '0o', but '{}' starts with '0O'! Change the 'O' to lowercase.",
+                expression.ast.literal_string(literal)
+            ),
+            UppercaseBinaryPrefix(literal) => write!(
+                f,
+                "Binary literals start with a lowercase '0b', but '{}' starts with '0B'! Change the 'B' to lowercase.",
+                expression.ast.literal_string(literal)
+            ),
+            DigitOutOfRange(literal) => {
+                let literal = expression.ast.literal_string(literal);
+                let (kind, digits) = match literal.as_bytes()[1] {
+                    b'o' | b'O' => ("Octal", "0-7"),
+                    _ => ("Binary", "0 and 1"),
+                };
+                write!(
+                    f,
+                    "{} literals can only have the digits {} in them, but '{}' has other digits! If you meant to write a decimal number, remove the '{}'.",
+                    kind,
+                    digits,
+                    literal,
+                    &literal[0..2]
+                )
+            }
+            ImaginaryWithUppercaseI(literal) => write!(
+                f,
+                "Imaginary numbers end with a lowercase 'i', but '{}' ends with 'I'! Change the 'I' to lowercase.",
+                expression.ast.literal_string(literal)
+            ),
+            DenormalizedIdentifier(literal) => write!(
+                f,
+                "The identifier '{}' is not in Unicode normal form (NFC), so it could look just like a different identifier! Retype it or save the file in NFC form.",
+                expression.ast.literal_string(literal)
+            ),
+            IncompleteGrapheme(literal) => write!(
+                f,
+                "'{}' has characters at the end that combine with the identifier, but aren't allowed in identifiers! Remove them or put a space before them.",
+                expression.ast.literal_string(literal)
+            ),
+            IdentifierTooLarge(literal) => write!(
+                f,
+                "The identifier '{}...' is more than {} characters long! Give it a shorter name.",
+                expression.ast.literal_string(literal).chars().take(20).collect::<String>(),
+                MAX_IDENTIFIER_LENGTH
+            ),
+            InconsistentIndentCharacters(_) => write!(
+                f,
+                "Indents cannot match due to difference in tabs and spaces. Either convert tabs to spaces, or vice versa; do not mix them."
+            ),
+            UnclosedBlockComment(_) => write!(
+                f,
+                "Unclosed block comment. Put a ]# at the end of it for each #[ in it (block comments nest)."
+            ),
+            DoubledDigitSeparator(literal) => write!(
+                f,
+                "There are two _'s in a row in '{}'. Use a single _ to separate digits.",
+                expression.ast.literal_string(literal)
+            ),
+            TrailingDigitSeparator(literal) => write!(
+                f,
+                "The _ in '{}' isn't followed by a digit. A _ can only go between two digits.",
+                expression.ast.literal_string(literal)
+            ),
+            LeadingDigitSeparator(literal) => write!(
+                f,
+                "The exponent in '{}' starts with _. A _ can only go between two digits.",
+                expression.ast.literal_string(literal)
+            ),
+            DigitSeparatorNextToRadixPrefix(literal) => write!(
+                f,
+                "The _ in '{}' is next to its 0x, 0o or 0b. A _ can only go between two digits.",
+                expression.ast.literal_string(literal)
+            ),
+            MissingRadixDigits(literal) => {
+                let literal = expression.ast.literal_string(literal);
+                let (kind, digits) = match literal.as_bytes()[1] {
+                    b'x' | b'X' => ("Hexadecimal", "0-9 and a-f"),
+                    b'o' | b'O' => ("Octal", "0-7"),
+                    _ => ("Binary", "0 and 1"),
+                };
+                write!(
+                    f,
+                    "'{}' has no digits after it! {} literals need at least one digit ({}) after the prefix.",
+                    literal, kind, digits
+                )
+            }
+            MultipleUndent => write!(
+                f,
+                "This line closes more than two indented blocks at once, which makes it hard to tell which block it belongs to. Put a comment at the indent of each block you are closing."
+            ),
+            LineTooLong(_) => write!(
+                f,
+                "This line is more than {} characters long! Break it up into several lines.",
+                MAX_LINE_LENGTH
+            ),
             UnsupportedCharacters(literal) => write!(f, "Unsupported Unicode characters! Perhaps this isn't a Berg source file? Unsupported characters: '{}'", expression.ast.literal_string(literal)),
             OpenWithoutClose => write!(
                 f,
@@ -552,6 +859,10 @@ impl CompilerError {
                 expression.expression().open_token().to_string(&expression.ast),
                 expression.expression().boundary().close_string()
             ),
+            UnclosedInterpolation => write!(
+                f,
+                "Unclosed interpolation. Put a }} at the end of the expression to fix this; if you meant a literal \"#{{\", write \"\\#{{\" instead."
+            ),
             CloseWithoutOpen => write!(
                 f,
                 "Close '{}' found without a matching open '{}'.",
@@ -658,6 +969,40 @@ impl CompilerError {
                 "foreach block must be a block! Did you mean to add brackets here, like '{{ {} }}'?",
                 expression.expression()
             ),
+            MatchWithoutSubject => write!(
+                f,
+                "match is missing a value and arms! It should look like: match x {{ 1 => \"one\"; _ => \"other\" }}"
+            ),
+            MatchWithoutArms => write!(
+                f,
+                "match statement missing its arms! match requires a block of arms, such as 'match x {{ 1 => \"one\"; _ => \"other\" }}'"
+            ),
+            MatchArmsMustBeBlock => write!(
+                f,
+                "match arms must be a block! Did you mean to add brackets here, like '{{ {} }}'?",
+                expression.expression()
+            ),
+            MatchArmWithoutArrow => write!(
+                f,
+                "'{}' is not a match arm! Arms look like 'pattern => result', such as '1 => \"one\"' or '_ => \"other\"'.",
+                expression.expression()
+            ),
+            InvalidFieldPattern => write!(
+                f,
+                "'{}' is not a field pattern! Object patterns list fields like '{{ :name; size: 1..10 }}'.",
+                expression.expression()
+            ),
+            NoMatchingArm(ref value) => write!(
+                f,
+                "No match arm matches '{}'! Add an arm for it, or a '_ => ...' arm to catch everything else.",
+                value.display()
+            ),
+            UnknownDsl(ref name) => write!(
+                f,
+                "No DSL named '{}' is registered! Text blocks like '{}:' need a DSL registered on the root to handle them.",
+                name, name
+            ),
+            InvalidDslText(ref message, _) => write!(f, "{}", message),
             BreakOutsideLoop => write!(
                 f,
                 "break found outside loop! break must be called from within a while loop."
@@ -732,6 +1077,12 @@ impl CompilerError {
                 expression.ast.identifier_string(name),
                 value.display()
             ),
+            IndexOutOfRange(ref value, ref index) => write!(
+                f,
+                "Nothing exists at index {} of '{}'! Indexes start at 0, and negative indexes count back from the end.",
+                index.display(),
+                value.display()
+            ),
             IdentifierStartsWithNumber(literal) => write!(
                 f,
                 "Field names must start with letters or '_', but '{}' starts with a number! You may have mistyped the field name, or missed an operator?",
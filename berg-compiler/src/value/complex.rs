@@ -0,0 +1,208 @@
+use crate::value::implement::*;
+use berg_parser::identifiers::*;
+use berg_parser::IdentifierIndex;
+use num::{BigRational, Complex, Signed, Zero};
+use std::fmt;
+
+///
+/// Complex number with exact rational parts (`1 + 2i`).
+///
+pub type BigComplex = Complex<BigRational>;
+
+///
+/// Displays a complex number the way you would write it in Berg: `1+2i`,
+/// `-(3/2)i`. A zero real part is left out, and fractional imaginary parts
+/// are parenthesized so `(3/2)i` doesn't read as `3/(2i)`.
+///
+pub(crate) struct ComplexDisplay<'a>(pub &'a BigComplex);
+
+impl fmt::Display for ComplexDisplay<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let ComplexDisplay(value) = self;
+        if !value.re.is_zero() {
+            write!(f, "{}", value.re)?;
+            if !value.im.is_negative() {
+                write!(f, "+")?;
+            }
+        }
+        if value.im.is_negative() {
+            write!(f, "-")?;
+        }
+        let im = value.im.abs();
+        if im.is_integer() {
+            write!(f, "{}i", im)
+        } else {
+            write!(f, "({})i", im)
+        }
+    }
+}
+
+impl BergValue for BigComplex {}
+
+impl EvaluatableValue for BigComplex {
+    fn evaluate(self) -> BergResult
+    where
+        Self: Sized,
+    {
+        self.ok()
+    }
+}
+
+impl Value for BigComplex {
+    fn lazy_val(self) -> Result<BergVal, EvalException>
+    where
+        Self: Sized,
+    {
+        self.ok()
+    }
+    fn eval_val(self) -> EvalResult
+    where
+        Self: Sized,
+    {
+        self.ok()
+    }
+    fn into_native<T: TryFromBergVal>(self) -> Result<T, EvalException> {
+        default_into_native(self)
+    }
+    fn try_into_native<T: TryFromBergVal>(self) -> Result<Option<T>, EvalException> {
+        default_try_into_native(self)
+    }
+    fn display(&self) -> &dyn std::fmt::Display {
+        self
+    }
+}
+
+impl IteratorValue for BigComplex {
+    fn next_val(self) -> Result<NextVal, EvalException> {
+        single_next_val(self)
+    }
+}
+
+impl ObjectValue for BigComplex {
+    fn field(self, name: IdentifierIndex) -> EvalResult
+    where
+        Self: Sized,
+    {
+        default_field(self, name)
+    }
+    fn set_field(
+        &mut self,
+        name: IdentifierIndex,
+        value: BergVal,
+    ) -> Result<(), EvalException> {
+        default_set_field(self, name, value)
+    }
+
+    fn index(self, index: BergVal) -> EvalResult
+    where
+        Self: Sized,
+    {
+        default_index(self, index)
+    }
+
+    fn set_index(&mut self, index: BergVal, value: BergVal) -> Result<(), EvalException> {
+        default_set_index(self, index, value)
+    }
+}
+
+impl OperableValue for BigComplex {
+    fn infix(
+        self,
+        operator: IdentifierIndex,
+        right: RightOperand<impl EvaluatableValue>,
+    ) -> EvalResult
+    where
+        Self: Sized,
+    {
+        match operator {
+            PLUS => (self + right.into_native::<BigComplex>()?).ok(),
+            DASH => (self - right.into_native::<BigComplex>()?).ok(),
+            SLASH => {
+                let right = right.into_native::<BigComplex>()?;
+                if right.is_zero() {
+                    CompilerError::DivideByZero.operand_err(Right)
+                } else {
+                    (self / right).ok()
+                }
+            }
+            STAR => (self * right.into_native::<BigComplex>()?).ok(),
+            EQUAL_TO => match right.try_into_native::<BigComplex>()? {
+                Some(right) => self == right,
+                None => false,
+            }
+            .ok(),
+            _ => default_infix(self, operator, right),
+        }
+    }
+
+    fn infix_assign(
+        self,
+        operator: IdentifierIndex,
+        right: RightOperand<impl EvaluatableValue>,
+    ) -> EvalResult
+    where
+        Self: Sized,
+    {
+        default_infix_assign(self, operator, right)
+    }
+
+    fn prefix(self, operator: IdentifierIndex) -> EvalResult
+    where
+        Self: Sized,
+    {
+        match operator {
+            PLUS => (self).ok(),
+            DASH => (-self).ok(),
+            _ => default_prefix(self, operator),
+        }
+    }
+
+    fn postfix(self, operator: IdentifierIndex) -> EvalResult
+    where
+        Self: Sized,
+    {
+        default_postfix(self, operator)
+    }
+
+    fn subexpression_result(self, boundary: ExpressionBoundary) -> EvalResult
+    where
+        Self: Sized,
+    {
+        default_subexpression_result(self, boundary)
+    }
+}
+
+///
+/// Complex numbers with no imaginary part (like `2i * 2i`) become plain
+/// rationals.
+///
+impl From<BigComplex> for BergVal {
+    fn from(from: BigComplex) -> Self {
+        if from.im.is_zero() {
+            BergVal::BigRational(from.re)
+        } else {
+            BergVal::Complex(Box::new(from))
+        }
+    }
+}
+impl From<BigComplex> for EvalVal {
+    fn from(from: BigComplex) -> Self {
+        BergVal::from(from).into()
+    }
+}
+
+///
+/// Rationals convert to complex numbers too, so `1 + 2i` works.
+///
+impl TryFromBergVal for BigComplex {
+    const TYPE_NAME: &'static str = "number";
+    fn try_from_berg_val(
+        from: EvalVal,
+    ) -> Result<Result<Self, BergVal>, EvalException> {
+        match from.lazy_val()? {
+            BergVal::Complex(value) => Ok(Ok(*value)),
+            BergVal::BigRational(value) => Ok(Ok(value.into())),
+            from => Ok(Err(from)),
+        }
+    }
+}
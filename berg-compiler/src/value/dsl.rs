@@ -0,0 +1,236 @@
+use crate::eval::BlockRef;
+use crate::value::implement::*;
+use berg_parser::{Ast, ByteIndex, ByteRange};
+use std::borrow::Cow;
+use std::fmt;
+use std::ops;
+use std::rc::Rc;
+
+///
+/// The text of a text block (like the indented query after `sql:`), handed to
+/// the DSL registered for it.
+///
+/// The block's indent is taken off each line, so the text starts at the
+/// margin. [`DslText::source_range()`] maps ranges of the text back to the
+/// source, so DSL errors can point at the right place.
+///
+#[derive(Debug, Clone)]
+pub struct DslText {
+    name: String,
+    text: String,
+    ///
+    /// Where each line starts in the text, and where it starts in the source.
+    ///
+    lines: Vec<(usize, ByteIndex)>,
+}
+
+///
+/// An error a DSL found in its text.
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DslError {
+    ///
+    /// Where the error is, as a range of the text.
+    ///
+    pub range: ops::Range<usize>,
+    pub message: String,
+}
+
+pub type DslResult = Result<BergVal, DslError>;
+
+///
+/// Turns the text blocks of a DSL into values. See [`RootRef::register_dsl()`].
+///
+#[derive(Clone)]
+pub enum DslHandler {
+    ///
+    /// A Rust function.
+    ///
+    Native(Rc<dyn Fn(&DslText) -> DslResult>),
+    ///
+    /// Berg source, which is run with the text as its input (`:text` gets the
+    /// text). It is parsed when it is registered.
+    ///
+    /// To report an error in the text, it throws the range of the text (in
+    /// bytes) and a message, like `throw (0...3, "bad is not allowed")`, or
+    /// just a message to point at the whole text. Other errors are reported
+    /// in the handler, where they happened.
+    ///
+    Berg {
+        source: Cow<'static, str>,
+        ast: Option<Rc<Ast>>,
+    },
+}
+
+impl DslText {
+    ///
+    /// Makes the text of a text block, given its text as it is in the source
+    /// (indent and all) and where that starts.
+    ///
+    pub fn new(name: &str, source_text: &str, start: ByteIndex) -> Self {
+        let indent_of = |line: &str| line.len() - line.trim_start_matches([' ', '\t']).len();
+        let indent = lines(source_text)
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(_, line)| indent_of(line))
+            .min()
+            .unwrap_or(0);
+
+        let mut text = String::with_capacity(source_text.len());
+        let mut line_starts = vec![];
+        for (offset, line) in lines(source_text) {
+            let indent = indent.min(indent_of(line));
+            line_starts.push((text.len(), start + offset + indent));
+            text.push_str(&line[indent..]);
+        }
+        DslText {
+            name: name.into(),
+            text,
+            lines: line_starts,
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    ///
+    /// The range in the source that `range` of the text came from.
+    ///
+    pub fn source_range(&self, range: ops::Range<usize>) -> ByteRange {
+        let start = self.source_index(range.start);
+        if range.end <= range.start {
+            return start..start;
+        }
+        // Map the last character rather than the end, so a range ending at a
+        // newline doesn't take in the indent of the next line.
+        start..self.source_index(range.end - 1) + 1
+    }
+
+    fn source_index(&self, index: usize) -> ByteIndex {
+        let index = index.min(self.text.len());
+        let line = self.lines.partition_point(|&(start, _)| start <= index) - 1;
+        let (text_start, source_start) = self.lines[line];
+        source_start + (index - text_start)
+    }
+}
+
+impl DslError {
+    pub fn new(range: ops::Range<usize>, message: impl fmt::Display) -> Self {
+        DslError {
+            range,
+            message: message.to_string(),
+        }
+    }
+}
+
+impl DslHandler {
+    pub fn native(handler: impl Fn(&DslText) -> DslResult + 'static) -> Self {
+        DslHandler::Native(Rc::new(handler))
+    }
+
+    pub fn berg(source: impl Into<Cow<'static, str>>) -> Self {
+        DslHandler::Berg {
+            source: source.into(),
+            ast: None,
+        }
+    }
+
+    ///
+    /// Parses a Berg handler, so it isn't parsed again each time it runs.
+    ///
+    pub(crate) fn parse(self, root: &RootRef, name: &str) -> Self {
+        match self {
+            DslHandler::Berg { source, ast: None } => {
+                let ast = root.parse_bytes(handler_name(name), source.as_bytes().to_vec());
+                let ast = Some(ast.ast.clone());
+                DslHandler::Berg { source, ast }
+            }
+            handler => handler,
+        }
+    }
+
+    ///
+    /// Turns the text into a value. An error in the text comes back as a
+    /// [`DslError`], to be mapped to the source with
+    /// [`DslText::source_range()`]; an error in the handler itself comes back
+    /// as it is.
+    ///
+    pub fn run(&self, root: &RootRef, text: &DslText) -> Result<DslResult, Exception> {
+        match self {
+            DslHandler::Native(handler) => Ok(handler(text)),
+            DslHandler::Berg { ast: Some(ast), .. } => {
+                let source = SourceSpec::Memory(handler_name(text.name()).into());
+                let ast = AstRef::new(root.clone(), source, ast.clone());
+                match BlockRef::from_ast(ast)?.apply(text.text().to_string().into()) {
+                    Ok(value) => Ok(Ok(value)),
+                    Err(exception) => match thrown_dsl_error(text, &exception.value) {
+                        Some(error) => Ok(Err(error)),
+                        None => Err(exception),
+                    },
+                }
+            }
+            DslHandler::Berg { .. } => self.clone().parse(root, text.name()).run(root, text),
+        }
+    }
+}
+
+impl fmt::Debug for DslHandler {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DslHandler::Native(_) => write!(f, "Native"),
+            DslHandler::Berg { source, .. } => f.debug_tuple("Berg").field(source).finish(),
+        }
+    }
+}
+
+fn handler_name(name: &str) -> String {
+    format!("{} DSL", name)
+}
+
+// The error a Berg handler threw about the text: `(range, message)`, or just a
+// message for the whole text.
+fn thrown_dsl_error(text: &DslText, value: &BergVal) -> Option<DslError> {
+    match value {
+        BergVal::String(message) => Some(DslError::new(0..text.text().len(), message)),
+        BergVal::Tuple(tuple) if tuple.len() == 2 => {
+            let mut values = tuple.clone().into_iter();
+            match (values.next()?, values.next()?) {
+                (BergVal::Range(range), BergVal::String(message)) => {
+                    let (start, end, inclusive) = range.integer_bounds()?;
+                    let end = if inclusive { end + 1 } else { end };
+                    let range = usize::try_from(start).ok()?..usize::try_from(end).ok()?;
+                    Some(DslError::new(range, message))
+                }
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+// The lines of the text and where they start, with their line endings.
+fn lines(text: &str) -> impl Iterator<Item = (usize, &str)> {
+    let bytes = text.as_bytes();
+    let mut start = 0;
+    std::iter::from_fn(move || {
+        if start >= bytes.len() {
+            return None;
+        }
+        let mut end = start;
+        while end < bytes.len() && bytes[end] != b'\n' && bytes[end] != b'\r' {
+            end += 1;
+        }
+        if bytes[end..].starts_with(b"\r\n") {
+            end += 2;
+        } else if end < bytes.len() {
+            end += 1;
+        }
+        let line = (start, &text[start..end]);
+        start = end;
+        Some(line)
+    })
+}
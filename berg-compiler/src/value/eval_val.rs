@@ -35,6 +35,10 @@ pub enum EvalVal {
     Foreach,
     /// foreach <input>
     ForeachInput(Result<BergVal, EvalException>),
+    /// match
+    Match,
+    /// match <subject>
+    MatchSubject(Result<BergVal, EvalException>),
     /// try
     Try,
     /// try { <error> }
@@ -61,7 +65,7 @@ pub enum EvalVal {
     TrailingComma(Vec<BergVal>),
     /// 1;2;
     TrailingSemicolon,
-    /// Things that can be assigned to: a, :a, a.b
+    /// Things that can be assigned to: a, :a, a.b, a[i]
     Target(AssignmentTarget),
 }
 
@@ -83,6 +87,8 @@ pub enum AssignmentTarget {
     LocalFieldReference(BlockRef, FieldIndex),
     LocalFieldDeclaration(BlockRef, FieldIndex),
     ObjectFieldReference(BergVal, IdentifierIndex),
+    /// a[i] (refers to the value at i in whatever a is)
+    IndexReference(Box<EvalVal>, BergVal),
 }
 
 #[derive(Debug, Clone)]
@@ -109,9 +115,10 @@ impl EvalVal {
         match self {
             Target(v) => v.get(),
             Val(_) | If | Else | ConditionalVal(..) | While | WhileCondition(_) | Foreach
-            | ForeachInput(_) | Try | TryResult(_) | Catch | TryCatch(_) | CatchResult(_)
-            | Finally | TryFinally(_) | Throw | MissingExpression | PartialTuple(_)
-            | TrailingComma(_) | TrailingSemicolon | RawIdentifier(_) => self.ok(),
+            | ForeachInput(_) | Match | MatchSubject(_) | Try | TryResult(_) | Catch
+            | TryCatch(_) | CatchResult(_) | Finally | TryFinally(_) | Throw
+            | MissingExpression | PartialTuple(_) | TrailingComma(_) | TrailingSemicolon
+            | RawIdentifier(_) => self.ok(),
         }
     }
 }
@@ -142,6 +149,8 @@ impl Value for EvalVal {
             WhileCondition(_) => WhileWithoutBlock.operand_err(Left),
             Foreach => ForeachWithoutInput.err(),
             ForeachInput(_) => ForeachWithoutBlock.operand_err(Left),
+            Match => MatchWithoutSubject.err(),
+            MatchSubject(_) => MatchWithoutArms.operand_err(Left),
             Try => TryWithoutBlock.err(),
             TryResult(_) => TryWithoutCatchOrFinally.err(),
             Catch => CatchWithoutResult.err(),
@@ -164,8 +173,8 @@ impl Value for EvalVal {
             RawIdentifier(v) => v.eval_val(),
             MissingExpression | PartialTuple(_) | TrailingComma(_) | TrailingSemicolon | If
             | Else | ConditionalVal(..) | While | WhileCondition(_) | Foreach | ForeachInput(_)
-            | Try | TryResult(_) | Catch | TryCatch(_) | CatchResult(_) | Finally
-            | TryFinally(_) | Throw => self.ok(),
+            | Match | MatchSubject(_) | Try | TryResult(_) | Catch | TryCatch(_)
+            | CatchResult(_) | Finally | TryFinally(_) | Throw => self.ok(),
         }
     }
 
@@ -176,8 +185,8 @@ impl Value for EvalVal {
             RawIdentifier(v) => v.into_native(),
             MissingExpression | PartialTuple(_) | TrailingComma(_) | TrailingSemicolon | If
             | Else | ConditionalVal(..) | While | WhileCondition(_) | Foreach | ForeachInput(_)
-            | Try | TryResult(_) | Catch | TryCatch(_) | CatchResult(_) | Finally
-            | TryFinally(_) | Throw => self.lazy_val().into_native(),
+            | Match | MatchSubject(_) | Try | TryResult(_) | Catch | TryCatch(_)
+            | CatchResult(_) | Finally | TryFinally(_) | Throw => self.lazy_val().into_native(),
         }
     }
 
@@ -188,8 +197,8 @@ impl Value for EvalVal {
             RawIdentifier(v) => v.try_into_native(),
             MissingExpression | PartialTuple(_) | TrailingComma(_) | TrailingSemicolon | If
             | Else | ConditionalVal(..) | While | WhileCondition(_) | Foreach | ForeachInput(_)
-            | Try | TryResult(_) | Catch | TryCatch(_) | CatchResult(_) | Finally
-            | TryFinally(_) | Throw => self.lazy_val().try_into_native(),
+            | Match | MatchSubject(_) | Try | TryResult(_) | Catch | TryCatch(_)
+            | CatchResult(_) | Finally | TryFinally(_) | Throw => self.lazy_val().try_into_native(),
         }
     }
 
@@ -206,8 +215,8 @@ impl IteratorValue for EvalVal {
             RawIdentifier(v) => v.next_val(),
             MissingExpression | PartialTuple(_) | TrailingComma(_) | TrailingSemicolon | If
             | Else | ConditionalVal(..) | While | WhileCondition(_) | Foreach | ForeachInput(_)
-            | Try | TryResult(_) | Catch | TryCatch(_) | CatchResult(_) | Finally
-            | TryFinally(_) | Throw => self.lazy_val().next_val(),
+            | Match | MatchSubject(_) | Try | TryResult(_) | Catch | TryCatch(_)
+            | CatchResult(_) | Finally | TryFinally(_) | Throw => self.lazy_val().next_val(),
         }
     }
 }
@@ -223,8 +232,8 @@ impl ObjectValue for EvalVal {
             RawIdentifier(v) => v.field(name),
             MissingExpression | PartialTuple(_) | TrailingComma(_) | TrailingSemicolon | If
             | Else | ConditionalVal(..) | While | WhileCondition(_) | Foreach | ForeachInput(_)
-            | Try | TryResult(_) | Catch | TryCatch(_) | CatchResult(_) | Finally
-            | TryFinally(_) | Throw => self.lazy_val().field(name),
+            | Match | MatchSubject(_) | Try | TryResult(_) | Catch | TryCatch(_)
+            | CatchResult(_) | Finally | TryFinally(_) | Throw => self.lazy_val().field(name),
         }
     }
 
@@ -239,14 +248,45 @@ impl ObjectValue for EvalVal {
             RawIdentifier(v) => v.set_field(name, value),
             MissingExpression => CompilerError::MissingOperand.err(),
             PartialTuple(_) | TrailingComma(_) | TrailingSemicolon | If | Else
-            | ConditionalVal(..) | While | WhileCondition(_) | Foreach | ForeachInput(_) | Try
-            | TryResult(_) | Catch | TryCatch(_) | CatchResult(_) | Finally | TryFinally(_)
-            | Throw => panic!(
+            | ConditionalVal(..) | While | WhileCondition(_) | Foreach | ForeachInput(_)
+            | Match | MatchSubject(_) | Try | TryResult(_) | Catch | TryCatch(_)
+            | CatchResult(_) | Finally | TryFinally(_) | Throw => panic!(
                 "not yet implemented: can't set field {} on {:?} to {}",
                 name, self, value
             ),
         }
     }
+
+    fn index(self, index: BergVal) -> EvalResult
+    where
+        Self: Sized,
+    {
+        match self {
+            Val(v) => v.index(index),
+            Target(v) => v.index(index),
+            RawIdentifier(v) => v.index(index),
+            MissingExpression | PartialTuple(_) | TrailingComma(_) | TrailingSemicolon | If
+            | Else | ConditionalVal(..) | While | WhileCondition(_) | Foreach | ForeachInput(_)
+            | Match | MatchSubject(_) | Try | TryResult(_) | Catch | TryCatch(_)
+            | CatchResult(_) | Finally | TryFinally(_) | Throw => self.lazy_val().index(index),
+        }
+    }
+
+    fn set_index(&mut self, index: BergVal, value: BergVal) -> Result<(), EvalException> {
+        match self {
+            Val(v) => v.set_index(index, value),
+            Target(v) => v.set_index(index, value),
+            RawIdentifier(v) => v.set_index(index, value),
+            MissingExpression => CompilerError::MissingOperand.err(),
+            PartialTuple(_) | TrailingComma(_) | TrailingSemicolon | If | Else
+            | ConditionalVal(..) | While | WhileCondition(_) | Foreach | ForeachInput(_)
+            | Match | MatchSubject(_) | Try | TryResult(_) | Catch | TryCatch(_)
+            | CatchResult(_) | Finally | TryFinally(_) | Throw => panic!(
+                "not yet implemented: can't set index {} on {:?} to {}",
+                index, self, value
+            ),
+        }
+    }
 }
 
 impl OperableValue for EvalVal {
@@ -383,6 +423,24 @@ impl OperableValue for EvalVal {
                     ForeachInput(input).lazy_val().infix(operator, right)
                 }
             }
+            // match <subject>
+            Match if operator.is_followed_by() => MatchSubject(right.lazy_val()).ok(),
+            MatchSubject(subject) => {
+                if operator.is_followed_by() {
+                    match right.lazy_val()? {
+                        BergVal::BlockRef(arms) => {
+                            let subject = subject?;
+                            match arms.run_match(&subject)? {
+                                Some(result) => result.ok(),
+                                None => NoMatchingArm(Box::new(subject)).operand_err(LeftRight),
+                            }
+                        }
+                        _ => MatchArmsMustBeBlock.operand_err(Right),
+                    }
+                } else {
+                    MatchSubject(subject).lazy_val().infix(operator, right)
+                }
+            }
             Try if operator.is_followed_by() => match right.lazy_val()? {
                 BergVal::BlockRef(block) => TryResult(block.evaluate()).ok(),
                 _ => TryBlockMustBeBlock.operand_err(Right),
@@ -442,7 +500,9 @@ impl OperableValue for EvalVal {
             }
             Throw if operator.is_followed_by() => right.lazy_val()?.throw(),
             MissingExpression | TrailingSemicolon | TrailingComma(_) | If | Else | While
-            | Foreach | Try | Catch | Finally | Throw => self.lazy_val().infix(operator, right),
+            | Foreach | Match | Try | Catch | Finally | Throw => {
+                self.lazy_val().infix(operator, right)
+            }
         }
     }
 
@@ -460,8 +520,10 @@ impl OperableValue for EvalVal {
             RawIdentifier(v) => v.infix_assign(operator, right),
             MissingExpression | PartialTuple(_) | TrailingComma(_) | TrailingSemicolon | If
             | Else | ConditionalVal(..) | While | WhileCondition(_) | Foreach | ForeachInput(_)
-            | Try | TryResult(_) | Catch | TryCatch(_) | CatchResult(_) | Finally
-            | TryFinally(_) | Throw => self.lazy_val().infix_assign(operator, right),
+            | Match | MatchSubject(_) | Try | TryResult(_) | Catch | TryCatch(_)
+            | CatchResult(_) | Finally | TryFinally(_) | Throw => {
+                self.lazy_val().infix_assign(operator, right)
+            }
         }
     }
 
@@ -475,8 +537,8 @@ impl OperableValue for EvalVal {
             RawIdentifier(v) => v.prefix(operator),
             MissingExpression | PartialTuple(_) | TrailingComma(_) | TrailingSemicolon | If
             | Else | ConditionalVal(..) | While | WhileCondition(_) | Foreach | ForeachInput(_)
-            | Try | TryResult(_) | Catch | TryCatch(_) | CatchResult(_) | Finally
-            | TryFinally(_) | Throw => self.lazy_val().prefix(operator),
+            | Match | MatchSubject(_) | Try | TryResult(_) | Catch | TryCatch(_)
+            | CatchResult(_) | Finally | TryFinally(_) | Throw => self.lazy_val().prefix(operator),
         }
     }
 
@@ -490,8 +552,8 @@ impl OperableValue for EvalVal {
             RawIdentifier(v) => v.prefix(operator),
             MissingExpression | PartialTuple(_) | TrailingComma(_) | TrailingSemicolon | If
             | Else | ConditionalVal(..) | While | WhileCondition(_) | Foreach | ForeachInput(_)
-            | Try | TryResult(_) | Catch | TryCatch(_) | CatchResult(_) | Finally
-            | TryFinally(_) | Throw => self.lazy_val().postfix(operator),
+            | Match | MatchSubject(_) | Try | TryResult(_) | Catch | TryCatch(_)
+            | CatchResult(_) | Finally | TryFinally(_) | Throw => self.lazy_val().postfix(operator),
         }
     }
 
@@ -509,8 +571,10 @@ impl OperableValue for EvalVal {
             }
             MissingExpression | PartialTuple(_) | TrailingComma(_) | TrailingSemicolon | If
             | Else | ConditionalVal(..) | While | WhileCondition(_) | Foreach | ForeachInput(_)
-            | Try | TryResult(_) | Catch | TryCatch(_) | CatchResult(_) | Finally
-            | TryFinally(_) | Throw => self.lazy_val().subexpression_result(boundary),
+            | Match | MatchSubject(_) | Try | TryResult(_) | Catch | TryCatch(_)
+            | CatchResult(_) | Finally | TryFinally(_) | Throw => {
+                self.lazy_val().subexpression_result(boundary)
+            }
         }
     }
 }
@@ -590,6 +654,8 @@ impl fmt::Display for EvalVal {
             WhileCondition(condition) => write!(f, "while {}", condition),
             Foreach => write!(f, "foreach"),
             ForeachInput(input) => write!(f, "foreach {}", input.display()),
+            Match => write!(f, "match"),
+            MatchSubject(subject) => write!(f, "match {}", subject.display()),
             Try => write!(f, "try"),
             TryResult(r) => write!(f, "try -> {}", r.display()),
             Catch => write!(f, "catch"),
@@ -624,7 +690,7 @@ impl AssignmentTarget {
         use AssignmentTarget::*;
         match self {
             LocalFieldDeclaration(scope, field) => scope.declare_field(*field, &scope.ast())?,
-            LocalFieldReference(..) | ObjectFieldReference(..) => {}
+            LocalFieldReference(..) | ObjectFieldReference(..) | IndexReference(..) => {}
         }
         Ok(())
     }
@@ -636,6 +702,7 @@ impl AssignmentTarget {
                 scope.local_field(*field, &scope.ast())
             }
             ObjectFieldReference(object, name) => object.clone().field(*name),
+            IndexReference(object, index) => (**object).clone().index(index.clone()),
         };
         self.point_errors_at_identifier(result)
     }
@@ -647,6 +714,7 @@ impl AssignmentTarget {
                 scope.set_local_field(*field, value, &scope.ast())
             }
             ObjectFieldReference(object, name) => object.set_field(*name, value),
+            IndexReference(object, index) => object.set_index(index.clone(), value),
         };
         self.point_errors_at_identifier(result)
     }
@@ -658,7 +726,7 @@ impl AssignmentTarget {
         use AssignmentTarget::*;
         use ExpressionPosition::*;
         match self {
-            LocalFieldDeclaration(..) | ObjectFieldReference(..) => {
+            LocalFieldDeclaration(..) | ObjectFieldReference(..) | IndexReference(..) => {
                 result.map_err(|e| e.reposition(Right))
             }
             LocalFieldReference(..) => result,
@@ -718,6 +786,19 @@ impl ObjectValue for AssignmentTarget {
         obj.set_field(name, value)?;
         self.set(obj, Expression).and(Ok(()))
     }
+    fn index(self, index: BergVal) -> EvalResult
+    where
+        Self: Sized,
+    {
+        self.get().index(index)
+    }
+    fn set_index(&mut self, index: BergVal, value: BergVal) -> Result<(), EvalException> {
+        // Values like tuples are copied when we get them, so put the changed
+        // copy back where we got it from.
+        let mut obj = self.get().lazy_val()?;
+        obj.set_index(index, value)?;
+        self.set(obj, Expression).and(Ok(()))
+    }
 }
 
 impl OperableValue for AssignmentTarget {
@@ -792,6 +873,7 @@ impl fmt::Display for AssignmentTarget {
                     .identifier_string(scope.ast().fields[*field].name)
             ),
             ObjectFieldReference(object, name) => write!(f, "{}.{}", object, name),
+            IndexReference(object, index) => write!(f, "{}[{}]", object, index),
         }
     }
 }
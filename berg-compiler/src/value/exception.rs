@@ -1,7 +1,9 @@
 use super::implement::*;
 use berg_parser::{
-    AstIndex, ByteRange, ExpressionPosition, ExpressionTreeWalker, IdentifierIndex, LineColumnRange,
+    AstIndex, ByteRange, ColumnUnit, ExpressionPosition, ExpressionTreeWalker, IdentifierIndex,
+    LineColumnRange,
 };
+use std::borrow::Cow;
 use std::fmt;
 
 ///
@@ -65,10 +67,32 @@ impl EvalException {
     }
 }
 impl ErrorLocation {
+    ///
+    /// The name of the source the error is in, if it is in one.
+    ///
+    pub fn source_name(&self) -> Option<Cow<'_, str>> {
+        match self {
+            ErrorLocation::Generic => None,
+            ErrorLocation::SourceOnly(ast)
+            | ErrorLocation::SourceExpression(ast, _)
+            | ErrorLocation::SourceRange(ast, _) => Some(ast.source.name()),
+        }
+    }
+    ///
+    /// Where the error is, with columns counted the way the root says to.
+    ///
     pub fn range(&self) -> LineColumnRange {
         match self {
             ErrorLocation::SourceExpression(ast, _) | ErrorLocation::SourceRange(ast, _) => {
-                ast.char_data.range(&self.byte_range())
+                self.range_in(ast.root.root().columns())
+            }
+            _ => unreachable!(),
+        }
+    }
+    pub fn range_in(&self, columns: ColumnUnit) -> LineColumnRange {
+        match self {
+            ErrorLocation::SourceExpression(ast, _) | ErrorLocation::SourceRange(ast, _) => {
+                ast.line_column_range(&self.byte_range(), columns)
             }
             _ => unreachable!(),
         }
@@ -86,7 +110,14 @@ impl ErrorLocation {
 
 impl Exception {
     pub fn new(value: BergVal, expression: ExpressionRef) -> Self {
-        Exception { value, expression }
+        let exception = Exception { value, expression };
+        if let Some(tracer) = exception.expression.ast.root.tracer() {
+            tracer.trace(&TraceEvent::ExceptionThrown {
+                expression: exception.expression.traced(),
+                exception: &exception,
+            });
+        }
+        exception
     }
 
     pub fn expression(&self) -> ExpressionRef {
@@ -109,6 +140,12 @@ impl Exception {
     }
 
     pub fn catch(self) -> CaughtException {
+        if let Some(tracer) = self.expression.ast.root.tracer() {
+            tracer.trace(&TraceEvent::ExceptionCaught {
+                expression: self.expression.traced(),
+                exception: &self,
+            });
+        }
         CaughtException(Box::new(self))
     }
 }
@@ -208,6 +245,28 @@ impl ObjectValue for EvalException {
             Thrown(..) => self.clone().err(),
         }
     }
+
+    fn index(self, index: BergVal) -> EvalResult
+    where
+        Self: Sized,
+    {
+        use EvalException::*;
+        match self {
+            Error(value) => value.index(index),
+            Thrown(..) => self.err(),
+        }
+    }
+
+    fn set_index(&mut self, index: BergVal, value: BergVal) -> Result<(), EvalException>
+    where
+        Self: Clone,
+    {
+        use EvalException::*;
+        match self {
+            Error(error) => error.set_index(index, value),
+            Thrown(..) => self.clone().err(),
+        }
+    }
 }
 
 impl OperableValue for EvalException {
@@ -335,6 +394,17 @@ impl ObjectValue for Exception {
     {
         self.clone().err()
     }
+
+    fn index(self, _index: BergVal) -> EvalResult {
+        self.err()
+    }
+
+    fn set_index(&mut self, _index: BergVal, _value: BergVal) -> Result<(), EvalException>
+    where
+        Self: Clone,
+    {
+        self.clone().err()
+    }
 }
 
 impl OperableValue for Exception {
@@ -430,6 +500,20 @@ impl ObjectValue for CaughtException {
     {
         self.0.value.set_field(name, value)
     }
+
+    fn index(self, index: BergVal) -> EvalResult
+    where
+        Self: Sized,
+    {
+        self.0.value.index(index)
+    }
+
+    fn set_index(&mut self, index: BergVal, value: BergVal) -> Result<(), EvalException>
+    where
+        Self: Clone,
+    {
+        self.0.value.set_index(index, value)
+    }
 }
 
 impl OperableValue for CaughtException {
@@ -12,7 +12,7 @@ impl ExpressionRef {
     pub fn new(ast: AstRef, root: AstIndex) -> Self {
         ExpressionRef { ast, root }
     }
-    pub fn expression(&self) -> ExpressionTreeWalker {
+    pub fn expression(&self) -> ExpressionTreeWalker<'_> {
         ExpressionTreeWalker::basic(&self.ast, self.root)
     }
     pub fn at_position(mut self, position: ExpressionPosition) -> Self {
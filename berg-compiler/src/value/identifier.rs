@@ -62,6 +62,17 @@ impl ObjectValue for IdentifierIndex {
     ) -> Result<(), EvalException> {
         default_set_field(self, name, value)
     }
+
+    fn index(self, index: BergVal) -> EvalResult
+    where
+        Self: Sized,
+    {
+        default_index(self, index)
+    }
+
+    fn set_index(&mut self, index: BergVal, value: BergVal) -> Result<(), EvalException> {
+        default_set_index(self, index, value)
+    }
 }
 
 impl OperableValue for IdentifierIndex {
@@ -2,14 +2,19 @@ mod berg_val;
 mod berg_value;
 mod boolean;
 mod compiler_error;
+mod complex;
+mod dsl;
 mod eval_val;
 mod exception;
 mod expression;
 mod identifier;
 mod macros;
+mod range;
 mod rational;
 mod root;
 mod source;
+mod string;
+mod tracer;
 mod tuple;
 
 pub use self::berg_val::{empty_tuple, BergResult, BergVal};
@@ -18,10 +23,16 @@ pub use self::berg_value::{
     RightOperand, TryFromBergVal, Value,
 };
 pub use self::compiler_error::{CompilerError, CompilerErrorCode};
+pub use self::complex::BigComplex;
+pub use self::dsl::{DslError, DslHandler, DslResult, DslText};
 pub use self::eval_val::{AssignmentTarget, EvalResult, EvalVal};
-pub use self::exception::{CaughtException, EvalException, Exception};
+pub use self::exception::{CaughtException, ErrorLocation, EvalException, Exception};
+pub use self::range::Range;
 pub use self::root::RootRef;
 pub use self::source::{AstRef, SourceRoot, SourceSpec};
+pub use self::tracer::{
+    JsonTracer, NoTracer, TextTracer, TraceEvent, TracedExpression, Tracer,
+};
 pub use self::tuple::Tuple;
 // Export types used in definition of BergValue and BergVal
 pub use berg_parser::{ExpressionBoundary, IdentifierIndex};
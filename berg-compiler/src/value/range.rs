@@ -0,0 +1,252 @@
+use crate::value::implement::*;
+use berg_parser::IdentifierIndex;
+use num::{BigRational, One, ToPrimitive};
+use std::fmt;
+
+///
+/// A range of numbers or characters: `1..10` and `"a".."z"` include their
+/// end, and `1...10` and `"a"..."z"` don't (as in Ruby).
+///
+/// Ranges are lazy: `foreach 1..1000000 { ... }` produces one value at a time
+/// rather than building a [`Tuple`] with all of them.
+///
+#[derive(Debug, Clone, PartialEq)]
+pub struct Range {
+    bounds: RangeBounds,
+    inclusive: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum RangeBounds {
+    Numbers(BigRational, BigRational),
+    Characters(char, char),
+}
+
+impl Range {
+    pub fn numbers(start: BigRational, end: BigRational, inclusive: bool) -> Self {
+        Range {
+            bounds: RangeBounds::Numbers(start, end),
+            inclusive,
+        }
+    }
+    pub fn characters(start: char, end: char, inclusive: bool) -> Self {
+        Range {
+            bounds: RangeBounds::Characters(start, end),
+            inclusive,
+        }
+    }
+
+    ///
+    /// Whether `value` is in the range: `x in 1..10`.
+    ///
+    /// Unlike iterating, this doesn't just check the steps: `1.5 in 1..10` is
+    /// true.
+    ///
+    pub fn contains(&self, value: &BergVal) -> bool {
+        use RangeBounds::*;
+        match (&self.bounds, value) {
+            (Numbers(start, end), BergVal::BigRational(value)) => {
+                start <= value && is_before_end(value, end, self.inclusive)
+            }
+            (Characters(start, end), BergVal::String(value)) => {
+                match single_char(value) {
+                    Some(value) => *start <= value && is_before_end(&value, end, self.inclusive),
+                    None => false,
+                }
+            }
+            _ => false,
+        }
+    }
+
+    ///
+    /// The start and end of an integer range, and whether the end is included,
+    /// for slices like `a[1..3]`. Returns `None` if either bound isn't an
+    /// integer.
+    ///
+    pub fn integer_bounds(&self) -> Option<(isize, isize, bool)> {
+        match &self.bounds {
+            RangeBounds::Numbers(start, end) if start.is_integer() && end.is_integer() => Some((
+                start.to_integer().to_isize()?,
+                end.to_integer().to_isize()?,
+                self.inclusive,
+            )),
+            _ => None,
+        }
+    }
+}
+
+fn is_before_end<T: PartialOrd>(value: &T, end: &T, inclusive: bool) -> bool {
+    if inclusive {
+        value <= end
+    } else {
+        value < end
+    }
+}
+
+///
+/// The character in a single-character string like `"a"`.
+///
+pub(crate) fn single_char(string: &str) -> Option<char> {
+    let mut chars = string.chars();
+    match (chars.next(), chars.next()) {
+        (Some(ch), None) => Some(ch),
+        _ => None,
+    }
+}
+
+///
+/// The character after `ch`, skipping the surrogates (which aren't
+/// characters).
+///
+fn next_char(ch: char) -> Option<char> {
+    match ch {
+        '\u{D7FF}' => Some('\u{E000}'),
+        ch => char::from_u32(ch as u32 + 1),
+    }
+}
+
+impl BergValue for Range {}
+
+impl EvaluatableValue for Range {
+    fn evaluate(self) -> BergResult
+    where
+        Self: Sized,
+    {
+        self.ok()
+    }
+}
+
+impl Value for Range {
+    fn lazy_val(self) -> Result<BergVal, EvalException>
+    where
+        Self: Sized,
+    {
+        self.ok()
+    }
+    fn eval_val(self) -> EvalResult
+    where
+        Self: Sized,
+    {
+        self.ok()
+    }
+    fn into_native<T: TryFromBergVal>(self) -> Result<T, EvalException> {
+        default_into_native(self)
+    }
+    fn try_into_native<T: TryFromBergVal>(self) -> Result<Option<T>, EvalException> {
+        default_try_into_native(self)
+    }
+    fn display(&self) -> &dyn fmt::Display {
+        self
+    }
+}
+
+impl IteratorValue for Range {
+    fn next_val(self) -> Result<NextVal, EvalException> {
+        use RangeBounds::*;
+        let Range { bounds, inclusive } = self;
+        match bounds {
+            Numbers(start, end) if is_before_end(&start, &end, inclusive) => NextVal {
+                head: Some(start.clone().into()),
+                tail: Range::numbers(start + BigRational::one(), end, inclusive).into(),
+            }
+            .ok(),
+            Characters(start, end) if is_before_end(&start, &end, inclusive) => NextVal {
+                head: Some(start.to_string().into()),
+                tail: match next_char(start) {
+                    Some(next) => Range::characters(next, end, inclusive).into(),
+                    None => empty_tuple(),
+                },
+            }
+            .ok(),
+            Numbers(..) | Characters(..) => NextVal::none(empty_tuple()).ok(),
+        }
+    }
+}
+
+impl ObjectValue for Range {
+    fn field(self, name: IdentifierIndex) -> EvalResult
+    where
+        Self: Sized,
+    {
+        default_field(self, name)
+    }
+    fn set_field(
+        &mut self,
+        name: IdentifierIndex,
+        value: BergVal,
+    ) -> Result<(), EvalException> {
+        default_set_field(self, name, value)
+    }
+    fn index(self, index: BergVal) -> EvalResult
+    where
+        Self: Sized,
+    {
+        default_index(self, index)
+    }
+    fn set_index(&mut self, index: BergVal, value: BergVal) -> Result<(), EvalException> {
+        default_set_index(self, index, value)
+    }
+}
+
+impl OperableValue for Range {
+    fn infix(
+        self,
+        operator: IdentifierIndex,
+        right: RightOperand<impl EvaluatableValue>,
+    ) -> EvalResult
+    where
+        Self: Sized,
+    {
+        default_infix(self, operator, right)
+    }
+    fn infix_assign(
+        self,
+        operator: IdentifierIndex,
+        right: RightOperand<impl EvaluatableValue>,
+    ) -> EvalResult
+    where
+        Self: Sized,
+    {
+        default_infix_assign(self, operator, right)
+    }
+    fn postfix(self, operator: IdentifierIndex) -> EvalResult
+    where
+        Self: Sized,
+    {
+        default_postfix(self, operator)
+    }
+    fn prefix(self, operator: IdentifierIndex) -> EvalResult
+    where
+        Self: Sized,
+    {
+        default_prefix(self, operator)
+    }
+    fn subexpression_result(self, boundary: ExpressionBoundary) -> EvalResult
+    where
+        Self: Sized,
+    {
+        default_subexpression_result(self, boundary)
+    }
+}
+
+impl fmt::Display for Range {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let operator = if self.inclusive { ".." } else { "..." };
+        match &self.bounds {
+            RangeBounds::Numbers(start, end) => write!(f, "{}{}{}", start, operator, end),
+            RangeBounds::Characters(start, end) => write!(f, "{}{}{}", start, operator, end),
+        }
+    }
+}
+
+impl From<Range> for BergVal {
+    fn from(from: Range) -> Self {
+        BergVal::Range(Box::new(from))
+    }
+}
+
+impl From<Range> for EvalVal {
+    fn from(from: Range) -> Self {
+        BergVal::from(from).into()
+    }
+}
@@ -2,6 +2,7 @@ use crate::value::implement::*;
 use berg_parser::identifiers::*;
 use berg_parser::IdentifierIndex;
 use num::{BigInt, BigRational, One, ToPrimitive, Zero};
+use std::cmp::Ordering;
 
 impl BergValue for BigRational {}
 
@@ -58,6 +59,17 @@ impl ObjectValue for BigRational {
     ) -> Result<(), EvalException> {
         default_set_field(self, name, value)
     }
+
+    fn index(self, index: BergVal) -> EvalResult
+    where
+        Self: Sized,
+    {
+        default_index(self, index)
+    }
+
+    fn set_index(&mut self, index: BergVal, value: BergVal) -> Result<(), EvalException> {
+        default_set_index(self, index, value)
+    }
 }
 
 impl OperableValue for BigRational {
@@ -69,27 +81,41 @@ impl OperableValue for BigRational {
     where
         Self: Sized,
     {
+        // Arithmetic stays rational unless the right side is imaginary
+        // (1 + 2i); results with no imaginary part come back as rationals.
         match operator {
-            PLUS => (self + right.into_native::<BigRational>()?).ok(),
-            DASH => (self - right.into_native::<BigRational>()?).ok(),
-            SLASH => {
-                let right = right.into_native::<BigRational>()?;
-                if right.is_zero() {
+            PLUS => match right.into_native::<Number>()? {
+                Number::Rational(right) => (self + right).ok(),
+                Number::Complex(right) => (BigComplex::from(self) + right).ok(),
+            },
+            DASH => match right.into_native::<Number>()? {
+                Number::Rational(right) => (self - right).ok(),
+                Number::Complex(right) => (BigComplex::from(self) - right).ok(),
+            },
+            SLASH => match right.into_native::<Number>()? {
+                Number::Rational(right) if right.is_zero() => {
                     CompilerError::DivideByZero.operand_err(Right)
-                } else {
-                    (self / right).ok()
                 }
-            }
-            STAR => (self * right.into_native::<BigRational>()?).ok(),
+                Number::Rational(right) => (self / right).ok(),
+                Number::Complex(right) => (BigComplex::from(self) / right).ok(),
+            },
+            STAR => match right.into_native::<Number>()? {
+                Number::Rational(right) => (self * right).ok(),
+                Number::Complex(right) => (BigComplex::from(self) * right).ok(),
+            },
+            // Complex values always have an imaginary part, so they never
+            // equal a rational.
             EQUAL_TO => match right.try_into_native::<BigRational>()? {
                 Some(right) => self == right,
                 None => false,
             }
             .ok(),
-            GREATER_THAN => (self > right.into_native::<BigRational>()?).ok(),
-            LESS_THAN => (self < right.into_native::<BigRational>()?).ok(),
-            GREATER_EQUAL => (self >= right.into_native::<BigRational>()?).ok(),
-            LESS_EQUAL => (self <= right.into_native::<BigRational>()?).ok(),
+            COMPARE => self.cmp(&right.into_native::<BigRational>()?).ok(),
+            // 1..10
+            DOT_DOT | DOT_DOT_DOT => {
+                let end = right.into_native::<BigRational>()?;
+                Range::numbers(self, end, operator == DOT_DOT).ok()
+            }
             _ => default_infix(self, operator, right),
         }
     }
@@ -171,6 +197,33 @@ impl TryFromBergVal for BigRational {
     }
 }
 
+///
+/// The result of `<=>`: -1, 0 or 1.
+///
+impl TryFromBergVal for Ordering {
+    const TYPE_NAME: &'static str = "number";
+    fn try_from_berg_val(
+        from: EvalVal,
+    ) -> Result<Result<Self, BergVal>, EvalException> {
+        match from.lazy_val()? {
+            BergVal::BigRational(value) => Ok(Ok(value.cmp(&BigRational::zero()))),
+            from => Ok(Err(from)),
+        }
+    }
+}
+
+impl From<Ordering> for BergVal {
+    fn from(from: Ordering) -> Self {
+        (from as i8).into()
+    }
+}
+
+impl From<Ordering> for EvalVal {
+    fn from(from: Ordering) -> Self {
+        BergVal::from(from).into()
+    }
+}
+
 macro_rules! impl_berg_val_for_primitive_num {
     ($($type:ty: $to:tt),*) => {
         $(
@@ -206,3 +259,24 @@ macro_rules! impl_berg_val_for_primitive_num {
 }
 
 impl_berg_val_for_primitive_num! { u64: to_u64, i64: to_i64, u32: to_u32, i32: to_i32, u16: to_u16, i16: to_i16, u8: to_u8, i8: to_i8, usize: to_usize, isize: to_isize }
+
+///
+/// The right operand of rational arithmetic: either another rational or a
+/// complex number (`1 + 2i`).
+///
+#[derive(Debug)]
+enum Number {
+    Rational(BigRational),
+    Complex(BigComplex),
+}
+
+impl TryFromBergVal for Number {
+    const TYPE_NAME: &'static str = "number";
+    fn try_from_berg_val(from: EvalVal) -> Result<Result<Self, BergVal>, EvalException> {
+        match from.lazy_val()? {
+            BergVal::BigRational(value) => Ok(Ok(Number::Rational(value))),
+            BergVal::Complex(value) => Ok(Ok(Number::Complex(*value))),
+            from => Ok(Err(from)),
+        }
+    }
+}
@@ -1,11 +1,14 @@
+use expression::ExpressionRef;
 use source::SourceRoot;
 
 use crate::value::*;
 use berg_parser::identifiers::keywords;
-use berg_parser::{FieldIndex, IdentifierIndex};
+use berg_parser::{ColumnUnit, FieldIndex, IdentifierIndex, OperatorTable};
 use berg_util::to_indexed_cow;
 
 use std::borrow::Cow;
+use std::cell::RefCell;
+use std::collections::HashSet;
 use std::fmt;
 use std::io;
 use std::io::Write;
@@ -19,8 +22,16 @@ struct RootData {
     root: SourceRoot,
     #[allow(dead_code)]
     out: Box<dyn Write>,
-    #[allow(dead_code)]
-    err: Box<dyn Write>,
+    err: RefCell<Box<dyn Write>>,
+    tracer: Box<dyn Tracer>,
+    ///
+    /// The warnings already reported, so each is only reported once.
+    ///
+    warnings: RefCell<HashSet<String>>,
+    ///
+    /// The registered DSLs, in the order they were registered.
+    ///
+    dsls: RefCell<Vec<(String, DslHandler)>>,
 }
 
 impl Default for RootRef {
@@ -34,7 +45,23 @@ impl Default for RootRef {
 //
 impl RootRef {
     pub fn new(root: SourceRoot, out: Box<dyn Write>, err: Box<dyn Write>) -> Self {
-        RootRef(Rc::new(RootData { root, out, err }))
+        RootRef::with_tracer(root, out, err, Box::new(NoTracer))
+    }
+
+    pub fn with_tracer(
+        root: SourceRoot,
+        out: Box<dyn Write>,
+        err: Box<dyn Write>,
+        tracer: Box<dyn Tracer>,
+    ) -> Self {
+        RootRef(Rc::new(RootData {
+            root,
+            out,
+            err: RefCell::new(err),
+            tracer,
+            warnings: Default::default(),
+            dsls: Default::default(),
+        }))
     }
 
     pub fn root(&self) -> &SourceRoot {
@@ -42,10 +69,111 @@ impl RootRef {
     }
 
     pub fn from_env() -> Self {
-        let root_path = SourceRoot::from_env();
+        RootRef::from_env_with_tracer(Box::new(NoTracer))
+    }
+
+    pub fn from_env_with_tracer(tracer: Box<dyn Tracer>) -> Self {
+        RootRef::from_env_with_columns(tracer, ColumnUnit::Bytes)
+    }
+
+    ///
+    /// The root from the environment, counting columns in `columns` in the
+    /// locations it reports.
+    ///
+    pub fn from_env_with_columns(tracer: Box<dyn Tracer>, columns: ColumnUnit) -> Self {
+        RootRef::from_env_with_args(tracer, columns, vec![])
+    }
+
+    ///
+    /// The root from the environment, like [`RootRef::from_env_with_columns()`],
+    /// with `args` as the program's arguments.
+    ///
+    pub fn from_env_with_args(
+        tracer: Box<dyn Tracer>,
+        columns: ColumnUnit,
+        args: Vec<String>,
+    ) -> Self {
+        let root_path = SourceRoot::from_env().with_columns(columns).with_args(args);
         let out = Box::new(io::stdout());
         let err = Box::new(io::stderr());
-        RootRef::new(root_path, out, err)
+        RootRef::with_tracer(root_path, out, err, tracer)
+    }
+
+    ///
+    /// The tracer watching evaluation, or `None` if nobody is watching.
+    ///
+    pub fn tracer(&self) -> Option<&dyn Tracer> {
+        let tracer = self.0.tracer.as_ref();
+        if tracer.is_enabled() {
+            Some(tracer)
+        } else {
+            None
+        }
+    }
+
+    ///
+    /// Reports something that is probably a mistake, but doesn't stop
+    /// evaluation (like a match arm that can never match). The warning goes to
+    /// the tracer and the error stream, once per expression.
+    ///
+    pub fn warn(&self, expression: &ExpressionRef, message: impl fmt::Display) {
+        let traced = expression.traced();
+        let message = message.to_string();
+        let line = match traced.location {
+            Some(location) => format!(
+                "warning: {}:{}: {}",
+                expression.ast.source.name(),
+                location,
+                message
+            ),
+            None => format!("warning: {}: {}", expression.ast.source.name(), message),
+        };
+        if !self.0.warnings.borrow_mut().insert(line.clone()) {
+            return;
+        }
+        if let Some(tracer) = self.tracer() {
+            tracer.trace(&TraceEvent::Warning {
+                expression: traced,
+                message: &message,
+            });
+        }
+        // Warnings must not change the result, so write errors are ignored.
+        let _ = writeln!(self.0.err.borrow_mut(), "{}", line);
+    }
+
+    ///
+    /// Registers a DSL. In sources parsed after this, `name:` followed by an
+    /// indented block is kept as text, and `handler` turns it into a value
+    /// when it is evaluated. Registering a name again replaces its handler.
+    ///
+    /// A Berg handler is parsed here, once.
+    ///
+    pub fn register_dsl(&self, name: impl Into<String>, handler: DslHandler) {
+        let name = name.into();
+        let handler = handler.parse(self, &name);
+        let mut dsls = self.0.dsls.borrow_mut();
+        match dsls.iter_mut().find(|(registered, _)| *registered == name) {
+            Some((_, registered)) => *registered = handler,
+            None => dsls.push((name, handler)),
+        }
+    }
+
+    pub fn dsl(&self, name: &str) -> Option<DslHandler> {
+        let dsls = self.0.dsls.borrow();
+        let (_, handler) = dsls.iter().find(|(registered, _)| registered == name)?;
+        Some(handler.clone())
+    }
+
+    ///
+    /// The operators to parse with: the source root's, with the registered
+    /// DSLs declared as text blocks.
+    ///
+    pub fn operators(&self) -> OperatorTable {
+        let mut operators = self.root().operators().clone();
+        for (name, _) in self.0.dsls.borrow().iter() {
+            operators.declare_text_block(name);
+        }
+        operators
     }
 
     pub fn parse_file(&self, path: impl Into<Cow<'static, Path>>) -> AstRef {
@@ -54,7 +182,7 @@ impl RootRef {
             Ok(buffer) => AstRef::new(
                 self.clone(),
                 SourceSpec::File(source),
-                berg_parser::parse(buffer),
+                self.root().parse(buffer, self.operators()),
             ),
             Err(error) => AstRef::new_error(self.clone(), SourceSpec::File(source), error),
         }
@@ -66,7 +194,7 @@ impl RootRef {
         buffer: impl Into<Cow<'static, [u8]>>,
     ) -> AstRef {
         let source = SourceSpec::Memory(name.into());
-        let ast = berg_parser::parse(to_indexed_cow(buffer.into()));
+        let ast = berg_parser::parse_with_operators(to_indexed_cow(buffer.into()), self.operators());
         AstRef::new(self.clone(), source, ast)
     }
 
@@ -86,7 +214,13 @@ impl RootRef {
     }
 
     pub fn local_field(&self, index: FieldIndex) -> EvalResult {
-        keyword_value(index)
+        match index {
+            keywords::ARGS => {
+                let args = self.root().args().iter().map(|arg| arg.clone().into());
+                BergVal::from_iter(args).ok()
+            }
+            _ => keyword_value(index),
+        }
     }
 
     #[allow(clippy::needless_pass_by_value)]
@@ -120,6 +254,7 @@ fn keyword_value(index: FieldIndex) -> EvalResult {
         keywords::CATCH => Catch.ok(),
         keywords::FINALLY => Finally.ok(),
         keywords::THROW => Throw.ok(),
+        keywords::MATCH => Match.ok(),
         _ => unreachable!(),
     }
 }
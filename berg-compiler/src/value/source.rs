@@ -6,13 +6,37 @@ use std::path::Path;
 use std::{borrow::Cow, rc::Rc};
 use std::{env, io};
 
-use berg_parser::{Ast, ByteIndex, ByteSlice};
+use berg_parser::{Ast, ByteIndex, ByteSlice, ColumnUnit, OperatorTable, ParseCache};
 
 use super::compiler_error::SourceLoadError;
 use super::RootRef;
 
 #[derive(Debug)]
-pub struct SourceRoot(Result<Cow<'static, Path>, SourceLoadError>);
+pub struct SourceRoot {
+    path: Result<Cow<'static, Path>, SourceLoadError>,
+    ///
+    /// Where to cache parsed files, if anywhere.
+    ///
+    cache: Option<ParseCache>,
+    ///
+    /// The operators declared for everything parsed under this root.
+    ///
+    operators: OperatorTable,
+    ///
+    /// What columns count in the locations reported for sources under this
+    /// root.
+    ///
+    columns: ColumnUnit,
+    ///
+    /// The arguments the program was run with (the root's `args`).
+    ///
+    args: Vec<String>,
+}
+
+///
+/// The environment variable naming the directory to cache parsed files in.
+///
+pub const CACHE_DIR_VAR: &str = "BERG_CACHE_DIR";
 
 ///
 /// Specification of where we got source from.
@@ -36,30 +60,116 @@ pub struct AstRef(Rc<AstData>);
 pub struct AstData {
     pub root: RootRef,
     pub source: SourceSpec,
-    pub ast: Ast,
+    ///
+    /// Shared, so a source parsed once (like a DSL handler) can be run again.
+    ///
+    pub ast: Rc<Ast>,
     pub error: Option<SourceLoadError>,
 }
 
 impl SourceRoot {
     pub fn new(root_path: Cow<'static, Path>) -> Self {
-        Self(Ok(root_path))
+        Self {
+            path: Ok(root_path),
+            cache: None,
+            operators: Default::default(),
+            columns: Default::default(),
+            args: Default::default(),
+        }
     }
 
     pub fn new_error(error: io::Error) -> Self {
-        Self(Err(SourceLoadError::CurrentDirectoryError(Rc::new(error))))
+        Self {
+            path: Err(SourceLoadError::CurrentDirectoryError(Rc::new(error))),
+            cache: None,
+            operators: Default::default(),
+            columns: Default::default(),
+            args: Default::default(),
+        }
     }
 
+    ///
+    /// The current directory, caching parsed files in `$BERG_CACHE_DIR` if it
+    /// is set.
+    ///
     pub fn from_env() -> Self {
-        let result = match env::current_dir() {
+        let path = match env::current_dir() {
             Ok(current_dir) => Ok(current_dir.into()),
             Err(io_error) => Err(SourceLoadError::CurrentDirectoryError(Rc::new(io_error))),
         };
-        Self(result)
+        let cache = env::var_os(CACHE_DIR_VAR)
+            .filter(|dir| !dir.is_empty())
+            .map(ParseCache::new);
+        Self {
+            path,
+            cache,
+            operators: Default::default(),
+            columns: Default::default(),
+            args: Default::default(),
+        }
+    }
+
+    pub fn with_cache(self, cache: ParseCache) -> Self {
+        Self {
+            cache: Some(cache),
+            ..self
+        }
+    }
+
+    pub fn cache(&self) -> Option<&ParseCache> {
+        self.cache.as_ref()
+    }
+
+    ///
+    /// Declares operators for everything parsed under this root. Values
+    /// implement them in their `infix()`, using the identifiers the table
+    /// gave them.
+    ///
+    pub fn with_operators(self, operators: OperatorTable) -> Self {
+        Self { operators, ..self }
+    }
+
+    pub fn operators(&self) -> &OperatorTable {
+        &self.operators
+    }
+
+    ///
+    /// Counts columns in `columns` when reporting locations (in errors,
+    /// warnings and traces). The default is bytes.
+    ///
+    pub fn with_columns(self, columns: ColumnUnit) -> Self {
+        Self { columns, ..self }
+    }
+
+    pub fn columns(&self) -> ColumnUnit {
+        self.columns
+    }
+
+    ///
+    /// Gives the program arguments, which it reads as the tuple `args`.
+    ///
+    pub fn with_args(self, args: Vec<String>) -> Self {
+        Self { args, ..self }
+    }
+
+    pub fn args(&self) -> &[String] {
+        &self.args
+    }
+
+    ///
+    /// Parses a source file with the given operators (generally these, plus
+    /// the root's DSLs), using the cache if there is one.
+    ///
+    pub fn parse(&self, buffer: Cow<'static, ByteSlice>, operators: OperatorTable) -> Ast {
+        match &self.cache {
+            Some(cache) => cache.parse_with_operators(buffer, operators),
+            None => berg_parser::parse_with_operators(buffer, operators),
+        }
     }
 
     pub fn resolve(&self, user_path: Cow<'static, Path>) -> SourceFileSpec {
         let resolved_path = if user_path.is_relative() {
-            match &self.0 {
+            match &self.path {
                 Ok(root_path) => Ok(root_path.join(&user_path).into()),
                 Err(error) => Err(error.clone()),
             }
@@ -74,7 +184,7 @@ impl SourceRoot {
 }
 
 impl SourceSpec {
-    pub fn name(&self) -> Cow<str> {
+    pub fn name(&self) -> Cow<'_, str> {
         match self {
             SourceSpec::File(source) => source.name(),
             SourceSpec::Memory(name) => name.clone(),
@@ -93,7 +203,7 @@ impl SourceSpec {
 }
 
 impl SourceFileSpec {
-    pub fn name(&self) -> Cow<str> {
+    pub fn name(&self) -> Cow<'_, str> {
         self.user_path.to_string_lossy()
     }
 
@@ -136,11 +246,11 @@ impl SourceFileSpec {
 }
 
 impl AstRef {
-    pub fn new(root: RootRef, source: SourceSpec, ast: Ast) -> Self {
+    pub fn new(root: RootRef, source: SourceSpec, ast: impl Into<Rc<Ast>>) -> Self {
         Self(Rc::new(AstData {
             root,
             source,
-            ast,
+            ast: ast.into(),
             error: None,
         }))
     }
@@ -149,7 +259,7 @@ impl AstRef {
         Self(Rc::new(AstData {
             root,
             source,
-            ast: Ast::default(),
+            ast: Rc::new(Ast::default()),
             error: Some(error),
         }))
     }
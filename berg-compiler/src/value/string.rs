@@ -0,0 +1,210 @@
+use super::range::single_char;
+use crate::value::implement::*;
+use berg_parser::identifiers::*;
+
+impl BergValue for String {}
+
+impl EvaluatableValue for String {
+    fn evaluate(self) -> BergResult
+    where
+        Self: Sized,
+    {
+        self.ok()
+    }
+}
+
+impl Value for String {
+    fn lazy_val(self) -> Result<BergVal, EvalException>
+    where
+        Self: Sized,
+    {
+        self.ok()
+    }
+
+    fn eval_val(self) -> EvalResult
+    where
+        Self: Sized,
+    {
+        self.ok()
+    }
+
+    fn into_native<T: TryFromBergVal>(self) -> Result<T, EvalException> {
+        default_into_native(self)
+    }
+
+    fn try_into_native<T: TryFromBergVal>(self) -> Result<Option<T>, EvalException> {
+        default_try_into_native(self)
+    }
+
+    fn display(&self) -> &dyn std::fmt::Display {
+        self
+    }
+}
+
+impl IteratorValue for String {
+    fn next_val(self) -> Result<NextVal, EvalException> {
+        single_next_val(self)
+    }
+}
+
+impl ObjectValue for String {
+    fn field(self, name: IdentifierIndex) -> EvalResult
+    where
+        Self: Sized,
+    {
+        default_field(self, name)
+    }
+
+    fn set_field(
+        &mut self,
+        name: IdentifierIndex,
+        value: BergVal,
+    ) -> Result<(), EvalException> {
+        default_set_field(self, name, value)
+    }
+
+    ///
+    /// Strings are indexed by character, so `"abc"[1]` is `"b"`.
+    ///
+    fn index(self, index: BergVal) -> EvalResult
+    where
+        Self: Sized,
+    {
+        let chars: Vec<char> = self.chars().collect();
+        match sequence_index(&index, chars.len())? {
+            Some(SequenceIndex::At(at)) => chars[at].to_string().ok(),
+            Some(SequenceIndex::Slice(range)) => chars[range].iter().collect::<String>().ok(),
+            None => CompilerError::IndexOutOfRange(Box::new(self), Box::new(index)).err(),
+        }
+    }
+
+    fn set_index(&mut self, index: BergVal, value: BergVal) -> Result<(), EvalException> {
+        let mut chars: Vec<char> = self.chars().collect();
+        let range = match sequence_index(&index, chars.len())? {
+            Some(SequenceIndex::At(at)) => at..at + 1,
+            Some(SequenceIndex::Slice(range)) => range,
+            None => {
+                return CompilerError::IndexOutOfRange(Box::new(self.clone()), Box::new(index))
+                    .err();
+            }
+        };
+        let value = value.into_native::<String>()?;
+        chars.splice(range, value.chars());
+        *self = chars.into_iter().collect();
+        Ok(())
+    }
+}
+
+impl OperableValue for String {
+    fn infix(
+        self,
+        operator: IdentifierIndex,
+        right: RightOperand<impl EvaluatableValue>,
+    ) -> EvalResult
+    where
+        Self: Sized,
+    {
+        match operator {
+            PLUS => (self + &right.into_native::<String>()?).ok(),
+            EQUAL_TO => match right.try_into_native::<String>()? {
+                Some(right) => self == right,
+                None => false,
+            }
+            .ok(),
+            COMPARE => self.cmp(&right.into_native::<String>()?).ok(),
+            // "a".."z"
+            DOT_DOT | DOT_DOT_DOT => match single_char(&self) {
+                Some(start) => {
+                    let end = right.into_native::<char>()?;
+                    Range::characters(start, end, operator == DOT_DOT).ok()
+                }
+                None => CompilerError::BadOperandType(Box::new(self), char::TYPE_NAME)
+                    .operand_err(Left),
+            },
+            _ => default_infix(self, operator, right),
+        }
+    }
+
+    fn infix_assign(
+        self,
+        operator: IdentifierIndex,
+        right: RightOperand<impl EvaluatableValue>,
+    ) -> EvalResult
+    where
+        Self: Sized,
+    {
+        default_infix_assign(self, operator, right)
+    }
+
+    fn prefix(self, operator: IdentifierIndex) -> EvalResult
+    where
+        Self: Sized,
+    {
+        default_prefix(self, operator)
+    }
+
+    fn postfix(self, operator: IdentifierIndex) -> EvalResult
+    where
+        Self: Sized,
+    {
+        default_postfix(self, operator)
+    }
+
+    fn subexpression_result(self, boundary: ExpressionBoundary) -> EvalResult
+    where
+        Self: Sized,
+    {
+        default_subexpression_result(self, boundary)
+    }
+}
+
+impl TryFromBergVal for String {
+    const TYPE_NAME: &'static str = "string";
+    fn try_from_berg_val(
+        from: EvalVal,
+    ) -> Result<Result<Self, BergVal>, EvalException> {
+        match from.lazy_val()? {
+            BergVal::String(value) => Ok(Ok(value)),
+            from => Ok(Err(from)),
+        }
+    }
+}
+
+impl TryFromBergVal for char {
+    const TYPE_NAME: &'static str = "character";
+    fn try_from_berg_val(
+        from: EvalVal,
+    ) -> Result<Result<Self, BergVal>, EvalException> {
+        match from.lazy_val()? {
+            BergVal::String(value) => match single_char(&value) {
+                Some(ch) => Ok(Ok(ch)),
+                None => Ok(Err(BergVal::String(value))),
+            },
+            from => Ok(Err(from)),
+        }
+    }
+}
+
+impl From<String> for BergVal {
+    fn from(from: String) -> Self {
+        BergVal::String(from)
+    }
+}
+
+impl From<String> for EvalVal {
+    fn from(from: String) -> Self {
+        BergVal::from(from).into()
+    }
+}
+
+impl From<&str> for BergVal {
+    fn from(from: &str) -> Self {
+        BergVal::String(from.into())
+    }
+}
+
+impl From<&str> for EvalVal {
+    fn from(from: &str) -> Self {
+        BergVal::from(from).into()
+    }
+}
@@ -0,0 +1,360 @@
+use super::implement::ExpressionRef;
+use berg_parser::LineColumnRange;
+use std::cell::RefCell;
+use std::fmt;
+use std::io::Write;
+
+///
+/// Watches evaluation as it happens.
+///
+/// A tracer is set on the [`RootRef`](super::RootRef), and receives a
+/// [`TraceEvent`] whenever a block starts or completes, an expression is
+/// evaluated, a field is declared or set, an exception is thrown or caught, or
+/// a warning is reported.
+///
+pub trait Tracer {
+    fn trace(&self, event: &TraceEvent);
+
+    ///
+    /// Whether this tracer wants events at all. If it doesn't, the evaluator
+    /// doesn't spend time creating them.
+    ///
+    fn is_enabled(&self) -> bool {
+        true
+    }
+}
+
+///
+/// Something that happened during evaluation.
+///
+pub enum TraceEvent<'a> {
+    ///
+    /// A block started running.
+    ///
+    BlockStart { block: TracedExpression<'a> },
+    ///
+    /// A block finished running.
+    ///
+    BlockComplete {
+        block: TracedExpression<'a>,
+        result: &'a dyn fmt::Display,
+    },
+    ///
+    /// An expression was evaluated.
+    ///
+    ExpressionEvaluated {
+        expression: TracedExpression<'a>,
+        value: &'a dyn fmt::Display,
+    },
+    ///
+    /// A field was declared in a block. Its value is the next input to the
+    /// block, if there was one.
+    ///
+    FieldDeclared {
+        block: TracedExpression<'a>,
+        name: &'a str,
+        value: Option<&'a dyn fmt::Display>,
+    },
+    ///
+    /// A field was set.
+    ///
+    FieldSet {
+        block: TracedExpression<'a>,
+        name: &'a str,
+        value: &'a dyn fmt::Display,
+    },
+    ///
+    /// An exception was thrown.
+    ///
+    ExceptionThrown {
+        expression: TracedExpression<'a>,
+        exception: &'a dyn fmt::Display,
+    },
+    ///
+    /// An exception was caught with `catch`.
+    ///
+    ExceptionCaught {
+        expression: TracedExpression<'a>,
+        exception: &'a dyn fmt::Display,
+    },
+    ///
+    /// Something that is probably a mistake was found, like a match arm that
+    /// can never match.
+    ///
+    Warning {
+        expression: TracedExpression<'a>,
+        message: &'a dyn fmt::Display,
+    },
+}
+
+///
+/// The expression (or block) a [`TraceEvent`] happened in.
+///
+pub struct TracedExpression<'a> {
+    ///
+    /// How deeply the expression is nested in its source.
+    ///
+    pub depth: usize,
+    ///
+    /// Where the expression is in its source (`None` if there is no source,
+    /// as when a file couldn't be loaded).
+    ///
+    pub location: Option<LineColumnRange>,
+    ///
+    /// The expression's source code.
+    ///
+    pub source: &'a dyn fmt::Display,
+}
+
+///
+/// The default tracer, which ignores everything.
+///
+#[derive(Debug, Default, Copy, Clone)]
+pub struct NoTracer;
+
+///
+/// Writes each event as an indented line of text.
+///
+/// Expressions are shortened to their first line, and to
+/// [`TEXT_TRACER_SOURCE_LENGTH`] characters; the location says where the
+/// rest is.
+///
+pub struct TextTracer(RefCell<Box<dyn Write>>);
+
+///
+/// The most characters of an expression's source that [`TextTracer`] writes.
+///
+pub const TEXT_TRACER_SOURCE_LENGTH: usize = 40;
+
+///
+/// Writes each event as a line of JSON (JSON lines).
+///
+/// Each line has `event`, `depth` and `location` properties, plus
+/// `expression`, `field`, `value`, `exception` and `message` depending on the
+/// event.
+///
+pub struct JsonTracer(RefCell<Box<dyn Write>>);
+
+impl<'a> TraceEvent<'a> {
+    ///
+    /// The event name, like `block_start` or `exception_thrown`.
+    ///
+    pub fn name(&self) -> &'static str {
+        use TraceEvent::*;
+        match self {
+            BlockStart { .. } => "block_start",
+            BlockComplete { .. } => "block_complete",
+            ExpressionEvaluated { .. } => "expression_evaluated",
+            FieldDeclared { .. } => "field_declared",
+            FieldSet { .. } => "field_set",
+            ExceptionThrown { .. } => "exception_thrown",
+            ExceptionCaught { .. } => "exception_caught",
+            Warning { .. } => "warning",
+        }
+    }
+
+    ///
+    /// The expression or block the event happened in.
+    ///
+    pub fn expression(&self) -> &TracedExpression<'a> {
+        use TraceEvent::*;
+        match self {
+            BlockStart { block }
+            | BlockComplete { block, .. }
+            | FieldDeclared { block, .. }
+            | FieldSet { block, .. } => block,
+            ExpressionEvaluated { expression, .. }
+            | ExceptionThrown { expression, .. }
+            | ExceptionCaught { expression, .. }
+            | Warning { expression, .. } => expression,
+        }
+    }
+}
+
+impl ExpressionRef {
+    pub fn traced(&self) -> TracedExpression<'_> {
+        let (depth, location) = if self.root < self.ast.tokens.len() {
+            let expression = self.expression();
+            let columns = self.ast.root.root().columns();
+            let location = self.ast.line_column_range(&expression.byte_range(), columns);
+            (expression.depth(), Some(location))
+        } else {
+            (0, None)
+        };
+        TracedExpression {
+            depth,
+            location,
+            source: self,
+        }
+    }
+}
+
+impl Tracer for NoTracer {
+    fn trace(&self, _event: &TraceEvent) {}
+    fn is_enabled(&self) -> bool {
+        false
+    }
+}
+
+impl TextTracer {
+    pub fn new(out: impl Write + 'static) -> Self {
+        TextTracer(RefCell::new(Box::new(out)))
+    }
+}
+
+impl Tracer for TextTracer {
+    fn trace(&self, event: &TraceEvent) {
+        use TraceEvent::*;
+        let expression = event.expression();
+        let mut line = "  ".repeat(expression.depth);
+        if let Some(location) = expression.location {
+            line.push_str(&format!("{} ", location));
+        }
+        let text = match event {
+            BlockStart { block } => format!("block {}", short_source(block.source)),
+            BlockComplete { block, result } => {
+                format!("block {} = {}", short_source(block.source), result)
+            }
+            ExpressionEvaluated { expression, value } => {
+                format!("{} = {}", short_source(expression.source), value)
+            }
+            FieldDeclared {
+                name,
+                value: Some(value),
+                ..
+            } => format!("declare {} = {}", name, value),
+            FieldDeclared {
+                name, value: None, ..
+            } => format!("declare {}", name),
+            FieldSet { name, value, .. } => format!("set {} = {}", name, value),
+            ExceptionThrown { exception, .. } => format!("throw {}", exception),
+            ExceptionCaught { exception, .. } => format!("catch {}", exception),
+            Warning { message, .. } => format!("warning {}", message),
+        };
+        // Keep each event on one line.
+        line.push_str(&text.replace('\n', "\\n"));
+        // Tracing must not change the result, so write errors are ignored.
+        let _ = writeln!(self.0.borrow_mut(), "{}", line);
+    }
+}
+
+///
+/// The first line of the source, cut off after [`TEXT_TRACER_SOURCE_LENGTH`]
+/// characters, with `...` if anything was left out.
+///
+/// This stops reading the source once it has enough, so tracing a big block
+/// doesn't mean writing out all of it.
+///
+fn short_source(source: &dyn fmt::Display) -> String {
+    struct ShortWriter {
+        text: String,
+        chars: usize,
+        is_cut: bool,
+    }
+    impl fmt::Write for ShortWriter {
+        fn write_str(&mut self, string: &str) -> fmt::Result {
+            for ch in string.chars() {
+                if ch == '\n' || ch == '\r' || self.chars == TEXT_TRACER_SOURCE_LENGTH {
+                    self.is_cut = true;
+                    // Stop formatting the rest of the source.
+                    return Err(fmt::Error);
+                }
+                self.text.push(ch);
+                self.chars += 1;
+            }
+            Ok(())
+        }
+    }
+    let mut writer = ShortWriter {
+        text: String::new(),
+        chars: 0,
+        is_cut: false,
+    };
+    let _ = fmt::Write::write_fmt(&mut writer, format_args!("{}", source));
+    if writer.is_cut {
+        writer.text.push_str("...");
+    }
+    writer.text
+}
+
+impl JsonTracer {
+    pub fn new(out: impl Write + 'static) -> Self {
+        JsonTracer(RefCell::new(Box::new(out)))
+    }
+}
+
+impl Tracer for JsonTracer {
+    fn trace(&self, event: &TraceEvent) {
+        use TraceEvent::*;
+        let expression = event.expression();
+        let mut line = format!(
+            "{{\"event\":\"{}\",\"depth\":{}",
+            event.name(),
+            expression.depth
+        );
+        if let Some(location) = expression.location {
+            write_json_property(&mut line, "location", &location);
+        }
+        match event {
+            BlockStart { block } => write_json_property(&mut line, "expression", block.source),
+            BlockComplete { block, result } => {
+                write_json_property(&mut line, "expression", block.source);
+                write_json_property(&mut line, "value", result);
+            }
+            ExpressionEvaluated { expression, value } => {
+                write_json_property(&mut line, "expression", expression.source);
+                write_json_property(&mut line, "value", value);
+            }
+            FieldDeclared { name, value, .. } => {
+                write_json_property(&mut line, "field", name);
+                if let Some(value) = value {
+                    write_json_property(&mut line, "value", value);
+                }
+            }
+            FieldSet { name, value, .. } => {
+                write_json_property(&mut line, "field", name);
+                write_json_property(&mut line, "value", value);
+            }
+            ExceptionThrown {
+                expression,
+                exception,
+            }
+            | ExceptionCaught {
+                expression,
+                exception,
+            } => {
+                write_json_property(&mut line, "expression", expression.source);
+                write_json_property(&mut line, "exception", exception);
+            }
+            Warning {
+                expression,
+                message,
+            } => {
+                write_json_property(&mut line, "expression", expression.source);
+                write_json_property(&mut line, "message", message);
+            }
+        }
+        line.push('}');
+        // Tracing must not change the result, so write errors are ignored.
+        let _ = writeln!(self.0.borrow_mut(), "{}", line);
+    }
+}
+
+///
+/// Appends `,"name":"value"` to a JSON object, escaping the value.
+///
+fn write_json_property(line: &mut String, name: &str, value: &dyn fmt::Display) {
+    line.push_str(&format!(",\"{}\":\"", name));
+    for ch in value.to_string().chars() {
+        match ch {
+            '"' => line.push_str("\\\""),
+            '\\' => line.push_str("\\\\"),
+            '\n' => line.push_str("\\n"),
+            '\r' => line.push_str("\\r"),
+            '\t' => line.push_str("\\t"),
+            ch if ch < ' ' => line.push_str(&format!("\\u{:04x}", ch as u32)),
+            ch => line.push(ch),
+        }
+    }
+    line.push('"');
+}
@@ -1,5 +1,7 @@
 use crate::value::implement::*;
-use berg_parser::IdentifierIndex;
+use berg_parser::identifiers::COMPARE;
+use berg_parser::{ExpressionPosition, IdentifierIndex};
+use std::cmp::Ordering;
 use std::fmt;
 use std::iter::FromIterator;
 
@@ -25,6 +27,56 @@ impl Tuple {
     pub fn is_empty(&self) -> bool {
         self.0.is_empty()
     }
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    ///
+    /// Compares tuples lexicographically: the first values that differ decide,
+    /// and if one tuple runs out first, it is the smaller one.
+    ///
+    /// If two values can't be compared, the error is at the value that
+    /// couldn't be compared (when the tuple is written out as `(a, b, c)`).
+    ///
+    pub fn compare(self, other: Tuple) -> Result<Ordering, EvalException> {
+        let mut other = other.into_iter();
+        for (index, value) in self.into_iter().enumerate() {
+            match other.next() {
+                Some(other_value) => {
+                    let ordering = value
+                        .infix(COMPARE, other_value.into())
+                        .into_native::<Ordering>()
+                        .map_err(|error| match error {
+                            EvalException::Thrown(error, position) => {
+                                EvalException::Thrown(error, element_position(position, index))
+                            }
+                            error => error,
+                        })?;
+                    match ordering {
+                        Ordering::Equal => {}
+                        ordering => return Ok(ordering),
+                    }
+                }
+                None => return Ok(Ordering::Greater),
+            }
+        }
+        match other.next() {
+            Some(_) => Ok(Ordering::Less),
+            None => Ok(Ordering::Equal),
+        }
+    }
+}
+
+///
+/// Moves an error in comparing two elements onto the element in the tuple.
+/// Errors in the comparison itself (like a value with no `<=>`) go on the
+/// left element.
+///
+fn element_position(position: ExpressionPosition, index: usize) -> ExpressionPosition {
+    match position {
+        Right | RightLeft | RightRight | RightElement(_) => RightElement(index),
+        Expression | Left | LeftLeft | LeftRight | LeftElement(_) => LeftElement(index),
+    }
 }
 
 impl IntoIterator for Tuple {
@@ -126,6 +178,42 @@ impl ObjectValue for Tuple {
     ) -> Result<(), EvalException> {
         default_set_field(self, name, value)
     }
+    fn index(self, index: BergVal) -> EvalResult
+    where
+        Self: Sized,
+    {
+        // Since the values are stored in reverse, a[i] is at len - 1 - i.
+        let len = self.len();
+        match sequence_index(&index, len)? {
+            Some(SequenceIndex::At(at)) => {
+                let mut values = self.0;
+                values.swap_remove(len - 1 - at).ok()
+            }
+            Some(SequenceIndex::Slice(range)) => {
+                Tuple::from_reversed_vec(self.0[len - range.end..len - range.start].to_vec()).ok()
+            }
+            None => CompilerError::IndexOutOfRange(Box::new(self), Box::new(index)).err(),
+        }
+    }
+    fn set_index(&mut self, index: BergVal, value: BergVal) -> Result<(), EvalException> {
+        let len = self.len();
+        match sequence_index(&index, len)? {
+            Some(SequenceIndex::At(at)) => {
+                self.0[len - 1 - at] = value;
+                Ok(())
+            }
+            // a[1,3] = x,y replaces the values in the slice with x and y.
+            Some(SequenceIndex::Slice(range)) => {
+                let values = match value {
+                    BergVal::Tuple(values) => values.0,
+                    value => vec![value],
+                };
+                self.0.splice(len - range.end..len - range.start, values);
+                Ok(())
+            }
+            None => CompilerError::IndexOutOfRange(Box::new(self.clone()), Box::new(index)).err(),
+        }
+    }
 }
 
 impl OperableValue for Tuple {
@@ -137,7 +225,14 @@ impl OperableValue for Tuple {
     where
         Self: Sized,
     {
-        default_infix(self, operator, right)
+        match operator {
+            COMPARE => match right.lazy_val()? {
+                BergVal::Tuple(right) => self.compare(right)?.ok(),
+                right => CompilerError::BadOperandType(Box::new(right), Tuple::TYPE_NAME)
+                    .operand_err(Right),
+            },
+            _ => default_infix(self, operator, right),
+        }
     }
     fn infix_assign(
         self,
@@ -25,3 +25,38 @@ fn one_character_field() {
 fn underscore_only() {
     expect("_").to_error(NoSuchField, 0)
 }
+
+#[test]
+fn unicode_field() {
+    expect("café = 1; café").to_yield(1)
+}
+#[test]
+fn unicode_field_not_found() {
+    expect("café").to_error(NoSuchField, "café")
+}
+#[test]
+fn unicode_field_non_latin() {
+    expect("Δx = 2; 名前 = 3; Δx * 名前").to_yield(6)
+}
+#[test]
+fn unicode_field_ending_in_0xbf() {
+    // ÿ is C3 BF, ſ is C5 BF: 0xBF is the last UTF-8 continuation byte.
+    expect("ÿ = 1; ſ = 2; ÿ + ſ").to_yield(3)
+}
+#[test]
+fn unicode_field_combining_mark() {
+    // "e" followed by U+0301 COMBINING ACUTE ACCENT, which is XID_Continue
+    expect("e\u{301}x = 1; e\u{301}x").to_error(DenormalizedIdentifier, "e\u{301}x")
+}
+#[test]
+fn unicode_field_different_from_ascii() {
+    expect("cafe = 1; café").to_error(NoSuchField, "café")
+}
+#[test]
+fn unicode_field_declaration() {
+    expect(":café = 1; café + 1").to_yield(2)
+}
+#[test]
+fn unicode_field_starts_with_number() {
+    expect("1café").to_error(IdentifierStartsWithNumber, "1café")
+}
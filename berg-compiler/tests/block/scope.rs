@@ -73,3 +73,11 @@ fn lazy_block_closes_over_scope_writeable() {
 fn lazy_block_assigned_and_used_out_of_scope() {
     expect("a = 1; x = { b = 2; c = { b + a }; c }; y = { a + x }; y").to_yield(4)
 }
+#[test]
+fn root_args_empty() {
+    expect("args").to_yield(tuple![])
+}
+#[test]
+fn child_references_root_args() {
+    expect("{ args }()").to_yield(tuple![])
+}
@@ -1,6 +1,6 @@
 pub use berg_compiler::test::*;
 pub use berg_compiler::*;
-pub use num::BigRational;
+pub use num::{BigRational, Complex};
 pub use std::iter::FromIterator;
 pub use std::str::FromStr;
 
@@ -8,3 +8,4 @@ mod block;
 mod control;
 mod parser;
 mod primitives;
+mod tracer;
@@ -0,0 +1,257 @@
+use crate::*;
+
+#[test]
+fn match_literal() {
+    expect(
+        r#"
+        match 2
+            1 => "one"
+            2 => "two"
+            _ => "many"
+    "#,
+    )
+    .to_yield("two");
+}
+
+#[test]
+fn match_curly_block() {
+    expect(r#"match 1 { 1 => "one"; _ => "many" }"#).to_yield("one");
+}
+
+#[test]
+fn match_wildcard() {
+    expect(r#"match 10 { 1 => "one"; _ => "many" }"#).to_yield("many");
+}
+
+#[test]
+fn match_string() {
+    expect(r#"match "b" { "a" => 1; "b" => 2; _ => 3 }"#).to_yield(2);
+}
+
+#[test]
+fn match_first_arm_wins() {
+    expect("match 1 { 1 => 10; 1 => 20 }").to_yield(10);
+}
+
+#[test]
+fn match_range() {
+    expect(
+        r#"
+        match 15
+            0...10 => "small"
+            10...100 => "medium"
+            _ => "large"
+    "#,
+    )
+    .to_yield("medium");
+}
+
+#[test]
+fn match_inclusive_range() {
+    expect(r#"match 10 { 0..10 => "small"; _ => "large" }"#).to_yield("small");
+}
+
+#[test]
+fn match_character_range() {
+    expect(r#"match "q" { "a".."z" => "lower"; _ => "other" }"#).to_yield("lower");
+}
+
+#[test]
+fn match_binding() {
+    expect("match 5 { :x => x * 2 }").to_yield(10);
+}
+
+#[test]
+fn match_tuple() {
+    expect(r#"match (1, 2) { (1, 1) => "ones"; (1, 2) => "one two"; _ => "other" }"#)
+        .to_yield("one two");
+}
+
+#[test]
+fn match_tuple_bindings() {
+    expect("match (3, 4) { (:a, :b) => a * b }").to_yield(12);
+}
+
+#[test]
+fn match_tuple_mixed() {
+    expect(
+        "
+        match (1, 7)
+            (0, :y) => y
+            (1, :y) => y + 100
+            _ => 0
+    ",
+    )
+    .to_yield(107);
+}
+
+#[test]
+fn match_tuple_wrong_length() {
+    expect(r#"match (1, 2, 3) { (:a, :b) => "pair"; _ => "other" }"#).to_yield("other");
+}
+
+#[test]
+fn match_nested_tuple() {
+    expect("match ((1, 2), 3) { ((:a, :b), :c) => a + b + c }").to_yield(6);
+}
+
+#[test]
+fn match_tuple_square_brackets() {
+    expect("match (1, 2) { [:a, 2] => a }").to_yield(1);
+}
+
+#[test]
+fn match_empty_tuple() {
+    expect(r#"match () { () => "empty"; _ => "other" }"#).to_yield("empty");
+}
+
+#[test]
+fn match_tuple_against_number() {
+    expect(r#"match 1 { (:a, :b) => "pair"; _ => "other" }"#).to_yield("other");
+}
+
+#[test]
+fn match_object_shape() {
+    expect(
+        "
+        point = { :x = 1; :y = 2 }
+        match point
+            { :x; :y } => x + y
+    ",
+    )
+    .to_yield(3);
+}
+
+#[test]
+fn match_object_field_pattern() {
+    expect(
+        r#"
+        point = { :x = 0; :y = 5 }
+        match point
+            { x: 1..10 } => "right"
+            { x: 0; :y } => y
+    "#,
+    )
+    .to_yield(5);
+}
+
+#[test]
+fn match_object_nested_pattern() {
+    expect(
+        "
+        line = { :start = (1, 2); :end = (3, 4) }
+        match line
+            { start: (:x1, :y1); end: (:x2, :y2) } => (x2 - x1) * (y2 - y1)
+    ",
+    )
+    .to_yield(4);
+}
+
+#[test]
+fn match_object_missing_field() {
+    expect(
+        r#"
+        point = { :x = 1 }
+        match point
+            { :x; :z } => "3d"
+            { :x } => "1d"
+    "#,
+    )
+    .to_yield("1d");
+}
+
+#[test]
+fn match_object_private_field() {
+    expect(
+        r#"
+        point = { x = 1 }
+        match point
+            { :x } => "has x"
+            _ => "no x"
+    "#,
+    )
+    .to_yield("no x");
+}
+
+#[test]
+fn match_object_against_number() {
+    expect(r#"match 1 { { :x } => x; _ => "not an object" }"#).to_yield("not an object");
+}
+
+#[test]
+fn match_variable_pattern() {
+    expect(
+        r#"
+        expected = 3
+        match 1 + 2 { expected => "yes"; _ => "no" }
+    "#,
+    )
+    .to_yield("yes");
+}
+
+#[test]
+fn match_bindings_per_match() {
+    expect(
+        "
+        area = { match :shape { (:w, :h) => w * h } }
+        (area (1, 2)) + (area (3, 4))
+    ",
+    )
+    .to_yield(14);
+}
+
+#[test]
+fn match_in_function() {
+    expect(
+        r#"
+        describe = { match :n { 0 => "zero"; 1..10 => "small"; _ => "big" } }
+        describe 5
+    "#,
+    )
+    .to_yield("small");
+}
+
+#[test]
+fn match_no_matching_arm() {
+    expect("match 3 { 1 => 1; 2 => 2 }").to_error(NoMatchingArm, 6);
+}
+
+#[test]
+fn match_empty_arms() {
+    expect("match 3 {}").to_error(NoMatchingArm, 6);
+}
+
+#[test]
+fn match_arm_without_arrow() {
+    expect("match 3 { 1 => 1; 2 }").to_error(MatchArmWithoutArrow, 18);
+}
+
+#[test]
+fn match_without_subject() {
+    expect("match").to_error(MatchWithoutSubject, 0..=4);
+}
+
+#[test]
+fn match_without_arms() {
+    expect("match 1").to_error(MatchWithoutArms, 0..=4);
+}
+
+#[test]
+fn match_arms_not_block() {
+    expect("match 1 2").to_error(MatchArmsMustBeBlock, 8);
+}
+
+#[test]
+fn match_subject_error() {
+    expect("match 1/0 { _ => 1 }").to_error(DivideByZero, 8);
+}
+
+#[test]
+fn match_result_error() {
+    expect("match 1 { 1 => 1/0 }").to_error(DivideByZero, 17);
+}
+
+#[test]
+fn match_invalid_field_pattern() {
+    expect("match 1 { { 1 } => 1 }").to_error(InvalidFieldPattern, 12);
+}
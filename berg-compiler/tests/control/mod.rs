@@ -1,4 +1,5 @@
 mod foreach;
 mod if_else;
+mod match_expression;
 mod try_catch;
 mod while_loop;
@@ -0,0 +1,218 @@
+use super::corpus::{corpus, parse_bytes};
+use berg_parser::{
+    content_hash, reparse, Ast, AstFormatError, ByteIndex, ParseCache, AST_FORMAT_VERSION,
+};
+use berg_util::to_indexed_cow;
+use std::borrow::Cow;
+use std::fs::{self, File};
+use std::path::PathBuf;
+use std::process;
+use std::time::{Duration, SystemTime};
+
+const SOURCE: &[u8] = b"a:\n    b = \"x#{1 + 2}y\" # comment\n\tc\n:d = 1_500.5e3; 0x1_F\n\xff\n";
+
+fn round_trip(source: &[u8]) -> Ast {
+    let hash = content_hash(source);
+    let binary = parse_bytes(source).to_binary(hash);
+    let ast = Ast::from_binary(&binary, hash).unwrap();
+    assert_eq!(ast.to_binary(hash), binary);
+    ast
+}
+
+///
+/// A cache directory of its own for each test, so tests don't see each
+/// other's files.
+///
+fn cache_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("berg-parse-cache-{}-{}", process::id(), name));
+    let _ = fs::remove_dir_all(&dir);
+    dir
+}
+
+#[test]
+fn round_trip_source() {
+    let ast = round_trip(SOURCE);
+    assert_eq!(ast.to_bytes(), SOURCE);
+    assert_eq!(ast.tokens, parse_bytes(SOURCE).tokens);
+}
+#[test]
+fn round_trip_corpus() {
+    for source in corpus() {
+        let ast = round_trip(&source);
+        let parsed = parse_bytes(&source);
+        let source = String::from_utf8_lossy(&source);
+        assert_eq!(ast.to_bytes(), parsed.to_bytes(), "{:?}", source);
+        assert_eq!(ast.tokens, parsed.tokens, "{:?}", source);
+        assert_eq!(ast.token_ranges, parsed.token_ranges, "{:?}", source);
+        assert_eq!(ast.blocks, parsed.blocks, "{:?}", source);
+        assert_eq!(ast.fields, parsed.fields, "{:?}", source);
+    }
+}
+#[test]
+fn round_trip_empty() {
+    let ast = round_trip(b"");
+    assert_eq!(ast.to_bytes(), b"");
+}
+#[test]
+fn reparse_loaded_ast() {
+    let ast = round_trip(SOURCE);
+    let edited = [&SOURCE[..4], b"x", &SOURCE[5..]].concat();
+    let ast = reparse(ast, ByteIndex::from(4)..ByteIndex::from(5), b"x");
+    assert_eq!(ast.to_bytes(), edited);
+    assert_eq!(ast.tokens, parse_bytes(&edited).tokens);
+    assert_eq!(ast.blocks, parse_bytes(&edited).blocks);
+}
+#[test]
+fn wrong_version() {
+    let hash = content_hash(SOURCE);
+    let mut binary = parse_bytes(SOURCE).to_binary(hash);
+    binary[4..8].copy_from_slice(&(AST_FORMAT_VERSION + 1).to_le_bytes());
+    assert_eq!(
+        Ast::from_binary(&binary, hash).unwrap_err(),
+        AstFormatError::WrongVersion(AST_FORMAT_VERSION + 1)
+    );
+}
+#[test]
+fn wrong_hash() {
+    let hash = content_hash(SOURCE);
+    let binary = parse_bytes(SOURCE).to_binary(hash);
+    assert_eq!(
+        Ast::from_binary(&binary, hash + 1).unwrap_err(),
+        AstFormatError::WrongHash(hash)
+    );
+}
+#[test]
+fn not_an_ast() {
+    assert_eq!(
+        Ast::from_binary(b"a = 1\n", 0).unwrap_err(),
+        AstFormatError::NotAnAst
+    );
+    assert_eq!(
+        Ast::from_binary(b"", 0).unwrap_err(),
+        AstFormatError::NotAnAst
+    );
+}
+#[test]
+fn truncated() {
+    let hash = content_hash(SOURCE);
+    let binary = parse_bytes(SOURCE).to_binary(hash);
+    for len in 4..binary.len() {
+        assert_eq!(
+            Ast::from_binary(&binary[..len], hash).unwrap_err(),
+            AstFormatError::Corrupt,
+            "truncated to {} bytes",
+            len
+        );
+    }
+}
+#[test]
+fn trailing_bytes() {
+    let hash = content_hash(SOURCE);
+    let mut binary = parse_bytes(SOURCE).to_binary(hash);
+    binary.push(0);
+    assert_eq!(
+        Ast::from_binary(&binary, hash).unwrap_err(),
+        AstFormatError::Corrupt
+    );
+}
+#[test]
+fn corrupt_bytes_dont_panic() {
+    let hash = content_hash(SOURCE);
+    let binary = parse_bytes(SOURCE).to_binary(hash);
+    for index in 16..binary.len() {
+        let mut corrupt = binary.clone();
+        corrupt[index] ^= 0xFF;
+        let _ = Ast::from_binary(&corrupt, hash);
+    }
+}
+#[test]
+fn content_hash_is_fnv1a() {
+    assert_eq!(content_hash(b""), 0xcbf2_9ce4_8422_2325);
+    assert_eq!(content_hash(b"a"), 0xaf63_dc4c_8601_ec8c);
+    assert_ne!(content_hash(b"a + b"), content_hash(b"a + c"));
+}
+
+#[test]
+fn cache_stores_ast() {
+    let dir = cache_dir("stores");
+    let cache = ParseCache::new(&dir);
+    let ast = cache.parse(to_indexed_cow(Cow::Borrowed(SOURCE)));
+    assert_eq!(ast.to_bytes(), SOURCE);
+    let hash = content_hash(SOURCE);
+    let stored = fs::read(cache.path(hash)).unwrap();
+    assert_eq!(stored, ast.to_binary(hash));
+    fs::remove_dir_all(&dir).unwrap();
+}
+#[test]
+fn cache_loads_ast() {
+    let dir = cache_dir("loads");
+    let cache = ParseCache::new(&dir);
+    // Date the stored AST in the past, so we can tell it was loaded rather
+    // than parsed (and stored) again.
+    let hash = content_hash(SOURCE);
+    fs::create_dir_all(&dir).unwrap();
+    fs::write(cache.path(hash), parse_bytes(SOURCE).to_binary(hash)).unwrap();
+    let modified = SystemTime::UNIX_EPOCH + Duration::from_secs(1_000_000_000);
+    File::options()
+        .write(true)
+        .open(cache.path(hash))
+        .unwrap()
+        .set_modified(modified)
+        .unwrap();
+    let ast = cache.parse(to_indexed_cow(Cow::Borrowed(SOURCE)));
+    assert_eq!(ast.to_bytes(), SOURCE);
+    assert_eq!(
+        fs::metadata(cache.path(hash)).unwrap().modified().unwrap(),
+        modified
+    );
+    fs::remove_dir_all(&dir).unwrap();
+}
+#[test]
+fn cache_replaces_bad_ast() {
+    let dir = cache_dir("replaces");
+    let cache = ParseCache::new(&dir);
+    let hash = content_hash(SOURCE);
+    fs::create_dir_all(&dir).unwrap();
+    let mut old_version = parse_bytes(SOURCE).to_binary(hash);
+    old_version[4..8].copy_from_slice(&(AST_FORMAT_VERSION + 1).to_le_bytes());
+    for bad in [b"garbage".to_vec(), old_version] {
+        fs::write(cache.path(hash), bad).unwrap();
+        let ast = cache.parse(to_indexed_cow(Cow::Borrowed(SOURCE)));
+        assert_eq!(ast.to_bytes(), SOURCE);
+        assert_eq!(fs::read(cache.path(hash)).unwrap(), ast.to_binary(hash));
+    }
+    fs::remove_dir_all(&dir).unwrap();
+}
+#[test]
+fn cache_ignores_different_size() {
+    let dir = cache_dir("size");
+    let cache = ParseCache::new(&dir);
+    let hash = content_hash(b"1 + 2");
+    fs::create_dir_all(&dir).unwrap();
+    fs::write(cache.path(hash), parse_bytes(b"1 + 23").to_binary(hash)).unwrap();
+    let ast = cache.parse(to_indexed_cow(Cow::Borrowed(b"1 + 2")));
+    assert_eq!(ast.to_bytes(), b"1 + 2");
+    fs::remove_dir_all(&dir).unwrap();
+}
+#[test]
+fn cache_ignores_different_source() {
+    let dir = cache_dir("source");
+    let cache = ParseCache::new(&dir);
+    // As if "1 + 2" and "3 + 4" had the same hash.
+    let hash = content_hash(b"3 + 4");
+    fs::create_dir_all(&dir).unwrap();
+    fs::write(cache.path(hash), parse_bytes(b"1 + 2").to_binary(hash)).unwrap();
+    let ast = cache.parse(to_indexed_cow(Cow::Borrowed(b"3 + 4")));
+    assert_eq!(ast.to_bytes(), b"3 + 4");
+    fs::remove_dir_all(&dir).unwrap();
+}
+#[test]
+fn cache_unwritable() {
+    let dir = cache_dir("unwritable");
+    // A file where the directory should be.
+    fs::write(&dir, b"").unwrap();
+    let cache = ParseCache::new(&dir);
+    let ast = cache.parse(to_indexed_cow(Cow::Borrowed(SOURCE)));
+    assert_eq!(ast.to_bytes(), SOURCE);
+    fs::remove_file(&dir).unwrap();
+}
@@ -0,0 +1,270 @@
+use crate::*;
+use berg_parser::{
+    format_source, parse_with_operators, reparse, Ast, AstBuilder, Expression, OperatorTable,
+};
+use berg_util::to_indexed_cow;
+use std::borrow::Cow;
+use Expression as E;
+
+fn build(statements: impl IntoIterator<Item = Expression>) -> Ast {
+    build_with_operators(statements, OperatorTable::default())
+}
+
+fn build_with_operators(
+    statements: impl IntoIterator<Item = Expression>,
+    operators: OperatorTable,
+) -> Ast {
+    let mut builder = AstBuilder::with_operators(operators);
+    for statement in statements {
+        builder.push(statement);
+    }
+    builder.finish()
+}
+
+// The built AST must be exactly what parsing its source gives.
+fn assert_builds(ast: &Ast, expected: &str) {
+    assert_eq!(String::from_utf8_lossy(&ast.to_bytes()), expected);
+    let parsed = parse_with_operators(
+        to_indexed_cow(Cow::Owned(expected.as_bytes().to_vec())),
+        ast.operators.clone(),
+    );
+    assert_eq!(ast.tokens, parsed.tokens);
+    assert_eq!(ast.token_ranges, parsed.token_ranges);
+    assert_eq!(ast.blocks, parsed.blocks);
+    assert_eq!(ast.fields, parsed.fields);
+    assert_eq!(ast.char_data.line_starts, parsed.char_data.line_starts);
+}
+
+fn yields(ast: Ast, expected: impl ExpectedValue) {
+    let actual = evaluate_built_ast(ast).unwrap();
+    assert!(
+        expected.clone().matches(actual.clone()).unwrap(),
+        "expected {}, got {}",
+        expected,
+        actual
+    );
+}
+
+#[test]
+fn terms() {
+    let ast = build([
+        E::integer(12),
+        E::float(1.5),
+        E::string("hi"),
+        E::field("a"),
+    ]);
+    assert_builds(&ast, "12\n1.5\n\"hi\"\na");
+}
+
+#[test]
+fn negative_numbers() {
+    let ast = build([E::integer(-12), E::float(-0.25), E::float(1e100)]);
+    assert_builds(&ast, "-12\n-0.25\n1e100");
+}
+
+#[test]
+fn string_escapes() {
+    let ast = build([E::string("a\"b\\c\nd\t#{e} #f")]);
+    assert_builds(&ast, r#""a\"b\\c\nd\t\#{e} #f""#);
+    yields(ast, "a\"b\\c\nd\t#{e} #f");
+}
+
+#[test]
+fn empty() {
+    let ast = build([]);
+    assert_builds(&ast, "");
+}
+
+#[test]
+fn infix() {
+    let ast = build([E::integer(1).infix("+", E::integer(2))]);
+    assert_builds(&ast, "1 + 2");
+    yields(ast, 3);
+}
+
+#[test]
+fn infix_precedence_needs_no_parentheses() {
+    let ast = build([E::integer(1).infix("+", E::integer(2).infix("*", E::integer(3)))]);
+    assert_builds(&ast, "1 + 2 * 3");
+    yields(ast, 7);
+}
+
+#[test]
+fn infix_left_parentheses() {
+    let ast = build([E::integer(1)
+        .infix("+", E::integer(2))
+        .infix("*", E::integer(3))]);
+    assert_builds(&ast, "(1 + 2) * 3");
+    yields(ast, 9);
+}
+
+#[test]
+fn infix_right_parentheses() {
+    let ast = build([E::integer(1).infix("-", E::integer(2).infix("-", E::integer(3)))]);
+    assert_builds(&ast, "1 - (2 - 3)");
+    yields(ast, 2);
+}
+
+#[test]
+fn infix_left_associative() {
+    let ast = build([E::integer(1)
+        .infix("-", E::integer(2))
+        .infix("-", E::integer(3))]);
+    assert_builds(&ast, "1 - 2 - 3");
+    yields(ast, -4);
+}
+
+#[test]
+fn prefix_and_postfix() {
+    let ast = build([
+        E::prefix("!", E::field("true")),
+        E::prefix("-", E::integer(1).infix("+", E::integer(2))),
+        E::field("a").postfix("++"),
+    ]);
+    assert_builds(&ast, "!true\n-(1 + 2)\na++");
+}
+
+#[test]
+fn negative_operand() {
+    let ast = build([E::integer(1).infix("-", E::integer(-2))]);
+    assert_builds(&ast, "1 - -2");
+    yields(ast, 3);
+}
+
+#[test]
+fn group() {
+    let ast = build([E::integer(1).group()]);
+    assert_builds(&ast, "(1)");
+    yields(ast, 1);
+}
+
+#[test]
+fn declarations() {
+    let ast = build([
+        E::declare("a", E::integer(1)),
+        E::declare("b", E::field("a").infix("+", E::integer(2))),
+        E::field("b"),
+    ]);
+    assert_builds(&ast, "a: 1\nb: a + 2\nb");
+    assert!(ast.fields.iter().any(|field| field.is_public));
+    yields(ast, 3);
+}
+
+#[test]
+fn assignment() {
+    let ast = build([
+        E::field("a").infix("=", E::integer(1)),
+        E::field("a").infix("+=", E::integer(2)),
+        E::field("a"),
+    ]);
+    assert_builds(&ast, "a = 1\na += 2\na");
+    yields(ast, 3);
+}
+
+#[test]
+fn semicolon_statement() {
+    let ast = build([E::integer(1).infix(";", E::integer(2)), E::integer(3)]);
+    assert_builds(&ast, "1; 2\n3");
+}
+
+#[test]
+fn semicolon_in_block_statement() {
+    let ast = build([E::block([
+        E::integer(1).infix(";", E::integer(2)),
+        E::integer(3),
+    ])]);
+    assert_builds(&ast, "{ 1; 2; 3 }");
+    yields(ast, 3);
+}
+
+#[test]
+fn block() {
+    let ast = build([
+        E::declare(
+            "f",
+            E::block([
+                E::declare("x", E::integer(1)),
+                E::field("x").infix("+", E::integer(1)),
+            ]),
+        ),
+        E::field("f").infix(".", E::field("x")),
+    ]);
+    assert_builds(&ast, "f: { x: 1; x + 1 }\nf.x");
+    yields(ast, 1);
+}
+
+#[test]
+fn empty_block() {
+    let ast = build([E::block([])]);
+    assert_builds(&ast, "{}");
+}
+
+#[test]
+fn block_refers_to_parent_field() {
+    let ast = build([
+        E::declare("a", E::integer(10)),
+        E::declare("f", E::block([E::field("a").infix("*", E::integer(2))])),
+        E::field("f"),
+    ]);
+    assert_builds(&ast, "a: 10\nf: { a * 2 }\nf");
+    yields(ast, 20);
+}
+
+#[test]
+fn dot_operand_parentheses() {
+    let ast = build([
+        E::prefix("-", E::field("a")).infix(".", E::field("b")),
+        E::prefix("-", E::field("a").infix(".", E::field("b"))),
+        E::field("a")
+            .infix(".", E::field("b"))
+            .infix(".", E::field("c")),
+    ]);
+    assert_builds(&ast, "(-a).b\n-(a.b)\na.b.c");
+}
+
+#[test]
+fn tuple() {
+    let ast = build([E::integer(1)
+        .infix(",", E::integer(2))
+        .infix(",", E::integer(3))]);
+    assert_builds(&ast, "1, 2, 3");
+    yields(ast, tuple!(1, 2, 3));
+}
+
+#[test]
+fn declared_operators() {
+    use berg_parser::{Associativity, Precedence, RelativePrecedence};
+    let mut operators = OperatorTable::default();
+    operators.declare(
+        "|>",
+        RelativePrecedence::LooserThan(Precedence::Or),
+        Associativity::Right,
+    );
+    let ast = build_with_operators(
+        [
+            E::field("a").infix("|>", E::field("b").infix("|>", E::field("c"))),
+            E::field("a")
+                .infix("|>", E::field("b"))
+                .infix("|>", E::field("c")),
+        ],
+        operators,
+    );
+    assert_builds(&ast, "a |> b |> c\n(a |> b) |> c");
+}
+
+#[test]
+fn formats_and_reparses() {
+    let ast = build([
+        E::declare("a", E::integer(1)),
+        E::declare("b", E::block([E::field("a")])),
+    ]);
+    assert_eq!(format_source(&ast).unwrap(), b"a: 1\nb: { a }\n");
+    let ast = reparse(ast, 3.into()..4.into(), b"2");
+    assert_eq!(ast.to_bytes(), b"a: 2\nb: { a }");
+}
+
+#[test]
+#[should_panic(expected = "is not an identifier")]
+fn invalid_field_name() {
+    E::field("a b");
+}
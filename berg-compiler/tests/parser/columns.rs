@@ -0,0 +1,112 @@
+use berg_compiler::{evaluate_bytes_with_columns, ColumnUnit, NoTracer};
+use berg_parser::{parse, Ast, ByteIndex, LineColumn, LineColumnRange};
+use berg_util::to_indexed_cow;
+use std::borrow::Cow;
+use ColumnUnit::*;
+
+// "é" is 2 bytes and 1 UTF-16 unit; "😀" is 4 bytes and 2 UTF-16 units.
+const SOURCE: &[u8] = "a = \"é😀\"\nb = \"x\" + zz\n\"😀\" + zz\n".as_bytes();
+
+fn ast(source: &[u8]) -> Ast {
+    parse(to_indexed_cow(Cow::Owned(source.to_vec())))
+}
+
+fn column(source: &[u8], index: usize, unit: ColumnUnit) -> u32 {
+    let location = ast(source)
+        .char_data
+        .location_in(source, ByteIndex::from(index), unit);
+    location.column
+}
+
+#[test]
+fn ascii_columns_are_the_same() {
+    for unit in [Bytes, Chars, Utf16] {
+        assert_eq!(column(SOURCE, 17, unit), 5);
+    }
+}
+
+#[test]
+fn columns_after_non_ascii() {
+    // The closing quote on the first line.
+    assert_eq!(column(SOURCE, 11, Bytes), 12);
+    assert_eq!(column(SOURCE, 11, Chars), 8);
+    assert_eq!(column(SOURCE, 11, Utf16), 9);
+}
+
+#[test]
+fn middle_of_character_is_at_character() {
+    // The third byte of "😀".
+    assert_eq!(column(SOURCE, 9, Chars), 7);
+    assert_eq!(column(SOURCE, 9, Utf16), 7);
+}
+
+#[test]
+fn invalid_utf8_is_one_character() {
+    // A truncated "😀".
+    let source = b"\"\xf0\x9f\x98\" + zz";
+    assert_eq!(column(source, 6, Bytes), 7);
+    assert_eq!(column(source, 6, Chars), 5);
+    assert_eq!(column(source, 6, Utf16), 5);
+}
+
+#[test]
+fn range_in_columns() {
+    let ast = ast(SOURCE);
+    let range = ByteIndex::from(4)..ByteIndex::from(12);
+    let expected = |start, end, unit| {
+        LineColumnRange::new(
+            LineColumn::new(1, start, unit),
+            LineColumn::new(1, end, unit),
+        )
+    };
+    assert_eq!(
+        ast.char_data.range_in(SOURCE, &range, Bytes),
+        expected(5, 12, Bytes)
+    );
+    assert_eq!(
+        ast.char_data.range_in(SOURCE, &range, Chars),
+        expected(5, 8, Chars)
+    );
+    assert_eq!(
+        ast.char_data.range_in(SOURCE, &range, Utf16),
+        expected(5, 9, Utf16)
+    );
+}
+
+#[test]
+fn byte_range_round_trips() {
+    let ast = ast(SOURCE);
+    for unit in [Bytes, Chars, Utf16] {
+        for (start, end) in [(4, 12), (5, 11), (23, 25), (27, 37)] {
+            let range = ByteIndex::from(start)..ByteIndex::from(end);
+            let location = ast.char_data.range_in(SOURCE, &range, unit);
+            assert_eq!(
+                ast.char_data.byte_range_in(SOURCE, location),
+                range,
+                "{:?}",
+                unit
+            );
+        }
+    }
+}
+
+#[test]
+fn convert_between_units() {
+    let ast = ast(SOURCE);
+    let range = ByteIndex::from(35)..ByteIndex::from(37);
+    let bytes = ast.char_data.range(&range);
+    let chars = bytes.to_unit(&ast.char_data, SOURCE, Chars);
+    let utf16 = chars.to_unit(&ast.char_data, SOURCE, Utf16);
+    assert_eq!(chars.to_string(), "3:7-8");
+    assert_eq!(utf16.to_string(), "3:8-9");
+    assert_eq!(utf16.to_unit(&ast.char_data, SOURCE, Bytes), bytes);
+}
+
+#[test]
+fn error_location_in_root_columns() {
+    let result = evaluate_bytes_with_columns("test.rs", "\"😀\" + zz".as_bytes(), NoTracer, Utf16);
+    let location = result.unwrap_err().location();
+    assert_eq!(location.range().to_string(), "1:8-9");
+    assert_eq!(location.range_in(Chars).to_string(), "1:7-8");
+    assert_eq!(location.range_in(Bytes).to_string(), "1:10-11");
+}
@@ -341,3 +341,89 @@ fn invalid_then_unsupported_multiple() {
     ])
     .to_yield(tuple!())
 }
+
+#[test]
+fn block_comment_only() {
+    expect("#[ comment ]#").to_yield(tuple!())
+}
+#[test]
+fn block_comment_empty() {
+    expect("#[]#").to_yield(tuple!())
+}
+#[test]
+fn block_comment_between_terms() {
+    expect("1 + #[ comment ]# 2").to_yield(3)
+}
+#[test]
+fn block_comment_ends_term() {
+    expect("2 * 1+1#[comment]#").to_yield(4)
+}
+#[test]
+fn block_comment_immediately_between_operands() {
+    expect("1#[ comment ]#+#[ comment ]#2").to_yield(3)
+}
+#[test]
+fn block_comment_multiple_lines() {
+    expect(
+        "
+        :x = 1
+        #[
+            x = 2
+        ]#
+        x
+    ",
+    )
+    .to_yield(1)
+}
+#[test]
+fn block_comment_nested() {
+    expect("#[ a #[ b ]# c ]# 1").to_yield(1)
+}
+#[test]
+fn block_comment_nested_close_is_not_end() {
+    expect("#[ #[ ]# 1 ]# 2").to_yield(2)
+}
+#[test]
+fn block_comment_with_line_comment_inside() {
+    expect("#[ # ]# 1").to_yield(1)
+}
+#[test]
+fn block_comment_after_line_comment_is_line_comment() {
+    expect("# #[\n1").to_yield(1)
+}
+#[test]
+fn block_comment_then_line_comment() {
+    expect("1 #[ a ]# # b").to_yield(1)
+}
+#[test]
+fn block_comment_crlf() {
+    expect("#[\r\n]#\r\n1").to_yield(1)
+}
+#[test]
+fn block_comment_invalid_utf8() {
+    expect(&[b'#', b'[', 0b1000_0000, b']', b'#', b'1']).to_yield(1)
+}
+#[test]
+fn block_comment_does_not_end_curly_braces() {
+    expect("{ #[ } ]# }").to_yield(tuple!())
+}
+#[test]
+fn block_comment_in_string_is_string() {
+    expect("\"#[\"").to_yield("#[")
+}
+#[test]
+fn block_comment_unclosed() {
+    expect("#[").to_error(UnclosedBlockComment, 0..=1)
+}
+#[test]
+fn block_comment_unclosed_after_expression() {
+    expect("1 + #[ comment\n3").to_error(UnclosedBlockComment, 4..=5)
+}
+#[test]
+fn block_comment_unclosed_nested() {
+    expect("#[ #[ ]#\n1").to_error(UnclosedBlockComment, 0..=1)
+}
+#[test]
+fn block_comment_unclosed_close_without_open() {
+    expect("1 ]#").to_error(CloseWithoutOpen, 2)
+}
@@ -0,0 +1,250 @@
+use berg_parser::{ByteIndex, CstGroup, CstNode, ExpressionBoundary, Trivia};
+
+use super::corpus::{corpus, parse_bytes};
+
+fn range(start: usize, end: usize) -> std::ops::Range<ByteIndex> {
+    ByteIndex::from(start)..ByteIndex::from(end)
+}
+
+fn expect_round_trip(source: &[u8]) {
+    let ast = parse_bytes(source);
+    let tree = ast.concrete_syntax_tree();
+    assert_eq!(
+        tree.to_bytes(),
+        source,
+        "round trip failed for {:?}",
+        String::from_utf8_lossy(source)
+    );
+    // Every byte is in exactly one token or one piece of trivia.
+    let mut pieces: Vec<_> = tree.trivia().iter().map(Trivia::byte_range).collect();
+    pieces.extend(tree.tokens().map(|token| token.byte_range()));
+    pieces.retain(|range| range.start < range.end);
+    pieces.sort_by_key(|range| range.start);
+    let mut end = ByteIndex::from(0);
+    for piece in pieces {
+        assert_eq!(
+            piece.start,
+            end,
+            "gap or overlap in {:?}",
+            String::from_utf8_lossy(source)
+        );
+        end = piece.end;
+    }
+    assert_eq!(end, ast.char_data.size);
+}
+
+fn find_curly_block(node: CstNode) -> Option<CstGroup> {
+    match node {
+        CstNode::Block(group) if group.boundary() == ExpressionBoundary::CurlyBraces => Some(group),
+        CstNode::Group(group) | CstNode::Block(group) => {
+            group.children().find_map(find_curly_block)
+        }
+        CstNode::Term(_) | CstNode::Operator(_) => None,
+    }
+}
+
+fn term_text(node: CstNode) -> String {
+    match node {
+        CstNode::Term(token) => String::from_utf8(token.bytes().into_owned()).unwrap(),
+        _ => panic!("expected term, got {:?}", node),
+    }
+}
+
+#[test]
+fn round_trip_corpus() {
+    for source in corpus() {
+        expect_round_trip(&source)
+    }
+}
+#[test]
+fn round_trip_simple() {
+    expect_round_trip(b"a = 1\nb = 2\na + b\n")
+}
+#[test]
+fn round_trip_empty() {
+    expect_round_trip(b"")
+}
+#[test]
+fn round_trip_only_space() {
+    expect_round_trip(b"  \n\n \t \n")
+}
+#[test]
+fn round_trip_crlf() {
+    expect_round_trip(b"a = 1\r\nb = 2\r\n\r\na + b\r")
+}
+#[test]
+fn round_trip_tabs() {
+    expect_round_trip(b"a:\n\tb\n\t  c\n \td\n")
+}
+#[test]
+fn round_trip_comments() {
+    expect_round_trip(b"# start\na # after a\n  # own line\r\nb#tight\n#")
+}
+#[test]
+fn round_trip_block_comments() {
+    expect_round_trip(b"a #[ x ]#+#[\r\n  y #[ z ]#\n]# b\n  #[ w ]#\n#[ open\n")
+}
+#[test]
+fn round_trip_digit_separators() {
+    expect_round_trip(b"1_000 + 0xFF_FF\n1_0.0_1e1_0i+2_2\n1__0 0x_1 1_ 1e_1\n")
+}
+#[test]
+fn digit_separators_are_not_in_literals() {
+    let source = b"1_000 + 0b1_0.5_5";
+    let ast = parse_bytes(source);
+    assert_eq!(ast.to_bytes(), source);
+    let literals: Vec<_> = ast.literals.iter().map(|(_, literal)| literal).collect();
+    assert_eq!(literals, ["1000", "0b10", "55"]);
+    let tree = ast.concrete_syntax_tree();
+    let tokens: Vec<_> = tree
+        .tokens()
+        .map(|token| String::from_utf8(token.bytes().into_owned()).unwrap())
+        .filter(|bytes| !bytes.trim().is_empty())
+        .collect();
+    assert_eq!(tokens, ["1_000", "+", "0b1_0", ".", "5_5"]);
+}
+#[test]
+fn round_trip_strings() {
+    expect_round_trip(b"x = \"multi\nline\r\nstring\" + \"a#{\n1 +\n2\n}c\"\n")
+}
+#[test]
+fn round_trip_unclosed_string() {
+    expect_round_trip(b"a\n\"open\n  string")
+}
+#[test]
+fn round_trip_invalid_utf8() {
+    expect_round_trip(b"a \xff b\n\"\x80\"\n")
+}
+#[test]
+fn round_trip_unclosed_groups() {
+    expect_round_trip(b"{ (1 +\n  ]\n")
+}
+
+#[test]
+fn trailing_trivia_stops_at_newline() {
+    let ast = parse_bytes(b"a  # note\n  # own line\nb\n");
+    let tree = ast.concrete_syntax_tree();
+    let a = tree.tokens().find(|token| &*token.bytes() == b"a").unwrap();
+    assert_eq!(
+        a.trailing_trivia(),
+        &[Trivia::Space(range(1, 3)), Trivia::Comment(range(3, 9))]
+    );
+    let b = tree.tokens().find(|token| &*token.bytes() == b"b").unwrap();
+    assert_eq!(
+        b.leading_trivia(),
+        &[
+            Trivia::Newline(range(9, 10)),
+            Trivia::Space(range(10, 12)),
+            Trivia::Comment(range(12, 22)),
+            Trivia::Newline(range(22, 23)),
+        ]
+    );
+    assert_eq!(b.trailing_trivia(), &[]);
+}
+
+#[test]
+fn leading_trivia_at_start() {
+    let ast = parse_bytes(b"\n  # header\n\tx");
+    let tree = ast.concrete_syntax_tree();
+    let x = tree.tokens().find(|token| &*token.bytes() == b"x").unwrap();
+    assert_eq!(
+        x.leading_trivia(),
+        &[
+            Trivia::Newline(range(0, 1)),
+            Trivia::Space(range(1, 3)),
+            Trivia::Comment(range(3, 11)),
+            Trivia::Newline(range(11, 12)),
+            Trivia::Whitespace(range(12, 13)),
+        ]
+    );
+    assert_eq!(&*tree.trivia_bytes(&x.leading_trivia()[4]), b"\t");
+}
+
+#[test]
+fn trivia_at_end_belongs_to_source_close() {
+    let ast = parse_bytes(b"x # done\r\n\n");
+    let tree = ast.concrete_syntax_tree();
+    let close = match tree.root() {
+        CstNode::Block(root) => root.close(),
+        node => panic!("expected block, got {:?}", node),
+    };
+    assert_eq!(
+        close.leading_trivia(),
+        &[
+            Trivia::Newline(range(8, 10)),
+            Trivia::Newline(range(10, 11))
+        ]
+    );
+    assert_eq!(&*tree.trivia_bytes(&close.leading_trivia()[0]), b"\r\n");
+    assert_eq!(tree.root().trailing_trivia(), &[]);
+}
+
+#[test]
+fn inserted_tokens_have_no_trivia() {
+    let ast = parse_bytes(b"a b");
+    let tree = ast.concrete_syntax_tree();
+    let inserted: Vec<_> = tree
+        .tokens()
+        .filter(|token| token.byte_range().start == token.byte_range().end)
+        .filter(|token| token.index() != ast.tokens.last_index())
+        .collect();
+    assert!(!inserted.is_empty());
+    for token in inserted {
+        assert!(token.bytes().is_empty());
+        assert_eq!(token.leading_trivia(), &[]);
+        assert_eq!(token.trailing_trivia(), &[]);
+    }
+}
+
+#[test]
+fn groups_and_children() {
+    let ast = parse_bytes(b"x = { 1 } + (2)");
+    let tree = ast.concrete_syntax_tree();
+    let root = match tree.root() {
+        CstNode::Block(root) => root,
+        node => panic!("expected block, got {:?}", node),
+    };
+    assert_eq!(root.boundary(), ExpressionBoundary::Source);
+    let children: Vec<_> = root.children().collect();
+    let terms: Vec<_> = children
+        .iter()
+        .filter(|node| matches!(node, CstNode::Term(_)))
+        .map(|node| term_text(*node))
+        .collect();
+    assert!(terms.contains(&"x".to_string()), "{:?}", children);
+
+    let block =
+        find_curly_block(tree.root()).unwrap_or_else(|| panic!("no curly block in {:?}", children));
+    assert_eq!(&*block.open().bytes(), b"{");
+    assert_eq!(&*block.close().bytes(), b"}");
+    assert_eq!(
+        block.open().trailing_trivia(),
+        &[Trivia::Space(range(5, 6))]
+    );
+    assert_eq!(CstNode::Block(block).to_bytes(), b"{ 1 } ");
+    assert_eq!(CstNode::Block(block).byte_range(), range(4, 9));
+}
+
+#[test]
+fn nested_children_skip_inner_tokens() {
+    let ast = parse_bytes(b"(a (b c)) d");
+    let tree = ast.concrete_syntax_tree();
+    fn count_terms(node: CstNode) -> usize {
+        match node {
+            CstNode::Term(_) => 1,
+            CstNode::Operator(_) => 0,
+            CstNode::Group(group) | CstNode::Block(group) => {
+                group.children().map(count_terms).sum()
+            }
+        }
+    }
+    assert_eq!(count_terms(tree.root()), 4);
+    let direct: usize = match tree.root() {
+        CstNode::Block(root) => root
+            .children()
+            .filter(|node| matches!(node, CstNode::Term(_)))
+            .count(),
+        _ => unreachable!(),
+    };
+    assert!(direct < 4);
+}
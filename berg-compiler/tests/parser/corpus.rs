@@ -0,0 +1,56 @@
+use berg_parser::{parse, parse_with_operators, Ast, OperatorTable};
+use berg_util::to_indexed_cow;
+use std::borrow::Cow;
+use std::fs;
+use std::path::Path;
+
+pub fn parse_bytes(source: &[u8]) -> Ast {
+    parse(to_indexed_cow(Cow::Owned(source.to_vec())))
+}
+
+pub fn parse_bytes_with_operators(source: &[u8], operators: OperatorTable) -> Ast {
+    parse_with_operators(to_indexed_cow(Cow::Owned(source.to_vec())), operators)
+}
+
+///
+/// Small sources that between them use most of the syntax.
+///
+pub const SOURCES: [&[u8]; 10] = [
+    b"a = 1\nb = 2\na + b\n",
+    b"if x > 10\n    y = 1\n    z = 2\nelse\n    3\n",
+    b"f: { :x = 1; :y = \"a#{x}b\" }\nf.y\n",
+    b"# comment\r\nx = \"multi\nline\" # trailing\r\n\tindented\n  mixed \t\n",
+    b"\"a#{\n1 +\n2\n}c\"\n{ { 1 } }\n(1 + 2) * 3\n",
+    b"1.5e3 + 0x1F - 2i\n:a: b\ncaf\xc3\xa9 = \"\xc3\xa9\"\n",
+    b"a:\n    b:\n        c\n    d\ne\n\n\n  \n",
+    b"x = { y\n} + (\n1)\n\xff z\n",
+    b"a = 1 #[ x ]# + 2\n#[\n  b #[ c ]#\n]#\n  #[ d ]#\ne\n",
+    b"a = 1_000 + 0xFF_FF\nb = 1_0.0_1e1_0i\n",
+];
+
+///
+/// [`SOURCES`], plus every `.berg` file in the repository's samples and docs.
+///
+pub fn corpus() -> Vec<Vec<u8>> {
+    let mut sources: Vec<Vec<u8>> = SOURCES.iter().map(|source| source.to_vec()).collect();
+    let root = Path::new(env!("CARGO_MANIFEST_DIR")).join("..");
+    for dir in ["samples", "doc"] {
+        add_berg_files(&root.join(dir), &mut sources);
+    }
+    sources
+}
+
+fn add_berg_files(dir: &Path, sources: &mut Vec<Vec<u8>>) {
+    let mut entries: Vec<_> = fs::read_dir(dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .collect();
+    entries.sort();
+    for path in entries {
+        if path.is_dir() {
+            add_berg_files(&path, sources);
+        } else if path.extension().is_some_and(|extension| extension == "berg") {
+            sources.push(fs::read(&path).unwrap());
+        }
+    }
+}
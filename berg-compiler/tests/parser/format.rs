@@ -0,0 +1,199 @@
+use super::corpus::{corpus, parse_bytes};
+use berg_parser::{format_source, ErrorTermError, ExpressionToken, FormatError, TermToken, Token};
+
+fn format_bytes(source: &[u8]) -> Vec<u8> {
+    format_source(&parse_bytes(source)).unwrap_or_else(|error| {
+        panic!(
+            "formatting {:?}: {}",
+            String::from_utf8_lossy(source),
+            error
+        )
+    })
+}
+
+fn expect_format(source: &str, expected: &str) {
+    let formatted = format_bytes(source.as_bytes());
+    assert_eq!(
+        String::from_utf8_lossy(&formatted),
+        expected,
+        "formatting {:?}",
+        source
+    );
+    let reformatted = format_bytes(&formatted);
+    assert_eq!(
+        String::from_utf8_lossy(&reformatted),
+        expected,
+        "reformatting {:?}",
+        expected
+    );
+}
+
+#[test]
+fn format_corpus() {
+    for source in corpus() {
+        let ast = parse_bytes(&source);
+        match format_source(&ast) {
+            Ok(formatted) => assert_eq!(
+                String::from_utf8_lossy(&format_bytes(&formatted)),
+                String::from_utf8_lossy(&formatted),
+                "reformatting {:?}",
+                String::from_utf8_lossy(&source)
+            ),
+            // An indent that can't be compared with the last line's may become
+            // comparable once that line is reindented.
+            Err(_) => assert!(
+                ast.tokens.iter().any(|token| matches!(
+                    token,
+                    Token::Expression(ExpressionToken::Term(TermToken::ErrorTerm(
+                        ErrorTermError::InconsistentIndentCharacters,
+                        _
+                    )))
+                )),
+                "formatting {:?}",
+                String::from_utf8_lossy(&source)
+            ),
+        }
+    }
+}
+
+#[test]
+fn already_formatted() {
+    expect_format("a = 1\nb = 2\na + b\n", "a = 1\nb = 2\na + b\n")
+}
+#[test]
+fn empty() {
+    expect_format("", "")
+}
+#[test]
+fn only_whitespace() {
+    expect_format(" \n\t\n\r\n", "")
+}
+#[test]
+fn collapse_space() {
+    expect_format("a  =\t1 +   2", "a = 1 + 2\n")
+}
+#[test]
+fn compact_terms_stay_compact() {
+    expect_format("a = 1+2  *  3", "a = 1+2 * 3\n")
+}
+#[test]
+fn prefix_and_postfix_keep_their_space() {
+    expect_format("a  -b; c++  + d", "a -b; c++ + d\n")
+}
+#[test]
+fn separators() {
+    expect_format("a ;b ,  c", "a; b, c\n")
+}
+#[test]
+fn trailing_separators() {
+    expect_format("a;  \nb ,\n", "a;\nb,\n")
+}
+#[test]
+fn colon() {
+    expect_format("a:1\nb :c", "a: 1\nb :c\n")
+}
+#[test]
+fn parentheses() {
+    expect_format("( 1 + 2 ) * f( x )", "(1 + 2) * f(x)\n")
+}
+#[test]
+fn apply_keeps_space() {
+    expect_format("f (x)", "f (x)\n")
+}
+#[test]
+fn curly_braces_keep_space() {
+    expect_format("{  1  } + {2}", "{ 1 } + {2}\n")
+}
+#[test]
+fn trailing_whitespace() {
+    expect_format("a = 1   \nb = 2\t\n", "a = 1\nb = 2\n")
+}
+#[test]
+fn line_endings() {
+    expect_format("a\r\nb\rc\n", "a\nb\nc\n")
+}
+#[test]
+fn final_newline() {
+    expect_format("a", "a\n")
+}
+#[test]
+fn blank_lines() {
+    expect_format("\n\na\n\n\n\nb\n  \n\t\nc\n\n\n", "a\n\nb\n\nc\n")
+}
+#[test]
+fn indent() {
+    expect_format(
+        "if x\n  y\n  if z\n   w\nelse\n        v\n",
+        "if x\n    y\n    if z\n        w\nelse\n    v\n",
+    )
+}
+#[test]
+fn tab_indent() {
+    expect_format("a:\n\tb:\n\t\tc\n\td\n", "a:\n    b:\n        c\n    d\n")
+}
+#[test]
+fn continued_expression_indent() {
+    expect_format("x = 1 +\n      2\ny", "x = 1 +\n    2\ny\n")
+}
+#[test]
+fn indent_in_braces() {
+    expect_format("f: {\n  x = 1\n  x\n}\nf", "f: {\n    x = 1\n    x\n}\nf\n")
+}
+#[test]
+fn comments() {
+    expect_format(
+        "# top  \na   # after a\n   # indented\nb#tight\n#end",
+        "# top\na # after a\n    # indented\nb #tight\n#end\n",
+    )
+}
+#[test]
+fn block_comments() {
+    expect_format(
+        "a  #[ x ]#+   #[y]#b\n  #[ own\n   line ]#  #[ z ]#\nc\n#[ open  ",
+        "a #[ x ]# + #[y]# b\n    #[ own\n   line ]# #[ z ]#\nc\n#[ open\n",
+    )
+}
+#[test]
+fn digit_separators_are_unchanged() {
+    expect_format("a  =  1_000 +  0xFF_FF", "a = 1_000 + 0xFF_FF\n")
+}
+#[test]
+fn comment_closes_indented_block() {
+    expect_format("a:\n  b\n# c\n  d\n", "a:\n    b\n# c\n    d\n")
+}
+#[test]
+fn block_comment_line_keeps_indented_block_open() {
+    expect_format(
+        ":a = 1 +\n    2 +\n\t#[ comment ]#\n    3\na\n",
+        ":a = 1 +\n    2 +\n    #[ comment ]#\n    3\na\n",
+    )
+}
+#[test]
+fn strings_are_unchanged() {
+    expect_format(
+        "x =  \"a  \n   b\r\n\"  +\"#{ 1  +  2 }  \"",
+        "x = \"a  \n   b\r\n\" +\"#{ 1 + 2 }  \"\n",
+    )
+}
+#[test]
+fn multiline_interpolation() {
+    expect_format("\"a#{\n  1 +\n  2\n}c\"\n", "\"a#{\n    1 +\n    2\n}c\"\n")
+}
+#[test]
+fn errors_are_kept() {
+    expect_format("a  \u{1f600}  )  ", "a \u{1f600})\n")
+}
+#[test]
+fn undent_between_levels() {
+    // c is between the indents of a and b. It still closes b's block.
+    expect_format("a\n        b\n    c\n", "a\n    b\n   c\n")
+}
+#[test]
+fn inconsistent_indent_that_would_change_meaning() {
+    // "  " can't be compared with "\t", but it can with the "    " that
+    // replaces it.
+    assert_eq!(
+        format_source(&parse_bytes(b"a\n\tb\n  c\n")),
+        Err(FormatError::ChangesMeaning)
+    );
+}
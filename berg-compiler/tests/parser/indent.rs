@@ -155,3 +155,169 @@ fn nested_parens_with_missing_indented_open_ought_to_report_the_inner_one_but_be
     )
     .to_error(CloseWithoutOpen, ")".after(")"));
 }
+
+#[test]
+fn tab_after_spaces() {
+    expect(
+        "
+(
+    1 +
+\t2
+)",
+    )
+    .to_error(InconsistentIndentCharacters, "\t")
+}
+
+#[test]
+fn spaces_after_tab() {
+    expect(
+        "
+(
+\t1 +
+    2
+)",
+    )
+    .to_error(InconsistentIndentCharacters, "    ")
+}
+
+#[test]
+fn tab_and_spaces_after_spaces_and_tab() {
+    expect(
+        "
+(
+    1 +
+ \t  2
+)",
+    )
+    .to_error(InconsistentIndentCharacters, " \t  ")
+}
+
+#[test]
+fn spaces_after_tab_indent() {
+    expect(
+        "
+\t1 +
+\t    2
+",
+    )
+    .to_yield(3)
+}
+
+#[test]
+fn multiple_undent() {
+    expect(
+        "
+:a = 1
+while { false }
+    while { false }
+        while { false }
+            a
+a",
+    )
+    .to_error(MultipleUndent, 82..82)
+}
+
+#[test]
+fn double_undent() {
+    expect(
+        "
+:a = 1
+if true
+    if true
+        a
+a",
+    )
+    .to_yield(1)
+}
+
+#[test]
+fn multiple_undent_with_comment() {
+    expect(
+        "
+:a = 1
+if true
+    if true
+        if true
+            a
+    # end
+a",
+    )
+    .to_yield(1)
+}
+
+#[test]
+fn multiple_undent_of_continuations() {
+    expect(
+        "
+:a = 1 +
+    2 *
+        3
+a",
+    )
+    .to_yield(7)
+}
+
+#[test]
+fn multiple_undent_in_parentheses() {
+    expect(
+        "
+(
+    1 +
+        2
+)",
+    )
+    .to_yield(3)
+}
+
+#[test]
+fn multiple_undent_at_end_of_file() {
+    expect(
+        "
+1 +
+    2 +
+        3
+",
+    )
+    .to_yield(6)
+}
+
+#[test]
+fn block_comment_line_does_not_affect_indent() {
+    expect(
+        "
+:a = 1 +
+    2 +
+\t#[ comment ]#
+    3
+a
+",
+    )
+    .to_yield(6)
+}
+
+#[test]
+fn multiline_block_comment_does_not_affect_indent() {
+    expect(
+        "
+:a = 1 +
+    #[
+a comment
+  ]#
+    2
+a
+",
+    )
+    .to_yield(3)
+}
+
+#[test]
+fn block_comment_before_indented_line() {
+    expect(
+        "
+:a = 1 +
+    #[ comment ]# 2
+a
+",
+    )
+    .to_yield(3)
+}
@@ -1,9 +1,20 @@
+mod ast_binary;
+mod ast_builder;
 mod comments;
+mod columns;
+mod concrete_syntax_tree;
+mod corpus;
+mod format;
 mod indent;
 mod newline_sequences;
 mod parentheses;
 mod parse_errors;
+mod position_index;
 mod precedence;
+mod reparse;
 mod semicolon_sequences;
 mod space_expressions;
 mod space_only;
+mod text_blocks;
+mod square_brackets;
+mod operators;
@@ -1,4 +1,7 @@
 use crate::*;
+use berg_parser::parse;
+use berg_util::to_indexed_cow;
+use std::borrow::Cow;
 
 #[test]
 fn left_newline() {
@@ -51,3 +54,15 @@ fn newline_sequence_or_and_ge_plus_mul() {
 fn newline_sequence_or_and_le_plus_mul() {
     expect("1*2+3<=4&&true||false\nfalse||true&&4<=3+2*1").to_yield(true)
 }
+
+#[test]
+fn line_starts_are_recorded_once() {
+    let ast = parse(to_indexed_cow(Cow::Owned(b"1\n\n2\n".to_vec())));
+    let line_starts: Vec<usize> = ast
+        .char_data
+        .line_starts
+        .iter()
+        .map(|&start| start.into())
+        .collect();
+    assert_eq!(line_starts, [0, 2, 3, 5]);
+}
@@ -0,0 +1,174 @@
+use super::corpus::parse_bytes_with_operators;
+use crate::*;
+use berg_parser::{
+    content_hash, parse, reparse, Associativity, Ast, ByteIndex,
+    ExpressionTreeWalker, OperatorTable, ParseCache, Precedence, RelativePrecedence,
+};
+use berg_util::to_indexed_cow;
+use std::borrow::Cow;
+use std::fs;
+use Associativity::*;
+use RelativePrecedence::*;
+
+fn operators(declarations: &[(&str, RelativePrecedence, Associativity)]) -> OperatorTable {
+    let mut operators = OperatorTable::default();
+    for &(name, precedence, associativity) in declarations {
+        operators.declare(name, precedence, associativity);
+    }
+    operators
+}
+
+fn power() -> OperatorTable {
+    operators(&[("**", TighterThan(Precedence::TimesDivide), Right)])
+}
+
+fn pipe() -> OperatorTable {
+    operators(&[("|>", LooserThan(Precedence::Or), Left)])
+}
+
+fn expect_tree(source: &str, operators: OperatorTable, expected: &str) {
+    let ast = parse_bytes_with_operators(source.as_bytes(), operators);
+    let tree = ExpressionTreeWalker::basic(&ast, ast.root_expression()).format();
+    assert_eq!(tree.to_string(), expected, "wrong tree for {:?}", source);
+}
+
+#[test]
+fn undeclared_is_left_associative_plus_minus() {
+    expect_tree(
+        "a ** b ** c",
+        OperatorTable::default(),
+        "source{ a ** b ** c }",
+    );
+    expect_tree(
+        "a * b ** c",
+        OperatorTable::default(),
+        "source{ a * b ** c }",
+    );
+    expect_tree(
+        "a ** b * c",
+        OperatorTable::default(),
+        "source{ a ** prec(b * c) }",
+    );
+}
+#[test]
+fn declared_right_associative() {
+    expect_tree("a ** b ** c", power(), "source{ a ** prec(b ** c) }");
+}
+#[test]
+fn declared_tighter_than() {
+    expect_tree("a * b ** c", power(), "source{ a * prec(b ** c) }");
+    expect_tree("a ** b * c", power(), "source{ a ** b * c }");
+    expect_tree("a ** b . c", power(), "source{ a ** prec(b . c) }");
+}
+#[test]
+fn declared_looser_than() {
+    expect_tree("a || b |> c", pipe(), "source{ a || b |> c }");
+    expect_tree("a |> b || c", pipe(), "source{ a |> prec(b || c) }");
+    expect_tree("a |> b, c", pipe(), "source{ a |> b , c }");
+    expect_tree("a, b |> c", pipe(), "source{ a , prec(b |> c) }");
+}
+#[test]
+fn declared_same_as() {
+    let operators = operators(&[("<=>", SameAs(Precedence::Comparison), Left)]);
+    expect_tree("a + b <=> c", operators.clone(), "source{ a + b <=> c }");
+    expect_tree(
+        "a <=> b + c",
+        operators.clone(),
+        "source{ a <=> prec(b + c) }",
+    );
+    expect_tree("a <=> b == c", operators, "source{ a <=> b == c }");
+}
+#[test]
+fn redeclared_builtin() {
+    let operators = operators(&[("-", SameAs(Precedence::PlusMinus), Right)]);
+    expect_tree("a - b - c", operators, "source{ a - prec(b - c) }");
+}
+#[test]
+fn declared_identifiers_are_stable() {
+    let a = operators(&[
+        ("|>", SameAs(Precedence::Or), Left),
+        ("**", SameAs(Precedence::Dot), Right),
+    ]);
+    let b = operators(&[
+        ("|>", SameAs(Precedence::Or), Left),
+        ("**", SameAs(Precedence::Dot), Right),
+    ]);
+    let ast = parse_bytes_with_operators(b"x ** y", a.clone());
+    let mut c = a.clone();
+    let power = c.declare("**", TighterThan(Precedence::TimesDivide), Right);
+    assert_eq!(ast.identifiers.get("**"), Some(power));
+    assert_eq!(a, b);
+    assert_ne!(a, c);
+}
+#[test]
+fn reparse_keeps_operators() {
+    let ast = parse_bytes_with_operators(b"a ** b ** c", power());
+    let ast = reparse(ast, ByteIndex::from(0)..ByteIndex::from(1), b"x");
+    assert_eq!(ast.operators, power());
+    assert_eq!(ast.tokens, parse_bytes_with_operators(b"x ** b ** c", power()).tokens);
+}
+#[test]
+fn binary_keeps_operators() {
+    let source = b"a ** b ** c |> d";
+    let mut operators = power();
+    operators.declare("|>", LooserThan(Precedence::Or), Left);
+    let hash = content_hash(source);
+    let binary = parse_bytes_with_operators(source, operators.clone()).to_binary(hash);
+    let ast = Ast::from_binary(&binary, hash).unwrap();
+    assert_eq!(ast.operators, operators);
+    assert_eq!(ast.tokens, parse_bytes_with_operators(source, operators).tokens);
+}
+#[test]
+fn cache_reparses_with_different_operators() {
+    let dir =
+        std::env::temp_dir().join(format!("berg-parse-cache-{}-operators", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    let cache = ParseCache::new(&dir);
+    let source = b"a ** b ** c";
+    let plain = cache.parse(to_indexed_cow(Cow::Borrowed(source)));
+    let declared = cache.parse_with_operators(to_indexed_cow(Cow::Borrowed(source)), power());
+    assert_eq!(
+        plain.tokens,
+        parse(to_indexed_cow(Cow::Borrowed(source))).tokens
+    );
+    assert_eq!(declared.tokens, parse_bytes_with_operators(source, power()).tokens);
+    assert_eq!(declared.operators, power());
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn evaluate_declared_operator() {
+    expect("1 |> 2")
+        .with_operators(pipe())
+        .to_error(UnsupportedOperator, "|>")
+}
+#[test]
+fn evaluate_declared_operator_in_expression() {
+    expect("2 * 3 ** 4 + 1")
+        .with_operators(power())
+        .to_error(UnsupportedOperator, "**")
+}
+#[test]
+fn evaluate_redeclared_builtin() {
+    let right_minus = operators(&[("-", SameAs(Precedence::PlusMinus), Right)]);
+    expect("10 - 5 - 2").to_yield(3);
+    expect("10 - 5 - 2").with_operators(right_minus).to_yield(7)
+}
+#[test]
+fn evaluate_redeclared_builtin_precedence() {
+    let loose_times = operators(&[("*", LooserThan(Precedence::PlusMinus), Left)]);
+    expect("2 * 3 + 4").to_yield(10);
+    expect("2 * 3 + 4").with_operators(loose_times).to_yield(14)
+}
+#[test]
+fn builtin_compare() {
+    let operators = OperatorTable::default();
+    expect_tree("a + b <=> c", operators.clone(), "source{ a + b <=> c }");
+    expect_tree("a <=> b && c", operators, "source{ a <=> b && c }");
+}
+#[test]
+fn operator_field_declaration() {
+    let operators = OperatorTable::default();
+    expect_tree(":<=> = 1", operators.clone(), "source{ :<=> = 1 }");
+    expect_tree("a:-b", operators, "source{ a : auto{-b} }");
+}
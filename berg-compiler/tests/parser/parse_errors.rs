@@ -1,4 +1,6 @@
 use crate::*;
+use berg_parser::{MAX_IDENTIFIER_LENGTH, MAX_LINE_LENGTH};
+use std::path::Path;
 
 #[test]
 fn unsupported() {
@@ -129,3 +131,96 @@ fn invalid_then_unsupported_multiple() {
     ])
     .to_error(InvalidUtf8, 0..=1)
 }
+
+#[test]
+fn unsupported_combining_mark_alone() {
+    expect("\u{301}").to_error(UnsupportedCharacters, "\u{301}")
+}
+#[test]
+fn denormalized_identifier() {
+    // Decomposed "é" (e + U+0301) instead of the precomposed U+00E9
+    expect("cafe\u{301}").to_error(DenormalizedIdentifier, "cafe\u{301}")
+}
+#[test]
+fn denormalized_identifier_then_ok() {
+    expect("cafe\u{301} + 1").to_error(DenormalizedIdentifier, "cafe\u{301}")
+}
+#[test]
+fn normalized_identifier() {
+    expect("caf\u{e9}").to_error(NoSuchField, "caf\u{e9}")
+}
+#[test]
+fn incomplete_grapheme() {
+    // U+20DD COMBINING ENCLOSING CIRCLE combines with the "e" but is not XID_Continue
+    expect("e\u{20dd}").to_error(IncompleteGrapheme, "e\u{20dd}")
+}
+#[test]
+fn incomplete_grapheme_multiple() {
+    expect("ab\u{20dd}\u{20dd} + 1").to_error(IncompleteGrapheme, "ab\u{20dd}\u{20dd}")
+}
+#[test]
+fn incomplete_grapheme_keycap() {
+    expect("a = 1; a\u{20e3}").to_error(IncompleteGrapheme, "a\u{20e3}")
+}
+#[test]
+fn line_too_long() {
+    // The 1 starts just past the end of the line.
+    let source = format!("\"{}\"; 1 + 2", "a".repeat(MAX_LINE_LENGTH - 4));
+    expect(source.leak()).to_error(LineTooLong, MAX_LINE_LENGTH..)
+}
+#[test]
+fn line_too_long_on_second_line() {
+    let source = format!("1\n\"{}\"; 1 + 2\n3", "a".repeat(MAX_LINE_LENGTH - 4));
+    expect(source.leak()).to_error(LineTooLong, 2 + MAX_LINE_LENGTH..=2 + MAX_LINE_LENGTH + 4)
+}
+#[test]
+fn number_past_line_length() {
+    let source = format!("1; {}.5", "1".repeat(u16::MAX as usize));
+    expect(source.leak()).to_error(LineTooLong, 3..)
+}
+#[test]
+fn line_almost_too_long() {
+    // The 1 starts on the last character of the line.
+    let source = format!("\"{}\"; 1", "a".repeat(MAX_LINE_LENGTH - 5));
+    expect(source.leak()).to_yield(1)
+}
+#[test]
+fn line_length_counts_characters() {
+    let source = format!("\"{}\"; 1", "\u{e9}".repeat(MAX_LINE_LENGTH - 5));
+    expect(source.leak()).to_yield(1)
+}
+#[test]
+fn identifier_too_large() {
+    let source = "a".repeat(MAX_IDENTIFIER_LENGTH + 1);
+    expect(source.leak()).to_error(IdentifierTooLarge, 0..=MAX_IDENTIFIER_LENGTH)
+}
+#[test]
+fn identifier_almost_too_large() {
+    let source = "a".repeat(MAX_IDENTIFIER_LENGTH);
+    expect(source.leak()).to_error(NoSuchField, 0..MAX_IDENTIFIER_LENGTH)
+}
+#[test]
+fn source_not_found() {
+    let error = evaluate_file_with_tracer(Path::new("does/not/exist.rg"), NoTracer).unwrap_err();
+    let code = error.code().unwrap();
+    assert_eq!(code, SourceNotFound);
+    assert!(code.is_source_load_error());
+    assert!(!code.is_syntax_error());
+}
+#[test]
+fn syntax_error_codes() {
+    for code in [
+        InvalidUtf8,
+        UnclosedBlockComment,
+        LineTooLong,
+        MissingOperand,
+        MultipleUndent,
+    ] {
+        assert!(code.is_syntax_error(), "{:?}", code);
+        assert!(!code.is_source_load_error(), "{:?}", code);
+    }
+    for code in [UnsupportedOperator, DivideByZero, NoSuchField] {
+        assert!(!code.is_syntax_error(), "{:?}", code);
+        assert!(!code.is_source_load_error(), "{:?}", code);
+    }
+}
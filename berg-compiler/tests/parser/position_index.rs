@@ -0,0 +1,161 @@
+use berg_parser::{parse, Ast, AstExpressionTree, AstIndex, ByteIndex, ColumnUnit, LineColumn};
+use berg_util::to_indexed_cow;
+use std::borrow::Cow;
+
+fn parse_str(source: &str) -> Ast {
+    parse(to_indexed_cow(Cow::Owned(source.as_bytes().to_vec())))
+}
+
+fn text<'a>(source: &'a str, expression: AstExpressionTree) -> &'a str {
+    let range = expression.byte_range();
+    &source[usize::from(range.start)..usize::from(range.end)]
+}
+
+fn token_text<'a>(source: &'a str, ast: &Ast, token: AstIndex) -> &'a str {
+    let range = ast.token_range(token);
+    &source[usize::from(range.start)..usize::from(range.end)]
+}
+
+fn at(source: &str, offset: usize) -> (String, Option<String>) {
+    let ast = parse_str(source);
+    let index = ast.position_index();
+    let at = index.at(ByteIndex::from(offset)).unwrap();
+    let declaration = at
+        .declaration()
+        .map(|token| ast.token_range(token).start.to_string());
+    (text(source, at.expression()).to_string(), declaration)
+}
+
+#[test]
+fn term_at_offset() {
+    assert_eq!(at("a + 12", 4), ("12".to_string(), None));
+    assert_eq!(at("a + 12", 5), ("12".to_string(), None));
+}
+
+#[test]
+fn operator_at_offset_is_operation() {
+    assert_eq!(at("a + 12 * 3", 7).0, "12 * 3");
+    assert_eq!(at("a + 12 * 3", 2).0, "a + 12 * 3");
+}
+
+#[test]
+fn parentheses_at_offset_are_group() {
+    assert_eq!(at("(1 + 2) * 3", 0).0, "(1 + 2)");
+    assert_eq!(at("(1 + 2) * 3", 6).0, "(1 + 2)");
+}
+
+#[test]
+fn whitespace_at_offset_is_surrounding_expression() {
+    assert_eq!(at("(1  + 2) * 3", 2).0, "1  + 2");
+    assert_eq!(at("a = 1\n\nb = 2", 6).0, "a = 1\n\nb = 2");
+}
+
+#[test]
+fn nothing_at_end() {
+    let ast = parse_str("1 + 2");
+    assert!(ast.position_index().at(ByteIndex::from(5)).is_none());
+}
+
+#[test]
+fn parents_innermost_first() {
+    let source = "(1 + 2) * 3";
+    let ast = parse_str(source);
+    let index = ast.position_index();
+    let at = index.at(ByteIndex::from(5)).unwrap();
+    let parents: Vec<&str> = at
+        .parents()
+        .map(|parent| parent.skip_implicit_groups())
+        .map(|parent| text(source, parent))
+        .collect();
+    assert_eq!(parents[0], "1 + 2");
+    assert!(parents.contains(&"(1 + 2)"));
+    assert!(parents.contains(&"(1 + 2) * 3"));
+    assert_eq!(
+        at.parents().last().unwrap().root_index(),
+        ast.root_expression()
+    );
+}
+
+#[test]
+fn field_reference_declaration() {
+    let source = "a = 1\nb = a + 2";
+    assert_eq!(at(source, 10), ("a".to_string(), Some("0".to_string())));
+}
+
+#[test]
+fn field_declaration_is_its_own_declaration() {
+    let source = "a = 1\nb = a + 2";
+    assert_eq!(at(source, 0), ("a".to_string(), Some("0".to_string())));
+}
+
+#[test]
+fn colon_declaration() {
+    let source = "x: 1\ny: x";
+    assert_eq!(at(source, 8), ("x".to_string(), Some("0".to_string())));
+}
+
+#[test]
+fn keyword_has_no_declaration() {
+    let ast = parse_str("true");
+    let index = ast.position_index();
+    let at = index.at(ByteIndex::from(0)).unwrap();
+    assert!(at.field().is_some());
+    assert_eq!(at.declaration(), None);
+}
+
+#[test]
+fn parent_field_declaration() {
+    let source = "a = 1\nf = { a + 1 }";
+    assert_eq!(at(source, 12), ("a".to_string(), Some("0".to_string())));
+}
+
+#[test]
+fn local_field_declaration() {
+    let source = "a = 1\nf = { :a = 2; a }";
+    assert_eq!(at(source, 20), ("a".to_string(), Some("13".to_string())));
+}
+
+#[test]
+fn block_at_offset() {
+    let source = "a = 1\nf = { :a = 2; a }";
+    let ast = parse_str(source);
+    let index = ast.position_index();
+    let outer = index.at(ByteIndex::from(0)).unwrap().block();
+    let inner = index.at(ByteIndex::from(20)).unwrap().block();
+    assert_ne!(outer, inner);
+    assert_eq!(inner - ast.blocks[inner].parent, outer);
+}
+
+#[test]
+fn references() {
+    let source = "a = 1\nb = a + a\nf = { a }";
+    let ast = parse_str(source);
+    let index = ast.position_index();
+    let field = index.at(ByteIndex::from(0)).unwrap().field().unwrap();
+    let references: Vec<String> = index
+        .references(field)
+        .map(|token| ast.token_range(token).start.to_string())
+        .collect();
+    assert_eq!(references, ["0", "10", "14", "22"]);
+    assert!(index
+        .references(field)
+        .all(|token| token_text(source, &ast, token) == "a"));
+}
+
+#[test]
+fn at_location() {
+    let source = "s = \"é\"\nt = s";
+    let ast = parse_str(source);
+    let index = ast.position_index();
+    let location = LineColumn::new(2, 5, ColumnUnit::Chars);
+    let at = index.at_location(location).unwrap();
+    assert_eq!(token_text(source, &ast, at.token()), "s");
+    assert_eq!(
+        at.declaration().map(|token| ast.token_range(token).start),
+        Some(0.into())
+    );
+
+    let location = LineColumn::new(1, 7, ColumnUnit::Utf16);
+    let at = index.at_location(location).unwrap();
+    assert_eq!(token_text(source, &ast, at.token()), "\"é\"");
+}
@@ -0,0 +1,296 @@
+use berg_parser::{parse_with_operators, reparse, Ast, ByteIndex, OperatorTable};
+use berg_util::to_indexed_cow;
+use std::borrow::Cow;
+
+use super::corpus::{parse_bytes, SOURCES};
+
+const FRAGMENTS: [&[u8]; 27] = [
+    b"",
+    b"\n",
+    b"\r",
+    b"\r\n",
+    b" ",
+    b"    ",
+    b"\t",
+    b"{",
+    b"}",
+    b"(",
+    b")",
+    b"\"",
+    b"#{",
+    b"#",
+    b"#[",
+    b"]#",
+    b"\\",
+    b"a",
+    b"1",
+    b"_",
+    b"+",
+    b":",
+    b";",
+    b"\xc3\xa9",
+    b"\xcc\x81",
+    b"x = 1\n",
+    b"\xff",
+];
+
+fn edit(source: &[u8], start: usize, end: usize, text: &[u8]) -> Vec<u8> {
+    [&source[..start], text, &source[end..]].concat()
+}
+
+fn reparse_bytes(ast: Ast, start: usize, end: usize, text: &[u8]) -> Ast {
+    reparse(ast, ByteIndex::from(start)..ByteIndex::from(end), text)
+}
+
+fn assert_same_ast(actual: &Ast, expected: &Ast, source: &[u8]) {
+    let source = String::from_utf8_lossy(source);
+    assert_eq!(
+        actual.tokens, expected.tokens,
+        "tokens differ for {:?}",
+        source
+    );
+    assert_eq!(
+        actual.token_ranges, expected.token_ranges,
+        "token ranges differ for {:?}",
+        source
+    );
+    assert_eq!(
+        actual.blocks, expected.blocks,
+        "blocks differ for {:?}",
+        source
+    );
+    assert_eq!(
+        actual.fields, expected.fields,
+        "fields differ for {:?}",
+        source
+    );
+    assert_eq!(
+        actual.identifiers.iter().collect::<Vec<_>>(),
+        expected.identifiers.iter().collect::<Vec<_>>(),
+        "identifiers differ for {:?}",
+        source
+    );
+    assert_eq!(
+        actual.literals.iter().collect::<Vec<_>>(),
+        expected.literals.iter().collect::<Vec<_>>(),
+        "literals differ for {:?}",
+        source
+    );
+    assert_eq!(
+        actual.raw_literals, expected.raw_literals,
+        "raw literals differ for {:?}",
+        source
+    );
+    let (actual_chars, expected_chars) = (&actual.char_data, &expected.char_data);
+    assert_eq!(
+        actual_chars.size, expected_chars.size,
+        "size differs for {:?}",
+        source
+    );
+    assert_eq!(
+        actual_chars.line_starts, expected_chars.line_starts,
+        "line starts differ for {:?}",
+        source
+    );
+    assert_eq!(
+        actual_chars
+            .whitespace_characters
+            .iter()
+            .collect::<Vec<_>>(),
+        expected_chars
+            .whitespace_characters
+            .iter()
+            .collect::<Vec<_>>(),
+        "whitespace differs for {:?}",
+        source
+    );
+    assert_eq!(
+        actual_chars.whitespace_ranges, expected_chars.whitespace_ranges,
+        "whitespace ranges differ for {:?}",
+        source
+    );
+    assert_eq!(
+        actual_chars.comments, expected_chars.comments,
+        "comments differ for {:?}",
+        source
+    );
+    assert_eq!(
+        actual_chars.digit_separators, expected_chars.digit_separators,
+        "digit separators differ for {:?}",
+        source
+    );
+}
+
+fn expect_reparse(source: &'static [u8], start: usize, end: usize, text: &[u8]) {
+    let edited = edit(source, start, end, text);
+    let ast = reparse_bytes(parse_bytes(source), start, end, text);
+    assert_same_ast(&ast, &parse_bytes(&edited), &edited);
+}
+
+// Reparses with `q:` declared as a text block.
+fn expect_reparse_text_block(source: &'static [u8], start: usize, end: usize, text: &[u8]) {
+    let mut operators = OperatorTable::default();
+    operators.declare_text_block("q");
+    let parse = |source: &[u8]| {
+        parse_with_operators(to_indexed_cow(Cow::Owned(source.to_vec())), operators.clone())
+    };
+    let edited = edit(source, start, end, text);
+    let ast = reparse_bytes(parse(source), start, end, text);
+    assert_same_ast(&ast, &parse(&edited), &edited);
+}
+
+#[test]
+fn insert_in_line() {
+    expect_reparse(b"a = 1\nb = 2\na + b\n", 10, 10, b"3")
+}
+#[test]
+fn replace_line() {
+    expect_reparse(b"a = 1\nb = 2\na + b\n", 6, 11, b"c = 3")
+}
+#[test]
+fn delete_line() {
+    expect_reparse(b"a = 1\nb = 2\na + b\n", 6, 12, b"")
+}
+#[test]
+fn declare_field_used_later() {
+    expect_reparse(b"a = 1\nb = 2\nc + a\n", 6, 6, b"c = 3\n")
+}
+#[test]
+fn declare_field_not_used_later() {
+    expect_reparse(b"a = 1\nb = 2\nb + a\nd = b\n", 6, 6, b"c = 3\n")
+}
+#[test]
+fn insert_at_start() {
+    expect_reparse(b"a = 1\nb = 2\n", 0, 0, b"x\n")
+}
+#[test]
+fn insert_at_end() {
+    expect_reparse(b"a = 1\nb = 2", 11, 11, b" + 1")
+}
+#[test]
+fn delete_everything() {
+    expect_reparse(b"a = 1\nb = 2\n", 0, 12, b"")
+}
+#[test]
+fn open_string_swallows_rest() {
+    expect_reparse(b"a = 1\nb = 2\nc = 3\n", 4, 4, b"\"")
+}
+#[test]
+fn close_string() {
+    expect_reparse(b"a = \"1\nb = 2\nc = 3\n", 6, 6, b"\"")
+}
+#[test]
+fn open_block_comment_swallows_rest() {
+    expect_reparse(b"a = 1\nb = 2\nc = 3\n", 4, 4, b"#[")
+}
+#[test]
+fn close_block_comment() {
+    expect_reparse(b"a = #[1\nb = 2\nc = 3\n", 11, 11, b"]#")
+}
+#[test]
+fn edit_inside_block_comment() {
+    expect_reparse(b"a = 1 #[\nb\n  c ]#\nd\n", 12, 12, b"#[")
+}
+#[test]
+fn add_digit_separator() {
+    expect_reparse(b"a = 1000\nb = 1_000\n", 5, 5, b"_")
+}
+#[test]
+fn indent_line() {
+    expect_reparse(b"a:\n    b\nc\nd\n", 10, 10, b"    ")
+}
+#[test]
+fn unindent_line() {
+    expect_reparse(b"a:\n    b\n    c\nd\n", 10, 14, b"")
+}
+#[test]
+fn add_field_used_later() {
+    expect_reparse(b"x = 1\n\nx + y\n", 6, 6, b"y = 2")
+}
+#[test]
+fn edit_inside_braces() {
+    expect_reparse(b"f: {\n    x = 1\n    x\n}\nf\n", 13, 14, b"2 + 3")
+}
+#[test]
+fn close_brace_early() {
+    expect_reparse(b"f: {\n    x = 1\n    x\n}\nf\n", 14, 14, b"}")
+}
+#[test]
+fn edit_inside_interpolation() {
+    expect_reparse(b"\"a#{\n1 +\n2\n}c\"\nx\n", 5, 6, b"10")
+}
+#[test]
+fn carriage_return_before_newline() {
+    expect_reparse(b"a\rb\n", 2, 2, b"\n")
+}
+#[test]
+fn tabs_in_indent() {
+    expect_reparse(b"a:\n\tb\n\tc\nd\n", 7, 7, b" ")
+}
+#[test]
+fn mix_tabs_and_spaces_in_indent() {
+    expect_reparse(b"a:\n    b\n    c\n    d\ne\n", 9, 13, b"\t")
+}
+#[test]
+fn fix_mixed_indent() {
+    expect_reparse(b"a:\n    b\n\tc\n    d\ne\n", 9, 10, b"    ")
+}
+#[test]
+fn make_line_too_long() {
+    expect_reparse(b"a = 1\nb = 2 + 3\nc\n", 9, 9, &[b'1'; 5000])
+}
+
+// A small xorshift generator, so the random edits are the same every run.
+struct Random(u64);
+impl Random {
+    fn next(&mut self, below: usize) -> usize {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        (self.0 % below as u64) as usize
+    }
+}
+
+#[test]
+fn random_edits_match_full_parse() {
+    let mut random = Random(0x2545_f491_4f6c_dd1d);
+    for _ in 0..200 {
+        let mut source = SOURCES[random.next(SOURCES.len())].to_vec();
+        let mut ast = parse_bytes(&source);
+        // Edit the same AST several times, so reparsed ASTs get reparsed too.
+        for _ in 0..5 {
+            let start = random.next(source.len() + 1);
+            let end = start + random.next(source.len() - start + 1).min(8);
+            let text: Vec<u8> = (0..random.next(4))
+                .flat_map(|_| FRAGMENTS[random.next(FRAGMENTS.len())].iter().copied())
+                .collect();
+            source = edit(&source, start, end, &text);
+            ast = reparse_bytes(ast, start, end, &text);
+            assert_same_ast(&ast, &parse_bytes(&source), &source);
+        }
+    }
+}
+#[test]
+fn edit_inside_text_block() {
+    expect_reparse_text_block(b"x = q:\n    a\n    b\ny\n", 16, 17, b"( \"")
+}
+#[test]
+fn undent_text_block_line() {
+    expect_reparse_text_block(b"x = q:\n    a\n    b\ny\n", 13, 17, b"")
+}
+#[test]
+fn indent_line_into_text_block() {
+    expect_reparse_text_block(b"x = q:\n    a\n    b\ny\n", 19, 19, b"    ")
+}
+#[test]
+fn indent_line_into_text_block_after_blank_line() {
+    expect_reparse_text_block(b"x = q:\n    a\n\ny\nz\n", 14, 14, b"    ")
+}
+#[test]
+fn indent_nested_line_into_text_block() {
+    expect_reparse_text_block(b"if x\n    q:\n        a\n    y\nz\n", 26, 26, b"  ")
+}
+#[test]
+fn start_text_block() {
+    expect_reparse_text_block(b"x = r:\n    a\n    b\ny\n", 4, 5, b"q")
+}
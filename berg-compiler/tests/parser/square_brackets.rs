@@ -0,0 +1,69 @@
+use crate::*;
+use berg_parser::{parse, Ast, ExpressionTreeWalker};
+use berg_util::to_indexed_cow;
+use std::borrow::Cow;
+
+fn expect_tree(source: &str, expected: &str) {
+    let ast: Ast = parse(to_indexed_cow(Cow::Owned(source.as_bytes().to_vec())));
+    let tree = ExpressionTreeWalker::basic(&ast, ast.root_expression()).format();
+    assert_eq!(tree.to_string(), expected, "wrong tree for {:?}", source);
+}
+
+#[test]
+fn index_tree() {
+    expect_tree("a[0]", "source{ term(a <index> [0]) }")
+}
+#[test]
+fn index_binds_like_dot() {
+    expect_tree("a.b[0].c", "source{ term(a . b <index> [0] . c) }")
+}
+#[test]
+fn index_binds_tighter_than_math() {
+    expect_tree("-a[0] * 2", "source{ term(-a <index> [0]) * 2 }")
+}
+#[test]
+fn space_before_bracket_is_not_index() {
+    expect_tree("a [0]", "source{ a  [0] }")
+}
+#[test]
+fn list_tree() {
+    expect_tree("[1, 2]", "source{ [1 , 2] }")
+}
+
+#[test]
+fn list() {
+    expect("[1, 2, 3]").to_yield(tuple!(1, 2, 3))
+}
+#[test]
+fn list_single() {
+    expect("[1]").to_yield(tuple!(1))
+}
+#[test]
+fn list_empty() {
+    expect("[]").to_yield(tuple!())
+}
+#[test]
+fn list_trailing_comma() {
+    expect("[1, 2,]").to_yield(tuple!(1, 2))
+}
+#[test]
+fn list_nested() {
+    expect("[[1, 2], []]").to_yield(tuple!([1, 2], []))
+}
+
+#[test]
+fn open_square_without_close() {
+    expect("[").to_error(OpenWithoutClose, 0)
+}
+#[test]
+fn close_square_without_open() {
+    expect("]").to_error(CloseWithoutOpen, 0)
+}
+#[test]
+fn index_without_close() {
+    expect("a = 1,2; a[0").to_error(OpenWithoutClose, 10)
+}
+#[test]
+fn mismatched_brackets() {
+    expect("(1]").to_error(OpenWithoutClose, 0)
+}
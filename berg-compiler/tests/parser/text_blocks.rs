@@ -0,0 +1,150 @@
+use crate::*;
+use berg_parser::OperatorTable;
+
+fn text() -> DslHandler {
+    DslHandler::native(|text| Ok(text.text().into()))
+}
+
+// Reports every "bad" in the text as an error.
+fn no_bad() -> DslHandler {
+    DslHandler::native(|text| match text.text().find("bad") {
+        Some(start) => Err(DslError::new(start..start + 3, "bad is not allowed")),
+        None => Ok(text.text().len().into()),
+    })
+}
+
+#[test]
+fn text_block() {
+    expect("q:\n    SELECT 1")
+        .with_dsl("q", text())
+        .to_yield("SELECT 1")
+}
+#[test]
+fn text_block_lines() {
+    expect("q:\n    a\n    b\n    c")
+        .with_dsl("q", text())
+        .to_yield("a\nb\nc")
+}
+#[test]
+fn text_block_keeps_extra_indent() {
+    expect("q:\n    a\n      b\n    c")
+        .with_dsl("q", text())
+        .to_yield("a\n  b\nc")
+}
+#[test]
+fn text_block_blank_lines() {
+    expect("q:\n    a\n\n    b\n\n")
+        .with_dsl("q", text())
+        .to_yield("a\n\nb")
+}
+#[test]
+fn text_block_crlf() {
+    expect("q:\r\n    a\r\n    b")
+        .with_dsl("q", text())
+        .to_yield("a\r\nb")
+}
+#[test]
+fn text_block_is_not_parsed() {
+    expect("q:\n    ( \" } #{ 1abc")
+        .with_dsl("q", text())
+        .to_yield("( \" } #{ 1abc")
+}
+#[test]
+fn text_block_in_expression() {
+    expect("x = q:\n    hello\nx + \"!\"")
+        .with_dsl("q", text())
+        .to_yield("hello!")
+}
+#[test]
+fn text_block_in_indented_block() {
+    expect("if true\n    q:\n        inner\n")
+        .with_dsl("q", text())
+        .to_yield("inner")
+}
+#[test]
+fn text_block_ends_at_undent() {
+    expect("x = q:\n    a\n    b\ny = 2\n(x, y)")
+        .with_dsl("q", text())
+        .to_yield(tuple!["a\nb", 2])
+}
+#[test]
+fn text_block_without_block() {
+    expect("q: 1\nq + 1").with_dsl("q", text()).to_yield(2)
+}
+#[test]
+fn text_block_with_text_after_colon() {
+    expect("q: 1 +\n    2\nq").with_dsl("q", text()).to_yield(3)
+}
+#[test]
+fn undeclared_name_is_not_text_block() {
+    expect("x:\n    1 + 2\nx").with_dsl("q", text()).to_yield(3)
+}
+#[test]
+fn two_text_blocks() {
+    expect("a = q:\n    one\nb = r:\n    two\na + b")
+        .with_dsl("q", text())
+        .with_dsl("r", text())
+        .to_yield("onetwo")
+}
+#[test]
+fn text_block_value() {
+    expect("q:\n    good\n    text")
+        .with_dsl("q", no_bad())
+        .to_yield(9)
+}
+#[test]
+fn text_block_error_points_into_source() {
+    expect("q:\n    good\n    bad text")
+        .with_dsl("q", no_bad())
+        .to_error(InvalidDslText, "bad")
+}
+#[test]
+fn text_block_error_in_indented_line() {
+    expect("q:\n    good\n        not bad")
+        .with_dsl("q", no_bad())
+        .to_error(InvalidDslText, "bad")
+}
+#[test]
+fn berg_dsl() {
+    expect("q:\n    hello")
+        .with_dsl("q", DslHandler::berg(":text + \"!\""))
+        .to_yield("hello!")
+}
+#[test]
+fn berg_dsl_runs_each_block() {
+    expect("a = q:\n    one\nb = q:\n    two\na + b")
+        .with_dsl("q", DslHandler::berg(":text + \"!\""))
+        .to_yield("one!two!")
+}
+#[test]
+fn berg_dsl_error_points_into_source() {
+    expect("q:\n    hello\n    there")
+        .with_dsl("q", DslHandler::berg("throw (6...11, \"not allowed\")"))
+        .to_error(InvalidDslText, "there")
+}
+#[test]
+fn berg_dsl_error_inclusive_range() {
+    expect("q:\n    hello\n    there")
+        .with_dsl("q", DslHandler::berg("throw (1..2, \"el is not allowed\")"))
+        .to_error(InvalidDslText, "el")
+}
+#[test]
+fn berg_dsl_error_message_points_at_text() {
+    expect("q:\n    hello\n    there")
+        .with_dsl("q", DslHandler::berg("throw \"not allowed\""))
+        .to_error(InvalidDslText, "hello\n    there")
+}
+#[test]
+fn berg_dsl_handler_error_is_in_handler() {
+    expect("q:\n    hello\n    there")
+        .with_dsl("q", DslHandler::berg(":text\n1 / 0"))
+        .to_error_in_dsl("q", DivideByZero, "0")
+}
+#[test]
+fn unknown_dsl() {
+    let mut operators = OperatorTable::default();
+    operators.declare_text_block("q");
+    expect("q:\n    hello")
+        .with_operators(operators)
+        .to_error(UnknownDsl, 0..)
+}
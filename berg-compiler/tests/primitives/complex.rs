@@ -0,0 +1,257 @@
+mod literals {
+    use crate::*;
+
+    #[test]
+    fn imaginary() {
+        expect("12i").to_yield(Complex::new(
+            BigRational::from_str("0").unwrap(),
+            BigRational::from_str("12").unwrap(),
+        ))
+    }
+    #[test]
+    fn imaginary_leading_zero() {
+        expect("012i").to_yield(Complex::new(
+            BigRational::from_str("0").unwrap(),
+            BigRational::from_str("12").unwrap(),
+        ))
+    }
+    #[test]
+    fn imaginary_float() {
+        expect("12.34i").to_yield(Complex::new(
+            BigRational::from_str("0").unwrap(),
+            BigRational::from_str("1234/100").unwrap(),
+        ))
+    }
+    #[test]
+    fn imaginary_exponent() {
+        expect("12e2i").to_yield(Complex::new(
+            BigRational::from_str("0").unwrap(),
+            BigRational::from_str("1200").unwrap(),
+        ))
+    }
+    #[test]
+    fn imaginary_float_negative_exponent() {
+        expect("1.2e-3i").to_yield(Complex::new(
+            BigRational::from_str("0").unwrap(),
+            BigRational::from_str("12/10000").unwrap(),
+        ))
+    }
+    #[test]
+    fn imaginary_float_uppercase_exponent() {
+        expect("1.2E+3i").to_yield(Complex::new(
+            BigRational::from_str("0").unwrap(),
+            BigRational::from_str("1200").unwrap(),
+        ))
+    }
+    #[test]
+    fn imaginary_big() {
+        expect("1938523759123651983641928734198741987343198741398741984719874139874139847139874319874139847139812795148619560i").to_yield(Complex::new(
+            BigRational::from_str("0").unwrap(),
+            BigRational::from_str("1938523759123651983641928734198741987343198741398741984719874139874139847139874319874139847139812795148619560").unwrap(),
+        ))
+    }
+    #[test]
+    fn zero_imaginary_is_rational() {
+        expect("0i").to_yield(0)
+    }
+    #[test]
+    fn imaginary_surrounded_by_comments() {
+        expect("# comment\n 1.2i# comment\n ").to_yield(Complex::new(
+            BigRational::from_str("0").unwrap(),
+            BigRational::from_str("12/10").unwrap(),
+        ))
+    }
+    #[test]
+    fn imaginary_then_field() {
+        expect("a: 1i\na == 1i").to_yield(true)
+    }
+}
+
+mod literal_errors {
+    use crate::*;
+
+    #[test]
+    fn uppercase_i() {
+        expect("12I").to_error(ImaginaryWithUppercaseI, 0..=2)
+    }
+    #[test]
+    fn uppercase_i_float() {
+        expect("12.34e56I").to_error(ImaginaryWithUppercaseI, 0..=8)
+    }
+    #[test]
+    fn imaginary_followed_by_letters() {
+        expect("12in").to_error(IdentifierStartsWithNumber, 0..=3)
+    }
+    #[test]
+    fn imaginary_twice() {
+        expect("12ii").to_error(IdentifierStartsWithNumber, 0..=3)
+    }
+    #[test]
+    fn hexadecimal_imaginary() {
+        expect("0x1i").to_error(IdentifierStartsWithNumber, 0..=3)
+    }
+}
+
+mod operators {
+    use crate::*;
+
+    #[test]
+    fn plus() {
+        expect("1 + 2i").to_yield(Complex::new(
+            BigRational::from_str("1").unwrap(),
+            BigRational::from_str("2").unwrap(),
+        ))
+    }
+    #[test]
+    fn plus_imaginary() {
+        expect("1i + 2i").to_yield(Complex::new(
+            BigRational::from_str("0").unwrap(),
+            BigRational::from_str("3").unwrap(),
+        ))
+    }
+    #[test]
+    fn imaginary_plus_rational() {
+        expect("2i + 1/2").to_yield(Complex::new(
+            BigRational::from_str("1/2").unwrap(),
+            BigRational::from_str("2").unwrap(),
+        ))
+    }
+    #[test]
+    fn minus() {
+        expect("1 - 2i").to_yield(Complex::new(
+            BigRational::from_str("1").unwrap(),
+            BigRational::from_str("-2").unwrap(),
+        ))
+    }
+    #[test]
+    fn minus_cancels_imaginary() {
+        expect("(1 + 2i) - 2i").to_yield(1)
+    }
+    #[test]
+    fn times() {
+        expect("(1 + 2i) * (3 + 4i)").to_yield(Complex::new(
+            BigRational::from_str("-5").unwrap(),
+            BigRational::from_str("10").unwrap(),
+        ))
+    }
+    #[test]
+    fn i_squared() {
+        expect("1i * 1i").to_yield(-1)
+    }
+    #[test]
+    fn rational_times_imaginary() {
+        expect("3 * 2i").to_yield(Complex::new(
+            BigRational::from_str("0").unwrap(),
+            BigRational::from_str("6").unwrap(),
+        ))
+    }
+    #[test]
+    fn divide() {
+        expect("(1 + 2i) / (3 + 4i)").to_yield(Complex::new(
+            BigRational::from_str("11/25").unwrap(),
+            BigRational::from_str("2/25").unwrap(),
+        ))
+    }
+    #[test]
+    fn rational_divide_imaginary() {
+        expect("1 / 2i").to_yield(Complex::new(
+            BigRational::from_str("0").unwrap(),
+            BigRational::from_str("-1/2").unwrap(),
+        ))
+    }
+    #[test]
+    fn divide_by_zero() {
+        expect("1i / 0").to_error(DivideByZero, 5)
+    }
+    #[test]
+    fn divide_by_zero_imaginary() {
+        expect("1 / 0i").to_error(DivideByZero, 4..=5)
+    }
+    #[test]
+    fn negative() {
+        expect("-2i").to_yield(Complex::new(
+            BigRational::from_str("0").unwrap(),
+            BigRational::from_str("-2").unwrap(),
+        ))
+    }
+    #[test]
+    fn equal() {
+        expect("1 + 2i == 1 + 2i").to_yield(true)
+    }
+    #[test]
+    fn equal_different() {
+        expect("1 + 2i == 1 + 3i").to_yield(false)
+    }
+    #[test]
+    fn equal_rational() {
+        expect("2i == 2").to_yield(false)
+    }
+    #[test]
+    fn rational_equal_imaginary() {
+        expect("2 == 2i").to_yield(false)
+    }
+    #[test]
+    fn not_equal() {
+        expect("1i != 2i").to_yield(true)
+    }
+    #[test]
+    fn equal_string() {
+        expect(r#"1i == "1i""#).to_yield(false)
+    }
+    #[test]
+    fn plus_string() {
+        expect(r#"1i + "a""#).to_error(BadOperandType, 5..=7)
+    }
+    #[test]
+    fn greater_than() {
+        expect("2i > 1i").to_error(UnsupportedOperator, 3)
+    }
+    #[test]
+    fn rational_greater_than_imaginary() {
+        expect("2 > 1i").to_error(BadOperandType, 4..=5)
+    }
+    #[test]
+    fn plus_assign() {
+        expect("a = 1; a += 2i; a").to_yield(Complex::new(
+            BigRational::from_str("1").unwrap(),
+            BigRational::from_str("2").unwrap(),
+        ))
+    }
+}
+
+mod display {
+    use crate::*;
+
+    fn display(re: &str, im: &str) -> String {
+        BergVal::from(Complex::new(
+            BigRational::from_str(re).unwrap(),
+            BigRational::from_str(im).unwrap(),
+        ))
+        .to_string()
+    }
+
+    #[test]
+    fn imaginary() {
+        assert_eq!(display("0", "2"), "2i")
+    }
+    #[test]
+    fn negative_imaginary() {
+        assert_eq!(display("0", "-2"), "-2i")
+    }
+    #[test]
+    fn fractional_imaginary() {
+        assert_eq!(display("0", "3/2"), "(3/2)i")
+    }
+    #[test]
+    fn negative_fractional_imaginary() {
+        assert_eq!(display("0", "-3/2"), "-(3/2)i")
+    }
+    #[test]
+    fn real_and_imaginary() {
+        assert_eq!(display("1", "2"), "1+2i")
+    }
+    #[test]
+    fn real_minus_fractional_imaginary() {
+        assert_eq!(display("1/2", "-3/2"), "1/2-(3/2)i")
+    }
+}
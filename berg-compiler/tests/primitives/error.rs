@@ -118,7 +118,7 @@ mod operators_comparison {
 
     #[test]
     fn less_or_equal_error_empty() {
-        expect("()<=1/0").to_error(UnsupportedOperator, 2..=3)
+        expect("()<=1/0").to_error(DivideByZero, 6)
     }
 
     #[test]
@@ -0,0 +1,164 @@
+use crate::*;
+
+#[test]
+fn tuple_index() {
+    expect("a = 1,2,3; a[0]").to_yield(1)
+}
+#[test]
+fn tuple_index_last() {
+    expect("a = 1,2,3; a[2]").to_yield(3)
+}
+#[test]
+fn tuple_index_negative() {
+    expect("a = 1,2,3; a[-1]").to_yield(3)
+}
+#[test]
+fn tuple_index_negative_first() {
+    expect("a = 1,2,3; a[-3]").to_yield(1)
+}
+#[test]
+fn tuple_index_expression() {
+    expect("a = 1,2,3; i = 1; a[i + 1]").to_yield(3)
+}
+#[test]
+fn list_index() {
+    expect("[1,2,3][1]").to_yield(2)
+}
+#[test]
+fn nested_index() {
+    expect("a = [[1,2],[3,4]]; a[1][0]").to_yield(3)
+}
+#[test]
+fn index_then_math() {
+    expect("a = 1,2,3; a[1] * 10").to_yield(20)
+}
+
+#[test]
+fn tuple_slice() {
+    expect("a = 1,2,3,4; a[1,3]").to_yield(tuple!(2, 3))
+}
+#[test]
+fn tuple_slice_to_end() {
+    expect("a = 1,2,3,4; a[2,4]").to_yield(tuple!(3, 4))
+}
+#[test]
+fn tuple_slice_negative() {
+    expect("a = 1,2,3,4; a[-3,-1]").to_yield(tuple!(2, 3))
+}
+#[test]
+fn tuple_slice_empty() {
+    expect("a = 1,2,3,4; a[2,2]").to_yield(tuple!())
+}
+
+#[test]
+fn tuple_index_out_of_range() {
+    expect("a = 1,2,3; a[3]").to_error(IndexOutOfRange, "[3]")
+}
+#[test]
+fn tuple_index_negative_out_of_range() {
+    expect("a = 1,2,3; a[-4]").to_error(IndexOutOfRange, "[-4]")
+}
+#[test]
+fn empty_tuple_index() {
+    expect("a = (); a[0]").to_error(IndexOutOfRange, "[0]")
+}
+#[test]
+fn tuple_slice_out_of_range() {
+    expect("a = 1,2,3; a[1,4]").to_error(IndexOutOfRange, "[1,4]")
+}
+#[test]
+fn tuple_slice_backwards() {
+    expect("a = 1,2,3; a[2,1]").to_error(IndexOutOfRange, "[2,1]")
+}
+#[test]
+fn tuple_index_not_integer() {
+    expect("a = 1,2,3; a[1.5]").to_error(BadOperandType, "[1.5]")
+}
+
+#[test]
+fn tuple_assign_index() {
+    expect("a = 1,2,3; a[1] = 20; a").to_yield(tuple!(1, 20, 3))
+}
+#[test]
+fn tuple_assign_index_negative() {
+    expect("a = 1,2,3; a[-1] = 30; a").to_yield(tuple!(1, 2, 30))
+}
+#[test]
+fn tuple_assign_index_plus() {
+    expect("a = 1,2,3; a[0] += 10; a").to_yield(tuple!(11, 2, 3))
+}
+#[test]
+fn tuple_assign_nested_index() {
+    expect("a = [[1,2],[3,4]]; a[1][0] = 30; a").to_yield(tuple!([1, 2], [30, 4]))
+}
+#[test]
+fn tuple_assign_slice() {
+    expect("a = 1,2,3,4; a[1,3] = 5,6,7; a").to_yield(tuple!(1, 5, 6, 7, 4))
+}
+#[test]
+fn tuple_assign_index_out_of_range() {
+    expect("a = 1,2,3; a[3] = 4").to_error(IndexOutOfRange, "[3]")
+}
+#[test]
+fn tuple_assign_index_leaves_copy_alone() {
+    expect("a = 1,2,3; b = a; b[0] = 10; a").to_yield(tuple!(1, 2, 3))
+}
+
+#[test]
+fn string_index() {
+    expect(r#""abc"[1]"#).to_yield("b")
+}
+#[test]
+fn string_index_negative() {
+    expect(r#""abc"[-1]"#).to_yield("c")
+}
+#[test]
+fn string_index_unicode() {
+    expect(r#""héllo"[1]"#).to_yield("é")
+}
+#[test]
+fn string_slice() {
+    expect(r#""hello"[1,4]"#).to_yield("ell")
+}
+#[test]
+fn string_index_out_of_range() {
+    expect(r#""abc"[3]"#).to_error(IndexOutOfRange, "[3]")
+}
+#[test]
+fn string_assign_index() {
+    expect(r#"a = "cat"; a[0] = "b"; a"#).to_yield("bat")
+}
+#[test]
+fn string_assign_slice() {
+    expect(r#"a = "cat"; a[1,3] = "og"; a"#).to_yield("cog")
+}
+
+#[test]
+fn object_index() {
+    expect(r#"a = { :x = 10 }; a["x"]"#).to_yield(10)
+}
+#[test]
+fn object_assign_index() {
+    expect(r#"a = { :x = 10 }; a["x"] = 20; a.x"#).to_yield(20)
+}
+#[test]
+fn object_index_missing_field() {
+    expect(r#"a = { :x = 10 }; a["y"]"#).to_error(IndexOutOfRange, r#"["y"]"#)
+}
+#[test]
+fn object_index_result() {
+    expect("a = { 1,2,3 }; a[1]").to_yield(2)
+}
+#[test]
+fn object_field_index() {
+    expect("a = { :x = 1,2,3 }; a.x[1] = 20; a.x").to_yield(tuple!(1, 20, 3))
+}
+
+#[test]
+fn number_index() {
+    expect("1[0]").to_error(UnsupportedOperator, 1)
+}
+#[test]
+fn index_missing_operand() {
+    expect("a = 1,2,3; a[]").to_error(BadOperandType, "[]")
+}
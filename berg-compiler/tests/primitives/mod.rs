@@ -1,4 +1,9 @@
 mod boolean;
+mod complex;
 mod error;
+mod index;
 mod number;
+mod ordering;
+mod range;
+mod string;
 mod tuple;
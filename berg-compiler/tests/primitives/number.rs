@@ -15,11 +15,348 @@ mod literals {
             BigRational::from_str("999999999999999999999999999999999999999999999").unwrap(),
         )
     }
+    #[test]
+    fn float() {
+        expect("12.34").to_yield(BigRational::from_str("1234/100").unwrap())
+    }
+    #[test]
+    fn float_zero() {
+        expect("0.0").to_yield(0)
+    }
+    #[test]
+    fn float_integral() {
+        expect("2.0").to_yield(2)
+    }
+    #[test]
+    fn float_exponent() {
+        expect("12e3").to_yield(12000)
+    }
+    #[test]
+    fn float_exponent_uppercase() {
+        expect("12E3").to_yield(12000)
+    }
+    #[test]
+    fn float_exponent_plus() {
+        expect("12e+3").to_yield(12000)
+    }
+    #[test]
+    fn float_exponent_minus() {
+        expect("12e-3").to_yield(BigRational::from_str("12/1000").unwrap())
+    }
+    #[test]
+    fn float_exponent_zero() {
+        expect("0e0").to_yield(0)
+    }
+    #[test]
+    fn float_fraction_exponent() {
+        expect("12.34e2").to_yield(1234)
+    }
+    #[test]
+    fn float_fraction_negative_exponent() {
+        expect("12.34E-5").to_yield(BigRational::from_str("1234/10000000").unwrap())
+    }
+    #[test]
+    fn float_huge_exponent() {
+        expect("1e56").to_yield(
+            BigRational::from_str("100000000000000000000000000000000000000000000000000000000")
+                .unwrap(),
+        )
+    }
+    #[test]
+    fn float_exact() {
+        expect("0.1 + 0.2 == 0.3").to_yield(true)
+    }
+    #[test]
+    fn float_negative() {
+        expect("-1.5").to_yield(BigRational::from_str("-3/2").unwrap())
+    }
+    #[test]
+    fn hexadecimal() {
+        expect("0x1F").to_yield(31)
+    }
+    #[test]
+    fn hexadecimal_mixed_case() {
+        expect("0xAbCdEf").to_yield(11259375)
+    }
+    #[test]
+    fn hexadecimal_zero() {
+        expect("0x00").to_yield(0)
+    }
+    #[test]
+    fn hexadecimal_huge() {
+        expect("0x7631254ae6f9167bfA776ed90123d6bf9167bfA7").to_yield(
+            BigRational::from_str("674756896114431468655915179679375719087106146215").unwrap(),
+        )
+    }
+    #[test]
+    fn hexadecimal_with_e() {
+        expect("0x1e5").to_yield(485)
+    }
+    #[test]
+    fn octal() {
+        expect("0o17").to_yield(15)
+    }
+    #[test]
+    fn octal_all_digits() {
+        expect("0o01234567").to_yield(342391)
+    }
+    #[test]
+    fn binary() {
+        expect("0b101").to_yield(5)
+    }
+    #[test]
+    fn binary_zero() {
+        expect("0b0").to_yield(0)
+    }
+    #[test]
+    fn negative_hexadecimal() {
+        expect("-0x10").to_yield(-16)
+    }
+    #[test]
+    fn radix_math() {
+        expect("0x10 + 0o10 + 0b10").to_yield(26)
+    }
+    #[test]
+    fn leading_zero_decimal() {
+        expect("010").to_yield(10)
+    }
+    #[test]
+    fn integer_dot_identifier() {
+        expect("1.a").to_error(NoSuchPublicField, 2)
+    }
+    #[test]
+    fn digit_separators() {
+        expect("1_000_000").to_yield(1000000)
+    }
+    #[test]
+    fn digit_separators_every_digit() {
+        expect("1_2_3").to_yield(123)
+    }
+    #[test]
+    fn digit_separators_float() {
+        expect("1_000.000_5").to_yield(BigRational::from_str("10000005/10000").unwrap())
+    }
+    #[test]
+    fn digit_separators_exponent() {
+        expect("1e1_0").to_yield(10000000000_u64)
+    }
+    #[test]
+    fn digit_separators_imaginary() {
+        expect("1_0i * 1_0i").to_yield(-100)
+    }
+    #[test]
+    fn digit_separators_radix() {
+        expect("0xFF_FF + 0o7_7 + 0b1010_1010").to_yield(65535 + 63 + 170)
+    }
+    #[test]
+    fn digit_separators_math() {
+        expect("1_000 + 2_000").to_yield(3000)
+    }
+    #[test]
+    fn leading_underscore_is_identifier() {
+        expect("_1").to_error(NoSuchField, 0..=1)
+    }
+    #[test]
+    fn dot_underscore_is_field() {
+        expect("1._5").to_error(NoSuchPublicField, 2..=3)
+    }
+    #[test]
+    fn float_parts() {
+        use berg_parser::FloatParts;
+        let parts = |literal: &'static str| FloatParts::new(literal).split(literal);
+        assert_eq!(parts("12.34E-56"), ("12", "34", "-56"));
+        assert_eq!(parts("12e+5"), ("12", "", "+5"));
+        assert_eq!(parts("0.5"), ("0", "5", ""));
+        assert_eq!(parts("1.5e3i"), ("1", "5", "3"));
+        assert_eq!(parts("12i"), ("12", "", ""));
+    }
+}
+
+mod literal_errors {
+    use crate::*;
+
+    #[test]
+    fn missing_exponent() {
+        expect("1e").to_error(MissingExponent, 0..=1)
+    }
+    #[test]
+    fn missing_exponent_uppercase() {
+        expect("1E").to_error(MissingExponent, 0..=1)
+    }
+    #[test]
+    fn missing_exponent_float() {
+        expect("0.0e").to_error(MissingExponent, 0..=3)
+    }
+    #[test]
+    fn missing_exponent_sign() {
+        expect("0.0e+").to_error(MissingExponent, 0..=3)
+    }
+    #[test]
+    fn missing_exponent_sign_integer() {
+        expect("1e+").to_error(MissingExponent, 0..=1)
+    }
+    #[test]
+    fn missing_exponent_sign_then_field() {
+        expect("1e-a").to_error(MissingExponent, 0..=1)
+    }
+    #[test]
+    fn missing_exponent_double_sign() {
+        expect("0.0e-+1").to_error(MissingExponent, 0..=3)
+    }
+    #[test]
+    fn missing_exponent_space() {
+        expect("0e 1").to_error(MissingExponent, 0..=1)
+    }
+    #[test]
+    fn missing_exponent_dot() {
+        expect("0.0e.1").to_error(MissingExponent, 0..=3)
+    }
+    #[test]
+    fn exponent_too_large() {
+        expect("1e1294761873468713641837634871648316431").to_error(ExponentTooLarge, 0..)
+    }
+    #[test]
+    fn float_followed_by_identifier() {
+        expect("0.0abc").to_error(IdentifierStartsWithNumber, 0..=5)
+    }
+    #[test]
+    fn exponent_followed_by_identifier() {
+        expect("0.0e0abc").to_error(IdentifierStartsWithNumber, 0..=7)
+    }
+    #[test]
+    fn integer_e_identifier() {
+        expect("0ea").to_error(IdentifierStartsWithNumber, 0..=2)
+    }
+    #[test]
+    fn uppercase_hexadecimal_prefix() {
+        expect("0X1F").to_error(UppercaseHexadecimalPrefix, 0..=3)
+    }
+    #[test]
+    fn uppercase_octal_prefix() {
+        expect("0O17").to_error(UppercaseOctalPrefix, 0..=3)
+    }
+    #[test]
+    fn uppercase_binary_prefix() {
+        expect("0B101").to_error(UppercaseBinaryPrefix, 0..=4)
+    }
+    #[test]
+    fn octal_digit_out_of_range() {
+        expect("0o18").to_error(DigitOutOfRange, 0..=3)
+    }
+    #[test]
+    fn octal_starts_out_of_range() {
+        expect("0o9").to_error(DigitOutOfRange, 0..=2)
+    }
+    #[test]
+    fn binary_digit_out_of_range() {
+        expect("0b102").to_error(DigitOutOfRange, 0..=4)
+    }
+    #[test]
+    fn uppercase_prefix_and_out_of_range() {
+        expect("0B2").to_error(UppercaseBinaryPrefix, 0..=2)
+    }
+    #[test]
+    fn hexadecimal_prefix_only() {
+        expect("0x").to_error(MissingRadixDigits, 0..=1)
+    }
+    #[test]
+    fn octal_prefix_only() {
+        expect("0o").to_error(MissingRadixDigits, 0..=1)
+    }
+    #[test]
+    fn binary_prefix_only() {
+        expect("0b").to_error(MissingRadixDigits, 0..=1)
+    }
+    #[test]
+    fn uppercase_prefix_only() {
+        expect("0X").to_error(MissingRadixDigits, 0..=1)
+    }
+    #[test]
+    fn prefix_only_then_operator() {
+        expect("0x+1").to_error(MissingRadixDigits, 0..=1)
+    }
+    #[test]
+    fn prefix_followed_by_identifier() {
+        expect("0xg").to_error(IdentifierStartsWithNumber, 0..=2)
+    }
+    #[test]
+    fn hexadecimal_followed_by_identifier() {
+        expect("0x1g").to_error(IdentifierStartsWithNumber, 0..=3)
+    }
+    #[test]
+    fn binary_followed_by_identifier() {
+        expect("0b1a").to_error(IdentifierStartsWithNumber, 0..=3)
+    }
+    #[test]
+    fn trailing_dot() {
+        expect("0.").to_error(UnsupportedOperator, 1)
+    }
+    #[test]
+    fn doubled_digit_separator() {
+        expect("1__000").to_error(DoubledDigitSeparator, 0..=5)
+    }
+    #[test]
+    fn doubled_digit_separator_in_fraction() {
+        expect("1.0__5 + 1").to_error(DoubledDigitSeparator, 0..=5)
+    }
+    #[test]
+    fn trailing_digit_separator() {
+        expect("1_ + 1").to_error(TrailingDigitSeparator, 0..=1)
+    }
+    #[test]
+    fn trailing_digit_separator_at_end() {
+        expect("1_000_").to_error(TrailingDigitSeparator, 0..=5)
+    }
+    #[test]
+    fn trailing_digit_separator_before_dot() {
+        expect("1_.5").to_error(TrailingDigitSeparator, 0..=1)
+    }
+    #[test]
+    fn trailing_digit_separator_before_exponent() {
+        expect("1_e5").to_error(TrailingDigitSeparator, 0..=3)
+    }
+    #[test]
+    fn trailing_digit_separator_in_fraction() {
+        expect("1.5_").to_error(TrailingDigitSeparator, 0..=3)
+    }
+    #[test]
+    fn trailing_digit_separator_before_imaginary() {
+        expect("1_i").to_error(TrailingDigitSeparator, 0..=2)
+    }
+    #[test]
+    fn trailing_digit_separator_radix() {
+        expect("0x1F_").to_error(TrailingDigitSeparator, 0..=4)
+    }
+    #[test]
+    fn leading_digit_separator_in_exponent() {
+        expect("1e_5").to_error(LeadingDigitSeparator, 0..=3)
+    }
+    #[test]
+    fn leading_digit_separator_in_signed_exponent() {
+        expect("1.5e-_5").to_error(LeadingDigitSeparator, 0..=6)
+    }
+    #[test]
+    fn digit_separator_after_radix_prefix() {
+        expect("0x_1F").to_error(DigitSeparatorNextToRadixPrefix, 0..=4)
+    }
+    #[test]
+    fn digit_separator_before_radix_prefix() {
+        expect("0_b101").to_error(DigitSeparatorNextToRadixPrefix, 0..=5)
+    }
+    #[test]
+    fn digit_separator_then_identifier() {
+        expect("1_000abc").to_error(IdentifierStartsWithNumber, 0..=7)
+    }
 }
 
 mod operators_comparison {
     use crate::*;
 
+    #[test]
+    fn greater_than_float() {
+        expect("1.5>1.25").to_yield(true)
+    }
+
     #[test]
     fn equal_0_0() {
         expect("0==0").to_yield(true)
@@ -533,6 +870,27 @@ mod operators_logical {
 mod operators_math {
     use crate::*;
 
+    #[test]
+    fn add_float_float() {
+        expect("1.5+1.25").to_yield(BigRational::from_str("11/4").unwrap())
+    }
+    #[test]
+    fn sub_float_integer() {
+        expect("1.5-1").to_yield(BigRational::from_str("1/2").unwrap())
+    }
+    #[test]
+    fn mul_float_exponent() {
+        expect("1.5*2e2").to_yield(300)
+    }
+    #[test]
+    fn div_float_float() {
+        expect("1.5/0.5").to_yield(3)
+    }
+    #[test]
+    fn div_float_zero() {
+        expect("1.5/0.0").to_error(DivideByZero, 4..=6)
+    }
+
     #[test]
     fn add0_0() {
         expect("0+0").to_yield(0)
@@ -0,0 +1,172 @@
+use crate::*;
+
+#[test]
+fn compare_less() {
+    expect("1 <=> 2").to_yield(-1)
+}
+#[test]
+fn compare_equal() {
+    expect("2 <=> 2").to_yield(0)
+}
+#[test]
+fn compare_greater() {
+    expect("3 <=> 2").to_yield(1)
+}
+#[test]
+fn compare_fractions() {
+    expect("1.5 <=> 1.25").to_yield(1)
+}
+#[test]
+fn compare_strings() {
+    expect(r#""abc" <=> "abd""#).to_yield(-1)
+}
+#[test]
+fn compare_binds_like_comparison() {
+    expect("1 + 1 <=> 2").to_yield(0)
+}
+#[test]
+fn compare_result_is_a_number() {
+    expect("(1 <=> 2) + 1").to_yield(0)
+}
+#[test]
+fn compare_number_to_string() {
+    expect(r#"1 <=> "a""#).to_error(BadOperandType, 6..=8)
+}
+// Booleans deliberately have no ordering.
+#[test]
+fn compare_booleans() {
+    expect("true <=> false").to_error(UnsupportedOperator, 5..=7)
+}
+#[test]
+fn compare_complex() {
+    expect("1i <=> 2i").to_error(UnsupportedOperator, 3..=5)
+}
+#[test]
+fn boolean_less_than() {
+    expect("false < true").to_error(UnsupportedOperator, 6)
+}
+
+#[test]
+fn compare_tuples_equal() {
+    expect("(1,2) <=> (1,2)").to_yield(0)
+}
+#[test]
+fn compare_tuples_first_differs() {
+    expect("(1,9) <=> (2,0)").to_yield(-1)
+}
+#[test]
+fn compare_tuples_last_differs() {
+    expect("(1,2,3) <=> (1,2,1)").to_yield(1)
+}
+#[test]
+fn compare_tuples_prefix() {
+    expect("(1,2) <=> (1,2,3)").to_yield(-1)
+}
+#[test]
+fn compare_tuples_longer() {
+    expect("(1,2,3) <=> (1,2)").to_yield(1)
+}
+#[test]
+fn compare_nested_tuples() {
+    expect("((1,2),3) <=> ((1,3),0)").to_yield(-1)
+}
+#[test]
+fn compare_tuples_of_strings() {
+    expect(r#"("a","b") <=> ("a","a")"#).to_yield(1)
+}
+#[test]
+fn tuple_less_than() {
+    expect("(1,2) < (1,3)").to_yield(true)
+}
+#[test]
+fn tuple_greater_or_equal() {
+    expect("(1,2) >= (1,2)").to_yield(true)
+}
+#[test]
+fn tuple_less_or_equal_prefix() {
+    expect("(1,2,3) <= (1,2)").to_yield(false)
+}
+#[test]
+fn tuple_compare_bad_element() {
+    expect("(1,2) < (1,true)").to_error(BadOperandType, 11..=14)
+}
+#[test]
+fn tuple_compare_unordered_left_element() {
+    expect("(1,true) <=> (1,false)").to_error(UnsupportedOperator, 3..=6)
+}
+#[test]
+fn tuple_compare_bad_nested_element() {
+    expect("((1,2),3) < ((1,true),3)").to_error(BadOperandType, 13..=20)
+}
+#[test]
+fn tuple_compare_bad_element_in_variable() {
+    expect(":t = (1,true); (1,2) < t").to_error(BadOperandType, 23)
+}
+#[test]
+fn tuple_compare_to_number() {
+    expect("(1,2) < 1").to_error(BadOperandType, 8)
+}
+
+#[test]
+fn object_compare() {
+    expect(
+        "
+        a = { :size = 1; :<=> = { size <=> :other.size } }
+        b = { :size = 2 }
+        a <=> b
+        ",
+    )
+    .to_yield(-1)
+}
+#[test]
+fn object_less_than() {
+    expect(
+        "
+        a = { :size = 1; :<=> = { size <=> :other.size } }
+        b = { :size = 2 }
+        a < b
+        ",
+    )
+    .to_yield(true)
+}
+#[test]
+fn object_greater_or_equal() {
+    expect(
+        "
+        a = { :size = 2; :<=> = { size <=> :other.size } }
+        b = { :size = 2 }
+        a >= b
+        ",
+    )
+    .to_yield(true)
+}
+#[test]
+fn object_compare_to_number() {
+    expect(
+        "
+        a = { :size = 3; :<=> = { size <=> :other } }
+        a > 2
+        ",
+    )
+    .to_yield(true)
+}
+#[test]
+fn object_compare_reversed() {
+    expect(
+        "
+        a = { :size = 3; :<=> = { :other <=> size } }
+        a > 2
+        ",
+    )
+    .to_yield(false)
+}
+#[test]
+fn object_compare_field() {
+    expect(
+        r#"
+        a = { :size = 1; :<=> = { size <=> :other } }
+        a["<=>"] 2
+        "#,
+    )
+    .to_yield(-1)
+}
@@ -0,0 +1,206 @@
+use crate::*;
+
+#[test]
+fn inclusive_range() {
+    expect("1..4").to_yield(tuple!(1, 2, 3, 4))
+}
+#[test]
+fn exclusive_range() {
+    expect("1...4").to_yield(tuple!(1, 2, 3))
+}
+#[test]
+fn empty_range() {
+    expect("1...1").to_yield(tuple!())
+}
+#[test]
+fn single_range() {
+    expect("1..1").to_yield(tuple!(1))
+}
+#[test]
+fn backwards_range() {
+    expect("4..1").to_yield(tuple!())
+}
+#[test]
+fn negative_range() {
+    expect("-2..1").to_yield(tuple!((-2), (-1), 0, 1))
+}
+#[test]
+fn fraction_range() {
+    expect("0.5..3 == (0.5, 1.5, 2.5)").to_yield(true)
+}
+#[test]
+fn range_with_spaces() {
+    expect("1 .. 4").to_yield(tuple!(1, 2, 3, 4))
+}
+#[test]
+fn range_with_variables() {
+    expect("a = 2; b = 5; a..b").to_yield(tuple!(2, 3, 4, 5))
+}
+#[test]
+fn character_range() {
+    expect(r#""a".."e""#).to_yield(tuple!("a", "b", "c", "d", "e"))
+}
+#[test]
+fn exclusive_character_range() {
+    expect(r#""0"..."3""#).to_yield(tuple!("0", "1", "2"))
+}
+
+#[test]
+fn range_binds_looser_than_plus() {
+    expect("1+1..2+2").to_yield(tuple!(2, 3, 4))
+}
+#[test]
+fn range_binds_tighter_than_comparison() {
+    expect("1..3 == (1,2,3)").to_yield(true)
+}
+#[test]
+fn range_binds_tighter_than_comma() {
+    expect("1..3, 5").to_yield(tuple!([1, 2, 3], 5))
+}
+#[test]
+fn range_not_equal() {
+    expect("1..3 != (1,2)").to_yield(true)
+}
+
+#[test]
+fn foreach_range() {
+    expect(
+        "
+        :sum = 0
+        foreach 1..100 {
+            sum += :x
+        }
+        sum
+    ",
+    )
+    .to_yield(5050)
+}
+#[test]
+fn foreach_big_range() {
+    expect(
+        "
+        :count = 0
+        foreach 1..100000 {
+            count++
+        }
+        count
+    ",
+    )
+    .to_yield(100000)
+}
+
+#[test]
+fn in_range() {
+    expect("2 in 1..3").to_yield(true)
+}
+#[test]
+fn in_range_includes_end() {
+    expect("3 in 1..3").to_yield(true)
+}
+#[test]
+fn in_exclusive_range_excludes_end() {
+    expect("3 in 1...3").to_yield(false)
+}
+#[test]
+fn in_range_last_digit() {
+    expect("9 in 0..9").to_yield(true)
+}
+#[test]
+fn in_range_before_start() {
+    expect("0 in 1..3").to_yield(false)
+}
+#[test]
+fn in_range_fraction() {
+    expect("1.5 in 1..3").to_yield(true)
+}
+#[test]
+fn in_huge_range() {
+    expect("999999999999 in 0..1000000000000").to_yield(true)
+}
+#[test]
+fn in_character_range() {
+    expect(r#""5" in "0".."9""#).to_yield(true)
+}
+#[test]
+fn in_character_range_includes_end() {
+    expect(r#""9" in "0".."9""#).to_yield(true)
+}
+#[test]
+fn in_exclusive_character_range_excludes_end() {
+    expect(r#""9" in "0"..."9""#).to_yield(false)
+}
+#[test]
+fn digit_test_from_sum_of_two_sample() {
+    // samples/codeabbey/sum-of-two.berg
+    expect(r#"input = { :Peek = "9" }; input.Peek in "0".."9""#).to_yield(true)
+}
+#[test]
+fn not_in_character_range() {
+    expect(r#""x" in "0".."9""#).to_yield(false)
+}
+#[test]
+fn string_in_character_range() {
+    expect(r#""55" in "0".."9""#).to_yield(false)
+}
+#[test]
+fn number_in_character_range() {
+    expect(r#"5 in "0".."9""#).to_yield(false)
+}
+#[test]
+fn in_tuple() {
+    expect("2 in (1,2,3)").to_yield(true)
+}
+#[test]
+fn not_in_tuple() {
+    expect("4 in (1,2,3)").to_yield(false)
+}
+#[test]
+fn in_with_condition() {
+    expect("x = 5; x in 1..10 && x != 3").to_yield(true)
+}
+#[test]
+fn in_as_identifier() {
+    expect("in = 5; in + 1").to_yield(6)
+}
+#[test]
+fn in_as_field() {
+    expect("a = { :in = 5 }; a.in").to_yield(5)
+}
+
+#[test]
+fn range_slice() {
+    expect("a = 1,2,3,4; a[1..3]").to_yield(tuple!(2, 3, 4))
+}
+#[test]
+fn exclusive_range_slice() {
+    expect("a = 1,2,3,4; a[1...3]").to_yield(tuple!(2, 3))
+}
+#[test]
+fn range_slice_to_last() {
+    expect("a = 1,2,3,4; a[1..(-1)]").to_yield(tuple!(2, 3, 4))
+}
+#[test]
+fn string_range_slice() {
+    expect(r#""hello"[1..3]"#).to_yield("ell")
+}
+#[test]
+fn range_slice_out_of_range() {
+    expect("a = 1,2,3; a[1..5]").to_error(IndexOutOfRange, "[1..5]")
+}
+
+#[test]
+fn range_bad_end() {
+    expect("1..true").to_error(BadOperandType, "true")
+}
+#[test]
+fn character_range_bad_start() {
+    expect(r#""ab".."z""#).to_error(BadOperandType, r#""ab""#)
+}
+#[test]
+fn character_range_bad_end() {
+    expect(r#""a".."yz""#).to_error(BadOperandType, r#""yz""#)
+}
+#[test]
+fn range_unsupported() {
+    expect("true..false").to_error(UnsupportedOperator, "..")
+}
@@ -0,0 +1,321 @@
+mod literals {
+    use crate::*;
+
+    #[test]
+    fn string() {
+        expect(r#""xy""#).to_yield("xy")
+    }
+    #[test]
+    fn single_character() {
+        expect(r#""x""#).to_yield("x")
+    }
+    #[test]
+    fn empty() {
+        expect(r#""""#).to_yield("")
+    }
+    #[test]
+    fn unicode() {
+        expect(r#""⌂""#).to_yield("⌂")
+    }
+    #[test]
+    fn unicode_ending_in_0xbf() {
+        // ¿ is C2 BF: 0xBF is the last UTF-8 continuation byte.
+        expect(r#""¿Qué?""#).to_yield("¿Qué?")
+    }
+    #[test]
+    fn multiline() {
+        expect("\"a\nb\nc\"").to_yield("a\nb\nc")
+    }
+    #[test]
+    fn multiline_blank_lines() {
+        expect("\"\n\n\"").to_yield("\n\n")
+    }
+    #[test]
+    fn multiline_indented() {
+        expect("\" \n \n \"").to_yield(" \n \n ")
+    }
+    #[test]
+    fn multiline_crlf() {
+        expect("\"a\r\nb\"").to_yield("a\r\nb")
+    }
+    #[test]
+    fn escape_quote() {
+        expect(r#""\"""#).to_yield("\"")
+    }
+    #[test]
+    fn escape_backslash() {
+        expect(r#""\\""#).to_yield("\\")
+    }
+    #[test]
+    fn escape_newline() {
+        expect(r#""\n""#).to_yield("\n")
+    }
+    #[test]
+    fn escape_tab() {
+        expect(r#""a\tb""#).to_yield("a\tb")
+    }
+    #[test]
+    fn escape_unrecognized() {
+        expect(r#""\q""#).to_yield("\\q")
+    }
+    #[test]
+    fn string_then_field() {
+        expect("a: \"x\"\na").to_yield("x")
+    }
+    #[test]
+    fn error_after_multiline_string() {
+        expect("\"a\nb\"\n1/0").to_error(DivideByZero, 2.line(3))
+    }
+}
+
+mod errors {
+    use crate::*;
+
+    #[test]
+    fn unclosed_string() {
+        expect(r#"""#).to_error(UnclosedString, 0)
+    }
+    #[test]
+    fn unclosed_escape() {
+        expect(r#""\"#).to_error(UnclosedString, 0..=1)
+    }
+    #[test]
+    fn unclosed_escaped_quote() {
+        expect(r#""\""#).to_error(UnclosedString, 0..=2)
+    }
+    #[test]
+    fn unclosed_newline() {
+        expect("\"\n").to_error(UnclosedString, 0..=1)
+    }
+    #[test]
+    fn unclosed_surrounded_by_space() {
+        expect(r#" " "#).to_error(UnclosedString, 1..=2)
+    }
+    #[test]
+    fn invalid_utf8_in_string() {
+        expect(&[b'"', 0b1000_0000, b'"']).to_error(InvalidUtf8InString, 0..=2)
+    }
+    #[test]
+    fn invalid_utf8_in_unclosed_string() {
+        expect(&[b'"', 0b1000_0000]).to_error(InvalidUtf8InString, 0..=1)
+    }
+    #[test]
+    fn backslash_outside_string() {
+        expect(r"\\").to_error(UnsupportedCharacters, 0..=1)
+    }
+}
+
+mod operators {
+    use crate::*;
+
+    #[test]
+    fn concatenate() {
+        expect(r#""a" + "b""#).to_yield("ab")
+    }
+    #[test]
+    fn concatenate_empty() {
+        expect(r#""a" + """#).to_yield("a")
+    }
+    #[test]
+    fn concatenate_number() {
+        expect(r#""a" + 1"#).to_error(BadOperandType, 6)
+    }
+    #[test]
+    fn number_plus_string() {
+        expect(r#"1 + "a""#).to_error(BadOperandType, 4..=6)
+    }
+    #[test]
+    fn equal() {
+        expect(r#""a" == "a""#).to_yield(true)
+    }
+    #[test]
+    fn equal_different() {
+        expect(r#""a" == "b""#).to_yield(false)
+    }
+    #[test]
+    fn equal_number() {
+        expect(r#""1" == 1"#).to_yield(false)
+    }
+    #[test]
+    fn not_equal() {
+        expect(r#""a" != "b""#).to_yield(true)
+    }
+    #[test]
+    fn less_than() {
+        expect(r#""a" < "b""#).to_yield(true)
+    }
+    #[test]
+    fn less_than_prefix() {
+        expect(r#""ab" < "a""#).to_yield(false)
+    }
+    #[test]
+    fn greater_than() {
+        expect(r#""b" > "a""#).to_yield(true)
+    }
+    #[test]
+    fn less_equal() {
+        expect(r#""a" <= "a""#).to_yield(true)
+    }
+    #[test]
+    fn greater_equal() {
+        expect(r#""a" >= "b""#).to_yield(false)
+    }
+    #[test]
+    fn less_than_number() {
+        expect(r#""a" < 1"#).to_error(BadOperandType, 6)
+    }
+    #[test]
+    fn negative() {
+        expect(r#"-"a""#).to_error(UnsupportedOperator, 0)
+    }
+    #[test]
+    fn plus_assign() {
+        expect("a = \"x\"; a += \"y\"; a").to_yield("xy")
+    }
+    #[test]
+    fn no_such_field() {
+        expect(r#""a".b"#).to_error(NoSuchPublicField, 4)
+    }
+}
+
+mod interpolation {
+    use crate::*;
+
+    #[test]
+    fn interpolate_string() {
+        expect(r##"name = "World"; "Hello #{name}!""##).to_yield("Hello World!")
+    }
+    #[test]
+    fn interpolate_number() {
+        expect(r##""#{1 + 2}""##).to_yield("3")
+    }
+    #[test]
+    fn interpolate_fraction() {
+        expect(r##""#{1/2}""##).to_yield("1/2")
+    }
+    #[test]
+    fn interpolate_boolean() {
+        expect(r##""is #{1 == 1}""##).to_yield("is true")
+    }
+    #[test]
+    fn interpolate_start() {
+        expect(r##""#{1}a""##).to_yield("1a")
+    }
+    #[test]
+    fn interpolate_end() {
+        expect(r##""a#{1}""##).to_yield("a1")
+    }
+    #[test]
+    fn interpolate_adjacent() {
+        expect(r##""#{1}#{2}""##).to_yield("12")
+    }
+    #[test]
+    fn interpolate_multiple() {
+        expect(r##"a = 1; b = 2; "a=#{a}, b=#{b}.""##).to_yield("a=1, b=2.")
+    }
+    #[test]
+    fn interpolate_field() {
+        expect(r##"friend = { :Name = "Bob" }; "Hello #{friend.Name}""##).to_yield("Hello Bob")
+    }
+    #[test]
+    fn interpolate_in_block() {
+        expect(r##"x = 1; { y = 2; "#{x + y}" }"##).to_yield("3")
+    }
+    #[test]
+    fn interpolate_declares_in_enclosing_scope() {
+        expect(r##""#{a = 5}"; a"##).to_yield(5)
+    }
+    #[test]
+    fn interpolate_nested_string() {
+        expect(r##""a#{"b" + "c"}d""##).to_yield("abcd")
+    }
+    #[test]
+    fn interpolate_nested_interpolation() {
+        expect(r##"x = 1; "a#{"b#{x}c"}d""##).to_yield("ab1cd")
+    }
+    #[test]
+    fn interpolate_curly_braces() {
+        expect(r##""a#{ { 1 + 1 } }b""##).to_yield("a2b")
+    }
+    #[test]
+    fn interpolate_with_spaces() {
+        expect(r##""a#{ 1 + 2 }b""##).to_yield("a3b")
+    }
+    #[test]
+    fn interpolate_escapes() {
+        expect(r##""\"#{1}\n""##).to_yield("\"1\n")
+    }
+    #[test]
+    fn escaped_interpolation() {
+        expect(r##""\#{1}""##).to_yield("#{1}")
+    }
+    #[test]
+    fn hash_without_curly() {
+        expect(r##""a#b""##).to_yield("a#b")
+    }
+    #[test]
+    fn curly_without_hash() {
+        expect(r##""a{1}b""##).to_yield("a{1}b")
+    }
+    #[test]
+    fn interpolated_string_plus() {
+        expect(r##""a#{1}" + "b""##).to_yield("a1b")
+    }
+    #[test]
+    fn interpolated_string_equal() {
+        expect(r##""a#{1}" == "a1""##).to_yield(true)
+    }
+    #[test]
+    fn interpolate_multiline() {
+        expect("\"a\n#{1}\nb\"").to_yield("a\n1\nb")
+    }
+    #[test]
+    fn interpolated_string_field() {
+        expect("a: \"x#{1}\"\na").to_yield("x1")
+    }
+}
+
+mod interpolation_errors {
+    use crate::*;
+
+    #[test]
+    fn unclosed_interpolation() {
+        expect(r##""a#{1"##).to_error(UnclosedInterpolation, 2..=3)
+    }
+    #[test]
+    fn unclosed_interpolation_at_end() {
+        expect(r##""#{"##).to_error(UnclosedInterpolation, 1..=2)
+    }
+    #[test]
+    fn unclosed_inner_interpolation() {
+        expect(r##""a#{1}b#{2"##).to_error(UnclosedInterpolation, 7..=8)
+    }
+    #[test]
+    fn unclosed_interpolated_string() {
+        expect(r##""a#{1}b"##).to_error(UnclosedString, 0)
+    }
+    #[test]
+    fn unclosed_interpolated_string_at_interpolation() {
+        expect(r##""a#{1}"##).to_error(UnclosedString, 0)
+    }
+    #[test]
+    fn unclosed_interpolated_string_at_start() {
+        expect(r##""#{1}"##).to_error(UnclosedString, 0)
+    }
+    #[test]
+    fn error_in_interpolation() {
+        expect(r##""a#{1/0}b""##).to_error(DivideByZero, 6)
+    }
+    #[test]
+    fn no_such_field_in_interpolation() {
+        expect(r##""a#{b}c""##).to_error(NoSuchField, 4)
+    }
+    #[test]
+    fn close_paren_in_interpolation() {
+        expect(r##""a#{1)}b""##).to_error(CloseWithoutOpen, 5)
+    }
+    #[test]
+    fn invalid_utf8_in_interpolated_string() {
+        expect(&[b'"', 0b1000_0000, b'#', b'{', b'1', b'}', b'"']).to_error(InvalidUtf8InString, 1)
+    }
+}
@@ -235,57 +235,57 @@ mod operators_comparison {
 
     #[test]
     fn greater_or_equal_empty_0() {
-        expect("()>=0").to_error(UnsupportedOperator, 2..=3)
+        expect("()>=0").to_error(BadOperandType, 4)
     }
 
     #[test]
     fn greater_or_equal_empty_error() {
-        expect("()>=1/0").to_error(UnsupportedOperator, 2..=3)
+        expect("()>=1/0").to_error(DivideByZero, 6)
     }
 
     #[test]
     fn greater_or_equal_empty_false() {
-        expect("()>=false").to_error(UnsupportedOperator, 2..=3)
+        expect("()>=false").to_error(BadOperandType, 4..=8)
     }
 
     #[test]
     fn greater_or_equal_empty_empty() {
-        expect("()>=()").to_error(UnsupportedOperator, 2..=3)
+        expect("()>=()").to_yield(true)
     }
 
     #[test]
     fn greater_or_equal_empty_true() {
-        expect("()>=true").to_error(UnsupportedOperator, 2..=3)
+        expect("()>=true").to_error(BadOperandType, 4..=7)
     }
 
     #[test]
     fn greater_than_empty_0() {
-        expect("()>0").to_error(UnsupportedOperator, 2)
+        expect("()>0").to_error(BadOperandType, 3)
     }
 
     #[test]
     fn greater_than_empty_error() {
-        expect("()>1/0").to_error(UnsupportedOperator, 2)
+        expect("()>1/0").to_error(DivideByZero, 5)
     }
 
     #[test]
     fn greater_than_empty_false() {
-        expect("()>false").to_error(UnsupportedOperator, 2)
+        expect("()>false").to_error(BadOperandType, 3..=7)
     }
 
     #[test]
     fn greater_than_empty_empty() {
-        expect("()>()").to_error(UnsupportedOperator, 2)
+        expect("()>()").to_yield(false)
     }
 
     #[test]
     fn greater_than_empty_true() {
-        expect("()>true").to_error(UnsupportedOperator, 2)
+        expect("()>true").to_error(BadOperandType, 3..=6)
     }
 
     #[test]
     fn less_or_equal_empty_0() {
-        expect("()<=0").to_error(UnsupportedOperator, 2..=3)
+        expect("()<=0").to_error(BadOperandType, 4)
     }
 
     #[test]
@@ -295,42 +295,42 @@ mod operators_comparison {
 
     #[test]
     fn less_or_equal_empty_false() {
-        expect("()<=false").to_error(UnsupportedOperator, 2..=3)
+        expect("()<=false").to_error(BadOperandType, 4..=8)
     }
 
     #[test]
     fn less_or_equal_empty_empty() {
-        expect("()<=()").to_error(UnsupportedOperator, 2..=3)
+        expect("()<=()").to_yield(true)
     }
 
     #[test]
     fn less_or_equal_empty_true() {
-        expect("()<=true").to_error(UnsupportedOperator, 2..=3)
+        expect("()<=true").to_error(BadOperandType, 4..=7)
     }
 
     #[test]
     fn less_than_empty_0() {
-        expect("()<0").to_error(UnsupportedOperator, 2)
+        expect("()<0").to_error(BadOperandType, 3)
     }
 
     #[test]
     fn less_than_empty_error() {
-        expect("()<1/0").to_error(UnsupportedOperator, 2)
+        expect("()<1/0").to_error(DivideByZero, 5)
     }
 
     #[test]
     fn less_than_empty_false() {
-        expect("()<false").to_error(UnsupportedOperator, 2)
+        expect("()<false").to_error(BadOperandType, 3..=7)
     }
 
     #[test]
     fn less_than_empty_empty() {
-        expect("()<()").to_error(UnsupportedOperator, 2)
+        expect("()<()").to_yield(false)
     }
 
     #[test]
     fn less_than_empty_true() {
-        expect("()<true").to_error(UnsupportedOperator, 2)
+        expect("()<true").to_error(BadOperandType, 3..=6)
     }
 }
 
@@ -0,0 +1,662 @@
+use berg_compiler::test::*;
+use berg_compiler::{tuple, BergResult, BergVal};
+use berg_parser::identifiers::NEWLINE_SEQUENCE;
+use berg_parser::{
+    Ast, AstExpressionTree, ByteRange, ExpressionBoundary, ExpressionToken, Fixity,
+    OperatorToken, TermToken, Token,
+};
+use berg_util::to_indexed_cow;
+use std::borrow::Cow;
+use std::collections::HashSet;
+use std::fs;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::{Path, PathBuf};
+use yaml_rust2::parser::{Event, EventReceiver, Parser};
+use yaml_rust2::scanner::TScalarStyle;
+
+///
+/// Declares a test for each YAML file under `spec_tests/`.
+///
+/// `all_spec_files_are_listed` makes sure new files get added here.
+///
+macro_rules! spec_files {
+    ( $( $name:ident => $file:expr ),* $(,)? ) => {
+        $(
+            #[test]
+            fn $name() {
+                run_spec_file($file)
+            }
+        )*
+        const SPEC_FILES: &[&str] = &[ $( $file ),* ];
+    };
+}
+
+spec_files! {
+    syntax_bareword => "Syntax/Bareword.yaml",
+    syntax_float_literal => "Syntax/FloatLiteral.yaml",
+    syntax_hexadecimal_literal => "Syntax/HexadecimalLiteral.yaml",
+    syntax_imaginary_literal => "Syntax/ImaginaryLiteral.yaml",
+    syntax_indent => "Syntax/Indent.yaml",
+    syntax_integer_literal => "Syntax/IntegerLiteral.yaml",
+    syntax_octal_literal => "Syntax/OctalLiteral.yaml",
+    syntax_operator_missing_expressions => "Syntax/OperatorMissingExpressions.yaml",
+    syntax_operator_precedence => "Syntax/OperatorPrecedence.yaml",
+    syntax_operator_stickiness => "Syntax/OperatorStickiness.yaml",
+    syntax_operators => "Syntax/Operators.yaml",
+    syntax_string_literal => "Syntax/StringLiteral.yaml",
+    syntax_whitespace => "Syntax/Whitespace.yaml",
+}
+
+///
+/// Cases that are known to fail (generally because the syntax isn't
+/// implemented yet). One case name per line; `#` starts a comment.
+///
+const EXPECTED_FAILURES: &str = "expected_failures.txt";
+
+#[test]
+fn all_spec_files_are_listed() {
+    let mut files = vec![];
+    find_spec_files(&spec_dir(), &mut files);
+    let mut files: Vec<String> = files
+        .iter()
+        .map(|file| {
+            let file = file.strip_prefix(spec_dir()).unwrap();
+            file.to_string_lossy().replace('\\', "/")
+        })
+        .collect();
+    files.sort();
+    let mut listed: Vec<&str> = SPEC_FILES.to_vec();
+    listed.sort();
+    assert_eq!(
+        files, listed,
+        "Spec files on disk don't match the spec_files! list in {}",
+        file!()
+    );
+}
+
+///
+/// Runs every case in a spec file, and fails if any case that isn't in
+/// `expected_failures.txt` fails (or if an expected failure passes).
+///
+/// Prints a line for each case and a count for the file; run with
+/// `--nocapture` to see them when everything passes.
+///
+/// Each case is a mapping with a `Berg:` key (the source) and one or more
+/// expectations:
+///
+/// * `Ast:` / `Ast -> Kind:`: the expression tree. Strings are compared
+///   against the source of the expression, and may have a location prefix
+///   (`1@2+0 = ` or `1@1-2@1 = `). Mappings check `$Term` (the whole
+///   expression), `Operator`, `Open`, `Close`, and the `Left`, `Right` and
+///   `Expression` operands.
+/// * `Result:` / `Result -> Void:`: the value the source evaluates to.
+///   Strings are compared directly; anything else is evaluated as Berg.
+/// * `Error:`: the error message, optionally preceded by the source the
+///   error points at (`0a = Number is mixed up ...`), or a mapping with
+///   `$Term` and `$Error`.
+///
+fn run_spec_file(file: &str) {
+    let path = spec_dir().join(file);
+    let yaml = fs::read_to_string(&path)
+        .unwrap_or_else(|error| panic!("Could not read {}: {}", path.display(), error));
+    let documents = load_yaml(&yaml);
+    let mut cases = vec![];
+    for document in &documents {
+        collect_cases(file.trim_end_matches(".yaml").to_string(), document, &mut cases);
+    }
+    assert!(!cases.is_empty(), "No cases found in {}", file);
+
+    let expected_failures = expected_failures();
+    let mut passed = 0;
+    let mut failed = 0;
+    let mut unexpected = vec![];
+    for case in &cases {
+        let errors = run_case_catching_panics(case);
+        let expected_to_fail = expected_failures.contains(case.name.as_str());
+        let result = match (errors.is_empty(), expected_to_fail) {
+            (true, false) => {
+                passed += 1;
+                "ok"
+            }
+            (false, true) => {
+                failed += 1;
+                "expected failure"
+            }
+            (true, true) => {
+                unexpected.push(format!(
+                    "{} passed! Remove it from {}.",
+                    case.name, EXPECTED_FAILURES
+                ));
+                "unexpectedly passed"
+            }
+            (false, false) => {
+                unexpected.push(format!(
+                    "{} failed (source: {:?}):\n    {}",
+                    case.name,
+                    case.source(),
+                    errors.join("\n    ")
+                ));
+                "FAILED"
+            }
+        };
+        println!("{} ... {}", case.name, result);
+    }
+    println!(
+        "{}: {} passed, {} expected failures, {} unexpected",
+        file,
+        passed,
+        failed,
+        unexpected.len()
+    );
+    assert!(
+        unexpected.is_empty(),
+        "{} unexpected results in {} ({} passed, {} expected failures):\n{}",
+        unexpected.len(),
+        file,
+        passed,
+        failed,
+        unexpected.join("\n")
+    );
+}
+
+fn spec_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("../spec_tests")
+}
+
+fn find_spec_files(dir: &Path, files: &mut Vec<PathBuf>) {
+    for entry in fs::read_dir(dir).unwrap() {
+        let path = entry.unwrap().path();
+        if path.is_dir() {
+            find_spec_files(&path, files);
+        } else if path.extension().is_some_and(|extension| extension == "yaml") {
+            files.push(path);
+        }
+    }
+}
+
+fn expected_failures() -> HashSet<String> {
+    let path = spec_dir().join(EXPECTED_FAILURES);
+    let contents = fs::read_to_string(&path)
+        .unwrap_or_else(|error| panic!("Could not read {}: {}", path.display(), error));
+    contents
+        .lines()
+        .map(|line| line.split('#').next().unwrap().trim())
+        .filter(|line| !line.is_empty())
+        .map(String::from)
+        .collect()
+}
+
+///
+/// A YAML value, with scalars kept exactly as written (so `0x1A` and
+/// `12.34e56` don't get turned into numbers).
+///
+#[derive(Debug)]
+enum Spec {
+    Null,
+    Scalar(String),
+    List(Vec<Spec>),
+    Map(Vec<(String, Spec)>),
+}
+
+impl Spec {
+    fn text(&self) -> Option<&str> {
+        match self {
+            Spec::Null => Some(""),
+            Spec::Scalar(text) => Some(text),
+            Spec::List(_) | Spec::Map(_) => None,
+        }
+    }
+}
+
+#[derive(Default)]
+struct SpecLoader {
+    documents: Vec<Spec>,
+    open: Vec<(Spec, Option<String>)>,
+}
+
+fn load_yaml(yaml: &str) -> Vec<Spec> {
+    let mut loader = SpecLoader::default();
+    Parser::new_from_str(yaml)
+        .load(&mut loader, true)
+        .unwrap_or_else(|error| panic!("Invalid YAML: {}", error));
+    loader.documents
+}
+
+impl EventReceiver for SpecLoader {
+    fn on_event(&mut self, event: Event) {
+        match event {
+            Event::Scalar(value, TScalarStyle::Plain, ..)
+                if matches!(value.as_str(), "" | "~" | "null") =>
+            {
+                self.push(Spec::Null)
+            }
+            Event::Scalar(value, ..) => self.push(Spec::Scalar(value)),
+            Event::SequenceStart(..) => self.open.push((Spec::List(vec![]), None)),
+            Event::MappingStart(..) => self.open.push((Spec::Map(vec![]), None)),
+            Event::SequenceEnd | Event::MappingEnd => {
+                let (value, _) = self.open.pop().unwrap();
+                self.push(value)
+            }
+            Event::Alias(_) => panic!("YAML aliases are not supported in spec tests"),
+            Event::Nothing
+            | Event::StreamStart
+            | Event::StreamEnd
+            | Event::DocumentStart
+            | Event::DocumentEnd => {}
+        }
+    }
+}
+
+impl SpecLoader {
+    fn push(&mut self, value: Spec) {
+        match self.open.last_mut() {
+            None => self.documents.push(value),
+            Some((Spec::List(values), _)) => values.push(value),
+            Some((Spec::Map(_), key @ None)) => match value.text() {
+                Some(text) => *key = Some(text.to_string()),
+                None => panic!("Spec test keys must be strings, not {:?}", value),
+            },
+            Some((Spec::Map(fields), key @ Some(_))) => fields.push((key.take().unwrap(), value)),
+            Some((Spec::Null, _)) | Some((Spec::Scalar(_), _)) => unreachable!(),
+        }
+    }
+}
+
+struct SpecCase<'a> {
+    name: String,
+    fields: &'a [(String, Spec)],
+}
+
+impl<'a> SpecCase<'a> {
+    fn source(&self) -> &'a str {
+        self.fields
+            .iter()
+            .find(|(key, _)| key == "Berg")
+            .and_then(|(_, value)| value.text())
+            .unwrap_or_else(|| panic!("{}: Berg: must be a string", self.name))
+    }
+}
+
+///
+/// Finds all mappings with a `Berg:` key. Each case is named after the path
+/// of keys and list indexes leading to it, like `Syntax/FloatLiteral/Float/0`.
+///
+fn collect_cases<'a>(name: String, spec: &'a Spec, cases: &mut Vec<SpecCase<'a>>) {
+    match spec {
+        Spec::Map(fields) if fields.iter().any(|(key, _)| key == "Berg") => {
+            cases.push(SpecCase { name, fields })
+        }
+        Spec::Map(fields) => {
+            for (key, value) in fields {
+                collect_cases(format!("{}/{}", name, key), value, cases);
+            }
+        }
+        Spec::List(values) => {
+            for (index, value) in values.iter().enumerate() {
+                collect_cases(format!("{}/{}", name, index), value, cases);
+            }
+        }
+        Spec::Scalar(_) | Spec::Null => {}
+    }
+}
+
+fn run_case_catching_panics(case: &SpecCase) -> Vec<String> {
+    match panic::catch_unwind(AssertUnwindSafe(|| run_case(case))) {
+        Ok(errors) => errors,
+        Err(error) => {
+            let message = error
+                .downcast_ref::<String>()
+                .map(String::as_str)
+                .or_else(|| error.downcast_ref::<&str>().copied())
+                .unwrap_or("unknown panic");
+            vec![format!("panicked: {}", message)]
+        }
+    }
+}
+
+fn run_case(case: &SpecCase) -> Vec<String> {
+    let source = case.source();
+    let mut errors = vec![];
+    let mut result = None;
+    for (key, value) in case.fields {
+        match split_key(key) {
+            ("Berg", None) => {}
+            ("Ast", kind) => {
+                let ast = berg_parser::parse(to_indexed_cow(Cow::Owned(source.as_bytes().to_vec())));
+                let root = AstExpressionTree::new(&ast, ast.root_expression());
+                let mut check = AstCheck {
+                    ast: &ast,
+                    source: source.as_bytes(),
+                    errors: &mut errors,
+                };
+                check.expression(root, kind, value, "Ast");
+            }
+            ("Result", kind) => {
+                let result = result.get_or_insert_with(|| evaluate(source));
+                check_result(result, kind, value, &mut errors);
+            }
+            ("Error", None) => {
+                let result = result.get_or_insert_with(|| evaluate(source));
+                check_error(source.as_bytes(), result, value, &mut errors);
+            }
+            _ => errors.push(format!("Unknown key {:?}", key)),
+        }
+    }
+    errors
+}
+
+fn evaluate(source: &str) -> BergResult {
+    evaluate_source(source.as_bytes().to_vec())
+}
+
+///
+/// Splits `Ast -> InfixOperation` into `("Ast", Some("InfixOperation"))`.
+///
+fn split_key(key: &str) -> (&str, Option<&str>) {
+    match key.split_once(" -> ") {
+        Some((name, kind)) => (name, Some(kind)),
+        None => (key, None),
+    }
+}
+
+///
+/// Splits `1@2+0 = text` into `(Some("1@2+0"), "text")`.
+///
+fn split_location(expected: &str) -> (Option<&str>, &str) {
+    match expected.split_once(" = ") {
+        Some((location, text)) if is_location(location) => (Some(location), text),
+        _ => (None, expected),
+    }
+}
+
+fn is_location(location: &str) -> bool {
+    match location.split_once(['+', '-']) {
+        Some((start, length)) if location.as_bytes()[start.len()] == b'+' => {
+            is_line_column(start) && is_number(length)
+        }
+        Some((start, end)) => is_line_column(start) && is_line_column(end),
+        None => is_line_column(location),
+    }
+}
+
+fn is_line_column(location: &str) -> bool {
+    match location.split_once('@') {
+        Some((line, column)) => is_number(line) && is_number(column),
+        None => false,
+    }
+}
+
+fn is_number(string: &str) -> bool {
+    !string.is_empty() && string.bytes().all(|byte| byte.is_ascii_digit())
+}
+
+struct AstCheck<'a> {
+    ast: &'a Ast,
+    source: &'a [u8],
+    errors: &'a mut Vec<String>,
+}
+
+impl<'a> AstCheck<'a> {
+    fn expression(
+        &mut self,
+        expression: AstExpressionTree,
+        kind: Option<&str>,
+        expected: &Spec,
+        path: &str,
+    ) {
+        let expression = skip_implicit_groups(expression);
+        let actual_kind = expression_kind(expression);
+        if let Some(kind) = kind
+            && kind != actual_kind
+        {
+            self.errors.push(format!(
+                "{}: expected {}, got {} ({})",
+                path,
+                kind,
+                actual_kind,
+                expression.format()
+            ));
+            return;
+        }
+
+        let fields = match expected {
+            Spec::Map(fields) => fields,
+            Spec::List(_) => {
+                self.errors
+                    .push(format!("{}: lists are not supported in Ast expectations", path));
+                return;
+            }
+            Spec::Null | Spec::Scalar(_) => {
+                let text = expected.text().unwrap();
+                return self.text(expression.byte_range(), text, path);
+            }
+        };
+
+        let fixity = expression.token().fixity();
+        for (key, value) in fields {
+            let path = format!("{} > {}", path, key);
+            let (name, kind) = split_key(key);
+            match (name, fixity) {
+                ("$Term", _) => self.spec_text(expression.byte_range(), value, &path),
+                // The newline operator is zero-width at the start of the next line.
+                ("Operator", Fixity::Infix)
+                    if matches!(
+                        expression.token(),
+                        Token::Operator(OperatorToken::InfixOperator(NEWLINE_SEQUENCE))
+                    ) =>
+                {
+                    if value.text() != Some("\n") {
+                        self.errors.push(format!(
+                            "{}: expected {:?}, got \"\\n\"",
+                            path,
+                            value.text()
+                        ))
+                    }
+                }
+                ("Operator", Fixity::Prefix | Fixity::Postfix | Fixity::Infix) => {
+                    let range = self.ast.token_range(expression.root_index());
+                    self.spec_text(range, value, &path)
+                }
+                ("Open", Fixity::Open | Fixity::Close) => {
+                    let range = self.ast.token_range(expression.open_operator());
+                    self.spec_text(range, value, &path)
+                }
+                ("Close", Fixity::Open | Fixity::Close) => {
+                    let range = self.ast.token_range(expression.close_operator());
+                    self.spec_text(range, value, &path)
+                }
+                ("Left", Fixity::Infix | Fixity::Postfix) => {
+                    self.expression(expression.left_expression(), kind, value, &path)
+                }
+                ("Right", Fixity::Infix | Fixity::Prefix) => {
+                    self.expression(expression.right_expression(), kind, value, &path)
+                }
+                ("Expression", Fixity::Open | Fixity::Close) => {
+                    self.expression(expression.inner_expression(), kind, value, &path)
+                }
+                // An empty block (like `a:` with nothing under it) is just the
+                // missing expression, without a group around it.
+                ("Expression", _) if actual_kind == "EmptyExpression" => {
+                    self.expression(expression, kind, value, &path)
+                }
+                ("Operator" | "Open" | "Close" | "Left" | "Right" | "Expression", _) => {
+                    self.errors.push(format!(
+                        "{}: {} has no {} ({})",
+                        path,
+                        actual_kind,
+                        name,
+                        expression.format()
+                    ))
+                }
+                _ => self.errors.push(format!("{}: unknown key", path)),
+            }
+        }
+    }
+
+    fn spec_text(&mut self, range: ByteRange, expected: &Spec, path: &str) {
+        match expected.text() {
+            Some(text) => self.text(range, text, path),
+            None => self.errors.push(format!("{}: expected a string", path)),
+        }
+    }
+
+    fn text(&mut self, range: ByteRange, expected: &str, path: &str) {
+        let (location, expected) = split_location(expected);
+        let actual = source_text(self.source, range.clone());
+        if actual != expected {
+            self.errors.push(format!(
+                "{}: expected {:?}, got {:?}",
+                path, expected, actual
+            ));
+        }
+        if let Some(location) = location {
+            let actual = self.location(range, location);
+            if actual != location {
+                self.errors.push(format!(
+                    "{}: expected location {}, got {}",
+                    path, location, actual
+                ));
+            }
+        }
+    }
+
+    ///
+    /// Formats the range the same way as `expected` (`line@column`,
+    /// `line@column+length` or `line@column-line@column`).
+    ///
+    fn location(&self, range: ByteRange, expected: &str) -> String {
+        let char_data = &self.ast.char_data;
+        let start = char_data.location(range.start);
+        if expected.contains('+') {
+            let length = usize::from(range.end) - usize::from(range.start);
+            format!("{}@{}+{}", start.line, start.column, length)
+        } else if expected.contains('-') {
+            let end = match range.start < range.end {
+                true => char_data.location(range.end - 1),
+                false => start,
+            };
+            format!("{}@{}-{}@{}", start.line, start.column, end.line, end.column)
+        } else {
+            format!("{}@{}", start.line, start.column)
+        }
+    }
+}
+
+///
+/// Skips groups the user didn't write, like precedence groups and the
+/// source block.
+///
+fn skip_implicit_groups(mut expression: AstExpressionTree) -> AstExpressionTree {
+    use ExpressionBoundary::*;
+    while matches!(expression.token().fixity(), Fixity::Open | Fixity::Close) {
+        match expression.boundary() {
+            Root | Source | PrecedenceGroup | CompoundTerm | AutoBlock => {
+                expression = expression.inner_expression()
+            }
+            CurlyBraces | Parentheses | SquareBrackets | Interpolation | InterpolatedString
+            | IndentedBlock | IndentedExpression => break,
+        }
+    }
+    expression
+}
+
+fn expression_kind(expression: AstExpressionTree) -> &'static str {
+    use ExpressionToken::*;
+    use OperatorToken::*;
+    use TermToken::*;
+    use Token::*;
+    match expression.token() {
+        Expression(Term(token)) => match token {
+            RawIdentifier(_) | FieldReference(_) => "Bareword",
+            IntegerLiteral(_) => "IntegerLiteral",
+            HexadecimalLiteral(_) => "HexadecimalLiteral",
+            OctalLiteral(_) => "OctalLiteral",
+            BinaryLiteral(_) => "BinaryLiteral",
+            FloatLiteral(..) => "FloatLiteral",
+            ImaginaryLiteral(..) => "ImaginaryLiteral",
+            StringLiteral(_) => "StringLiteral",
+            StringFragment(_) => "StringFragment",
+            TextBlock(_) => "TextBlock",
+            MissingExpression => "EmptyExpression",
+            ErrorTerm(..) | RawErrorTerm(..) => "ErrorTerm",
+        },
+        Expression(PrefixOperator(_)) => "PrefixOperation",
+        Operator(PostfixOperator(_)) => "PostfixOperation",
+        Operator(InfixOperator(_)) | Operator(InfixAssignment(_)) => "InfixOperation",
+        Expression(Open(..)) | Operator(Close(..)) | Operator(CloseBlock(..)) => {
+            "DelimitedOperation"
+        }
+    }
+}
+
+fn source_text(source: &[u8], range: ByteRange) -> Cow<'_, str> {
+    String::from_utf8_lossy(&source[usize::from(range.start)..usize::from(range.end)])
+}
+
+fn check_result(result: &BergResult, kind: Option<&str>, expected: &Spec, errors: &mut Vec<String>) {
+    let expected = match expected.text() {
+        Some(expected) => expected,
+        None => return errors.push("Result: expected a string".to_string()),
+    };
+    let actual = match result {
+        Ok(actual) => actual.clone(),
+        Err(error) => {
+            return errors.push(format!("Result: expected {}, got error {}", expected, error))
+        }
+    };
+    let matches = match (kind, &actual) {
+        (Some("Void"), _) => tuple!().matches(actual.clone()),
+        (None, BergVal::String(string)) => Ok(string == expected),
+        (None, _) => match evaluate(expected) {
+            Ok(expected) => expected.matches(actual.clone()),
+            Err(error) => {
+                return errors.push(format!("Result: could not evaluate {:?}: {}", expected, error))
+            }
+        },
+        (Some(kind), _) => return errors.push(format!("Result -> {}: unknown kind", kind)),
+    };
+    match matches {
+        Ok(true) => {}
+        Ok(false) => errors.push(format!("Result: expected {}, got {}", expected, actual)),
+        Err(error) => errors.push(format!(
+            "Result: error comparing {} to {}: {}",
+            expected, actual, error
+        )),
+    }
+}
+
+fn check_error(source: &[u8], result: &BergResult, expected: &Spec, errors: &mut Vec<String>) {
+    let (term, message) = match expected {
+        Spec::Scalar(expected) => match expected.split_once(" = ") {
+            Some((term, message)) => (Some(term), message),
+            None => (None, expected.as_str()),
+        },
+        Spec::Map(fields) => {
+            let field = |name| {
+                fields
+                    .iter()
+                    .find(|(key, _)| key == name)
+                    .and_then(|(_, value)| value.text())
+            };
+            match field("$Error") {
+                Some(message) => (field("$Term"), message),
+                None => return errors.push("Error: missing $Error".to_string()),
+            }
+        }
+        Spec::Null | Spec::List(_) => return errors.push("Error: expected a string".to_string()),
+    };
+    let actual = match result {
+        Ok(value) => return errors.push(format!("Error: expected {:?}, got value {}", message, value)),
+        Err(actual) => actual,
+    };
+    let actual_message = actual.to_string();
+    if actual_message != message {
+        errors.push(format!("Error: expected {:?}, got {:?}", message, actual_message));
+    }
+    if let Some(term) = term {
+        let actual_term = source_text(source, actual.location().byte_range());
+        if actual_term != term {
+            errors.push(format!("Error: expected at {:?}, got {:?}", term, actual_term));
+        }
+    }
+}
@@ -0,0 +1,118 @@
+use crate::*;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+///
+/// Remembers each event as `name expression = value`.
+///
+#[derive(Clone, Default)]
+struct CollectingTracer(Rc<RefCell<Vec<String>>>);
+
+impl Tracer for CollectingTracer {
+    fn trace(&self, event: &TraceEvent) {
+        use TraceEvent::*;
+        let line = match event {
+            BlockStart { block } => format!("{} {}", event.name(), block.source),
+            BlockComplete { block, result } => {
+                format!("{} {} = {}", event.name(), block.source, result)
+            }
+            ExpressionEvaluated { expression, value } => {
+                format!("{} {} = {}", event.name(), expression.source, value)
+            }
+            FieldDeclared {
+                name,
+                value: Some(value),
+                ..
+            } => format!("{} {} = {}", event.name(), name, value),
+            FieldDeclared { name, .. } => format!("{} {}", event.name(), name),
+            FieldSet { name, value, .. } => format!("{} {} = {}", event.name(), name, value),
+            ExceptionThrown { expression, .. } | ExceptionCaught { expression, .. } => {
+                format!("{} {}", event.name(), expression.source)
+            }
+            Warning { message, .. } => format!("{} {}", event.name(), message),
+        };
+        self.0.borrow_mut().push(line);
+    }
+}
+
+fn trace(source: &'static str) -> Vec<String> {
+    let tracer = CollectingTracer::default();
+    let _ = evaluate_bytes_with_tracer("trace.rs", source.as_bytes(), tracer.clone());
+    tracer.0.take()
+}
+
+fn assert_traced(events: &[String], expected: &str) {
+    assert!(
+        events.iter().any(|event| event == expected),
+        "expected {:?} in trace:\n{}",
+        expected,
+        events.join("\n")
+    );
+}
+
+#[test]
+fn trace_expression_evaluated() {
+    let events = trace("1 + 2");
+    assert_traced(&events, "expression_evaluated 1 = 1");
+    assert_traced(&events, "expression_evaluated 1 + 2 = 3");
+}
+
+#[test]
+fn trace_block_start_and_complete() {
+    let events = trace("1 + 2");
+    assert_eq!(events.first().unwrap(), "block_start 1 + 2");
+    assert_eq!(events.last().unwrap(), "block_complete 1 + 2 = 3");
+}
+
+#[test]
+fn trace_field_set() {
+    let events = trace("a = 1 + 2; a");
+    assert_traced(&events, "field_set a = 3");
+}
+
+#[test]
+fn trace_field_declared() {
+    let events = trace("Square: :x*x; Square 10");
+    assert_traced(&events, "field_declared x = 10");
+}
+
+#[test]
+fn trace_exception_thrown() {
+    let events = trace("1 / 0");
+    assert_traced(&events, "exception_thrown 0");
+}
+
+#[test]
+fn trace_exception_caught() {
+    let events = trace("try { 1 / 0 } catch { 2 }");
+    assert_traced(&events, "exception_thrown 0");
+    assert_traced(&events, "exception_caught 0");
+}
+
+#[test]
+fn trace_unreachable_match_arm() {
+    let events = trace("match 1 { _ => 1; 2 => 2 }");
+    assert_traced(
+        &events,
+        "warning match arm '2 => 2' can never match, because '_' before it matches everything.",
+    );
+}
+
+#[test]
+fn trace_duplicate_match_arm() {
+    let events = trace("match 1 { 1 => 1; 1 => 2; _ => 3 }");
+    assert_traced(
+        &events,
+        "warning match arm '1 => 2' can never match, because an earlier arm has the same pattern.",
+    );
+}
+
+#[test]
+fn trace_unreachable_match_arm_once() {
+    let events = trace("foreach 1,2,3 { match :_ { :x => x; _ => 0 } }");
+    let warnings = events
+        .iter()
+        .filter(|event| event.starts_with("warning"))
+        .count();
+    assert_eq!(warnings, 1, "{}", events.join("\n"));
+}
@@ -0,0 +1,89 @@
+use crate::*;
+use std::cell::RefCell;
+use std::io::{self, Write};
+use std::rc::Rc;
+
+///
+/// A Write that can still be read after the tracer owns it.
+///
+#[derive(Clone, Default)]
+struct SharedBuffer(Rc<RefCell<Vec<u8>>>);
+
+impl Write for SharedBuffer {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.borrow_mut().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl SharedBuffer {
+    fn lines(&self) -> Vec<String> {
+        String::from_utf8(self.0.borrow().clone())
+            .unwrap()
+            .lines()
+            .map(String::from)
+            .collect()
+    }
+}
+
+#[test]
+fn text_tracer() {
+    let out = SharedBuffer::default();
+    let _ = evaluate_bytes_with_tracer("trace.rs", &b"1 + 2"[..], TextTracer::new(out.clone()));
+    let lines = out.lines();
+    assert_eq!(lines.first().unwrap(), "1:1-5 block 1 + 2");
+    assert!(lines.contains(&"    1:1 1 = 1".to_string()), "{:?}", lines);
+    assert_eq!(lines.last().unwrap(), "1:1-5 block 1 + 2 = 3");
+}
+
+#[test]
+fn text_tracer_one_line_per_event() {
+    let out = SharedBuffer::default();
+    let _ = evaluate_bytes_with_tracer("trace.rs", &b"\"a\nb\""[..], TextTracer::new(out.clone()));
+    let lines = out.lines();
+    assert_eq!(lines.len(), 3, "{:?}", lines);
+}
+
+#[test]
+fn text_tracer_shows_first_line_of_expression() {
+    let out = SharedBuffer::default();
+    let _ = evaluate_bytes_with_tracer("trace.rs", &b"a = 1\na"[..], TextTracer::new(out.clone()));
+    let lines = out.lines();
+    assert_eq!(lines.first().unwrap(), "1:1-2:1 block a = 1...");
+}
+
+#[test]
+fn text_tracer_shortens_long_expression() {
+    let out = SharedBuffer::default();
+    let source = format!("{}1", "1 + ".repeat(20));
+    let _ = evaluate_bytes_with_tracer(
+        "trace.rs",
+        source.into_bytes(),
+        TextTracer::new(out.clone()),
+    );
+    let lines = out.lines();
+    let expected = format!("1:1-81 block {}...", &"1 + ".repeat(10));
+    assert_eq!(lines.first().unwrap(), &expected);
+}
+
+#[test]
+fn json_tracer() {
+    let out = SharedBuffer::default();
+    let _ = evaluate_bytes_with_tracer("trace.rs", &b"a = \"x\""[..], JsonTracer::new(out.clone()));
+    let lines = out.lines();
+    assert_eq!(
+        lines.first().unwrap(),
+        r#"{"event":"block_start","depth":0,"location":"1:1-7","expression":"a = \"x\""}"#
+    );
+    assert!(
+        lines.contains(
+            &r#"{"event":"field_set","depth":0,"location":"1:1-7","field":"a","value":"x"}"#
+                .to_string()
+        ),
+        "{:?}",
+        lines
+    );
+}
@@ -0,0 +1,2 @@
+mod events;
+mod formats;
@@ -1,16 +1,25 @@
 mod parser;
 pub(crate) mod syntax;
 
-pub use parser::parse;
+pub use parser::{
+    format_source, parse, parse_with_operators, reparse, AstBuilder, Expression, FormatError,
+    ParseCache, MAX_IDENTIFIER_LENGTH, MAX_LINE_LENGTH,
+};
 pub use syntax::{
     ast::{Ast, AstIndex, LiteralIndex, RawLiteralIndex},
+    ast_binary::{content_hash, AstFormatError, AST_FORMAT_VERSION},
     block::{BlockIndex, FieldError, FieldIndex},
     bytes::{ByteIndex, ByteRange, ByteSlice},
-    char_data::LineColumnRange,
+    char_data::{ColumnUnit, LineColumn, LineColumnRange},
+    concrete_syntax_tree::{ConcreteSyntaxTree, CstChildren, CstGroup, CstNode, CstToken, Trivia},
     expression_tree::{AstExpressionTree, ExpressionPosition, ExpressionTreeWalker},
     identifiers::{self, IdentifierIndex},
+    position_index::{ExpressionAt, PositionIndex},
+    precedence::{
+        Associativity, OperatorDeclaration, OperatorTable, Precedence, RelativePrecedence,
+    },
     token::{
         ErrorTermError, ExpressionBoundary, ExpressionBoundaryError, ExpressionToken, Fixity,
-        OperatorToken, RawErrorTermError, TermToken, Token,
+        FloatParts, OperatorToken, RawErrorTermError, TermToken, Token,
     },
 };
@@ -0,0 +1,471 @@
+use crate::syntax::{
+    ast::Ast,
+    bytes::{ByteIndex, ByteRange},
+    identifiers::{IdentifierIndex, COLON, COMMA, DOT, NEWLINE_SEQUENCE, SEMICOLON},
+    precedence::OperatorTable,
+    token::{ExpressionBoundary, ExpressionToken, FloatParts, OperatorToken, TermToken},
+};
+use ExpressionBoundary::*;
+use OperatorToken::*;
+use TermToken::*;
+
+use super::{sequencer::Sequencer, tokenizer::Tokenizer};
+
+///
+/// Builds an AST from expressions made in Rust, without writing source and
+/// parsing it.
+///
+/// The builder lays out source for the expressions as it goes (in the layout
+/// [`format_source()`](crate::format_source) would give it), and sends it
+/// through the same tokenizer, grouper and binder that parsing does. The AST
+/// is exactly what parsing that source would make: blocks and fields are bound
+/// the same way, [`Ast::to_bytes()`] yields the source, and it can be reparsed
+/// and formatted.
+///
+/// Parentheses are added wherever precedence would otherwise group the
+/// expressions differently than they were built.
+///
+/// # Examples
+///
+/// ```
+/// use berg_parser::{AstBuilder, Expression};
+/// let mut builder = AstBuilder::default();
+/// builder.push(Expression::declare("a", Expression::integer(1)));
+/// builder.push(Expression::field("a").infix("*", Expression::integer(2).infix("+", Expression::integer(3))));
+/// let ast = builder.finish();
+/// assert_eq!(ast.to_bytes(), b"a: 1\na * (2 + 3)");
+/// ```
+///
+#[derive(Debug)]
+pub struct AstBuilder {
+    tokenizer: Tokenizer,
+    ///
+    /// The end of the source laid out so far.
+    ///
+    index: ByteIndex,
+    ///
+    /// Whether any statements have been pushed.
+    ///
+    has_statements: bool,
+}
+
+///
+/// An expression to build with [`AstBuilder`].
+///
+/// Expressions are trees, made from terms with operators, groups and blocks:
+/// `Expression::field("a").infix("+", Expression::integer(1))` is `a + 1`.
+///
+#[derive(Clone, Debug, PartialEq)]
+pub struct Expression(Box<ExpressionNode>);
+
+#[derive(Clone, Debug, PartialEq)]
+enum ExpressionNode {
+    Term(TermKind, String),
+    Prefix(String, Expression),
+    Postfix(Expression, String),
+    Infix(Expression, String, Expression),
+    Group(Expression),
+    Block(Vec<Expression>),
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+enum TermKind {
+    Integer,
+    Float,
+    String,
+    Field,
+}
+
+impl Default for AstBuilder {
+    fn default() -> Self {
+        AstBuilder::with_operators(OperatorTable::default())
+    }
+}
+
+impl AstBuilder {
+    ///
+    /// A builder whose infix operators group according to `operators`.
+    ///
+    pub fn with_operators(operators: OperatorTable) -> Self {
+        let mut tokenizer = Tokenizer::default();
+        *tokenizer.ast_mut() = Ast::with_operators(operators);
+        tokenizer.on_source_start(0.into());
+        let mut builder = AstBuilder {
+            tokenizer,
+            index: 0.into(),
+            has_statements: false,
+        };
+        builder.line_start();
+        builder
+    }
+
+    ///
+    /// Adds a statement to the end of the source, on its own line.
+    ///
+    pub fn push(&mut self, statement: Expression) -> &mut Self {
+        if self.has_statements {
+            self.newline();
+        }
+        self.indent();
+        self.statement(&statement, NEWLINE_SEQUENCE, self.has_statements);
+        self.has_statements = true;
+        self
+    }
+
+    ///
+    /// Finishes the source and returns the AST.
+    ///
+    pub fn finish(mut self) -> Ast {
+        self.tokenizer.ast_mut().char_data.size = self.index;
+        self.tokenizer.on_source_end(self.index)
+    }
+
+    fn expression(&mut self, expression: &Expression) {
+        match &*expression.0 {
+            ExpressionNode::Term(kind, text) => {
+                let ast = self.tokenizer.ast_mut();
+                let token = match kind {
+                    TermKind::Integer => IntegerLiteral(ast.intern_literal(text.as_str())),
+                    TermKind::Float => {
+                        FloatLiteral(ast.intern_literal(text.as_str()), FloatParts::new(text))
+                    }
+                    TermKind::String => StringLiteral(ast.intern_literal(text.as_str())),
+                    TermKind::Field => RawIdentifier(ast.intern_identifier(text.as_str())),
+                };
+                let range = self.advance(text.len());
+                self.tokenizer.on_expression_token(token, range);
+            }
+            ExpressionNode::Prefix(operator, operand) => {
+                let operator_identifier = self
+                    .tokenizer
+                    .ast_mut()
+                    .intern_identifier(operator.as_str());
+                let range = self.advance(operator.len());
+                self.tokenizer.on_expression_token(
+                    ExpressionToken::PrefixOperator(operator_identifier),
+                    range,
+                );
+                self.operand(operand, !operand.is_compact());
+            }
+            ExpressionNode::Postfix(operand, operator) => {
+                self.operand(operand, !operand.is_compact());
+                let operator_identifier = self
+                    .tokenizer
+                    .ast_mut()
+                    .intern_identifier(operator.as_str());
+                let range = self.advance(operator.len());
+                self.tokenizer
+                    .on_operator_token(PostfixOperator(operator_identifier), range);
+            }
+            ExpressionNode::Infix(left, operator, right) => self.infix(left, operator, right),
+            ExpressionNode::Group(inner) => self.parenthesized(inner),
+            ExpressionNode::Block(statements) => {
+                let range = self.advance(1);
+                self.tokenizer.on_open(CurlyBraces, range);
+                if !statements.is_empty() {
+                    self.space();
+                    for (index, statement) in statements.iter().enumerate() {
+                        if index > 0 {
+                            self.separator(InfixOperator(SEMICOLON));
+                            self.space();
+                        }
+                        self.statement(statement, SEMICOLON, index > 0);
+                    }
+                    self.space();
+                }
+                let range = self.advance(1);
+                self.tokenizer.on_close(CurlyBraces, range);
+            }
+        }
+    }
+
+    fn infix(&mut self, left: &Expression, operator: &str, right: &Expression) {
+        let token = self.infix_token(operator);
+        let is_compact = token == InfixOperator(DOT);
+
+        // (a + b) * c: the left side must not take the operator as its right child.
+        let left_needs_parentheses = match left.infix_operator() {
+            Some(left_operator) => {
+                let left_token = self.infix_token(left_operator);
+                self.operators().takes_right_child(left_token, token)
+            }
+            None => is_compact && !left.is_compact(),
+        };
+        self.operand(left, left_needs_parentheses);
+
+        match token {
+            // a.b
+            InfixOperator(DOT) => {
+                let range = self.advance(operator.len());
+                self.tokenizer.on_operator_token(token, range);
+            }
+            // a: b, a; b, a, b
+            InfixOperator(COLON | SEMICOLON | COMMA) => {
+                self.separator(token);
+                self.space();
+            }
+            // a + b
+            _ => {
+                self.space();
+                let range = self.advance(operator.len());
+                self.tokenizer.on_separator(token, range);
+                self.space();
+            }
+        }
+
+        // a - (b + c): the operator must take the right side as its child.
+        let right_needs_parentheses = match right.infix_operator() {
+            Some(right_operator) => {
+                let right_token = self.infix_token(right_operator);
+                !self.operators().takes_right_child(token, right_token)
+            }
+            None => is_compact && !right.is_compact(),
+        };
+        self.operand(right, right_needs_parentheses);
+    }
+
+    ///
+    /// Lays out a statement separated from the ones around it by `separator`,
+    /// in parentheses if the separator would otherwise split it up.
+    ///
+    fn statement(&mut self, statement: &Expression, separator: IdentifierIndex, has_left: bool) {
+        let needs_parentheses = statement.infix_operator().is_some_and(|operator| {
+            let token = self.infix_token(operator);
+            let separator = InfixOperator(separator);
+            self.operators().takes_right_child(token, separator)
+                || (has_left && !self.operators().takes_right_child(separator, token))
+        });
+        self.operand(statement, needs_parentheses);
+    }
+
+    fn operand(&mut self, operand: &Expression, needs_parentheses: bool) {
+        if needs_parentheses {
+            self.parenthesized(operand);
+        } else {
+            self.expression(operand);
+        }
+    }
+
+    fn parenthesized(&mut self, inner: &Expression) {
+        let range = self.advance(1);
+        self.tokenizer.on_open(Parentheses, range);
+        self.expression(inner);
+        let range = self.advance(1);
+        self.tokenizer.on_close(Parentheses, range);
+    }
+
+    fn infix_token(&mut self, operator: &str) -> OperatorToken {
+        let ast = self.tokenizer.ast_mut();
+        if Sequencer::is_assignment_operator(operator.as_bytes()) {
+            InfixAssignment(ast.intern_identifier(&operator[..operator.len() - 1]))
+        } else {
+            InfixOperator(ast.intern_identifier(operator))
+        }
+    }
+
+    fn operators(&self) -> &OperatorTable {
+        &self.tokenizer.ast().operators
+    }
+
+    fn separator(&mut self, token: OperatorToken) {
+        let len = self
+            .tokenizer
+            .ast()
+            .identifiers
+            .resolve(token_identifier(token))
+            .unwrap()
+            .len();
+        let range = self.advance(len);
+        self.tokenizer.on_separator(token, range);
+    }
+
+    fn space(&mut self) {
+        let range = self.advance(1);
+        self.tokenizer.on_space(range);
+    }
+
+    fn newline(&mut self) {
+        let range = self.advance(1);
+        self.tokenizer.on_space(range);
+        self.line_start();
+    }
+
+    fn line_start(&mut self) {
+        let start = self.index;
+        // CharData already starts with the first line.
+        if start > 0 {
+            self.tokenizer.ast_mut().char_data.line_starts.push(start);
+        }
+    }
+
+    fn indent(&mut self) {
+        self.tokenizer.on_line_start(self.index, 0.into());
+    }
+
+    fn advance(&mut self, len: usize) -> ByteRange {
+        let start = self.index;
+        self.index += len;
+        start..self.index
+    }
+}
+
+fn token_identifier(token: OperatorToken) -> IdentifierIndex {
+    match token {
+        InfixOperator(identifier) | InfixAssignment(identifier) | PostfixOperator(identifier) => {
+            identifier
+        }
+        Close(..) | CloseBlock(..) => unreachable!(),
+    }
+}
+
+impl Expression {
+    ///
+    /// An integer literal. Negative numbers are `-` applied to the literal.
+    ///
+    pub fn integer(value: i64) -> Self {
+        let literal = Expression::term(TermKind::Integer, value.unsigned_abs().to_string());
+        if value < 0 {
+            literal.prefix_negative()
+        } else {
+            literal
+        }
+    }
+
+    ///
+    /// A float literal. Negative numbers are `-` applied to the literal.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the value is infinite or NaN, which have no literals.
+    ///
+    pub fn float(value: f64) -> Self {
+        assert!(value.is_finite(), "{} has no float literal", value);
+        // Debug formatting always has a `.` or exponent, so it's never an integer.
+        let literal = Expression::term(TermKind::Float, format!("{:?}", value.abs()));
+        if value.is_sign_negative() {
+            literal.prefix_negative()
+        } else {
+            literal
+        }
+    }
+
+    ///
+    /// A string literal with the given value. Quotes, backslashes, control
+    /// characters and `#{` are escaped.
+    ///
+    pub fn string(value: &str) -> Self {
+        let mut literal = String::with_capacity(value.len() + 2);
+        literal.push('"');
+        let mut chars = value.chars().peekable();
+        while let Some(ch) = chars.next() {
+            match ch {
+                '"' => literal.push_str("\\\""),
+                '\\' => literal.push_str("\\\\"),
+                '\n' => literal.push_str("\\n"),
+                '\r' => literal.push_str("\\r"),
+                '\t' => literal.push_str("\\t"),
+                '\0' => literal.push_str("\\0"),
+                '#' if chars.peek() == Some(&'{') => literal.push_str("\\#"),
+                ch => literal.push(ch),
+            }
+        }
+        literal.push('"');
+        Expression::term(TermKind::String, literal)
+    }
+
+    ///
+    /// A reference to the field with the given name. The field is bound just
+    /// like it would be in source: to the nearest field with that name in
+    /// scope, or a new field if there isn't one.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `name` isn't an identifier.
+    ///
+    pub fn field(name: &str) -> Self {
+        let mut chars = name.chars();
+        assert!(
+            chars
+                .next()
+                .is_some_and(|first| first.is_alphabetic() || first == '_')
+                && chars.all(|ch| ch.is_alphanumeric() || ch == '_'),
+            "{:?} is not an identifier",
+            name
+        );
+        Expression::term(TermKind::Field, name.to_string())
+    }
+
+    ///
+    /// A public field declaration: `name: value`.
+    ///
+    pub fn declare(name: &str, value: Expression) -> Self {
+        Expression::field(name).infix(":", value)
+    }
+
+    ///
+    /// A block of statements: `{ a; b }`.
+    ///
+    pub fn block(statements: impl IntoIterator<Item = Expression>) -> Self {
+        Expression::new(ExpressionNode::Block(statements.into_iter().collect()))
+    }
+
+    ///
+    /// This expression in parentheses: `(a)`.
+    ///
+    pub fn group(self) -> Self {
+        Expression::new(ExpressionNode::Group(self))
+    }
+
+    ///
+    /// A prefix operation, like `-a` or `!a`.
+    ///
+    pub fn prefix(operator: &str, operand: Expression) -> Self {
+        Expression::new(ExpressionNode::Prefix(operator.to_string(), operand))
+    }
+
+    ///
+    /// A postfix operation on this expression, like `a++`.
+    ///
+    pub fn postfix(self, operator: &str) -> Self {
+        Expression::new(ExpressionNode::Postfix(self, operator.to_string()))
+    }
+
+    ///
+    /// An infix operation with this expression on the left, like `a + b`.
+    /// Assignment operators (`=`, `+=`) work here too, as does `.` (with a
+    /// field on the right, as in `a.b`).
+    ///
+    pub fn infix(self, operator: &str, right: Expression) -> Self {
+        Expression::new(ExpressionNode::Infix(self, operator.to_string(), right))
+    }
+
+    fn new(node: ExpressionNode) -> Self {
+        Expression(Box::new(node))
+    }
+
+    fn term(kind: TermKind, text: String) -> Self {
+        Expression::new(ExpressionNode::Term(kind, text))
+    }
+
+    fn prefix_negative(self) -> Self {
+        Expression::prefix("-", self)
+    }
+
+    fn infix_operator(&self) -> Option<&str> {
+        match &*self.0 {
+            ExpressionNode::Infix(_, operator, _) => Some(operator),
+            _ => None,
+        }
+    }
+
+    ///
+    /// Whether this can be written right next to an operator (as in `-a` or
+    /// `a.b`) without changing what the operator applies to.
+    ///
+    fn is_compact(&self) -> bool {
+        matches!(
+            &*self.0,
+            ExpressionNode::Term(..) | ExpressionNode::Group(_) | ExpressionNode::Block(_)
+        )
+    }
+}
@@ -51,6 +51,37 @@ impl Default for Binder {
 }
 
 impl Binder {
+    ///
+    /// Picks up binding right after a statement separator directly inside the
+    /// source, with `ast` holding everything parsed up to there and
+    /// `top_level_fields` the fields declared in the source's scope so far.
+    ///
+    pub fn resume(ast: Ast, top_level_fields: &[FieldIndex]) -> Self {
+        let mut binder = Binder {
+            ast,
+            ..Default::default()
+        };
+        // Both the root and the source scope start right after the root names.
+        let source = OpenScope {
+            open_index: 0.into(),
+            index: 0.into(),
+            scope_start: binder.scope.len(),
+        };
+        binder.open_scopes.push(source);
+        binder.scope.extend_from_slice(top_level_fields);
+        binder
+    }
+
+    ///
+    /// The fields declared in the source's scope, if no other block is open.
+    ///
+    pub fn top_level_fields(&self) -> Option<&[FieldIndex]> {
+        match self.open_scopes.len() {
+            2 => Some(&self.scope[keywords::FIELD_NAMES.len()..]),
+            _ => None,
+        }
+    }
+
     pub fn on_source_end(self) -> Ast {
         self.ast
     }
@@ -69,8 +100,9 @@ impl Binder {
                 {
                     self.push_field_reference(name, range)
                 }
-                IntegerLiteral(_) | RawIdentifier(_) | ErrorTerm(..) | RawErrorTerm(..)
-                | MissingExpression => self.ast.push_token(token, range),
+                IntegerLiteral(_) | HexadecimalLiteral(_) | OctalLiteral(_) | BinaryLiteral(_)
+                | FloatLiteral(..) | ImaginaryLiteral(..) | StringLiteral(_) | StringFragment(_)
+                | TextBlock(_) | RawIdentifier(_) | ErrorTerm(..) | RawErrorTerm(..) | MissingExpression => self.ast.push_token(token, range),
                 // The binder generates these tokens, so should not receive them as input.
                 FieldReference(_) => unreachable!(),
             },
@@ -149,7 +181,7 @@ impl Binder {
             // example, if we start making a table of which fields are referenced by
             // other blocks). Watch out for that! We'll either need to delay identification
             // until we know the next token, or else have to go fix it up.
-            if field < self.open_scope().scope_start {
+            if field < self.ast.blocks[self.open_block_index()].scope_start {
                 let name = self.ast.fields[field].name;
                 let new_field = self.create_field(name, true);
                 self.ast.tokens[prev_token_index] = FieldReference(new_field).into();
@@ -199,7 +231,6 @@ impl Binder {
                 delta,
                 boundary,
             };
-            println!("insert block {:?} at {}", ast_block, index);
             (index, ast_block)
         };
 
@@ -279,7 +310,6 @@ impl Binder {
             block.scope_count = FieldIndex(self.ast.fields.len() as u32) - block.scope_start;
             block.delta = delta;
         }
-        println!("push close scope {:?}", self.ast.blocks[open_scope.index]);
         self.scope.truncate(open_scope.scope_start);
         open_scope.index
     }
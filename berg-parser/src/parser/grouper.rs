@@ -1,6 +1,8 @@
 use crate::syntax::{
     ast::{Ast, AstDelta, AstIndex},
+    block::FieldIndex,
     bytes::ByteRange,
+    identifiers::NEWLINE_SEQUENCE,
     token::{
         ExpressionBoundary, ExpressionBoundaryError, ExpressionToken, OperatorToken, TermToken,
         Token,
@@ -58,6 +60,34 @@ struct OpenExpression {
 }
 
 impl Grouper {
+    ///
+    /// Picks up grouping right after the newline at `newline_index`, which
+    /// separates two statements directly inside the source.
+    ///
+    pub fn resume(ast: Ast, top_level_fields: &[FieldIndex], newline_index: AstIndex) -> Self {
+        let source = OpenExpression {
+            infix: Some((InfixOperator(NEWLINE_SEQUENCE), newline_index)),
+            open_index: 0.into(),
+            boundary: Source,
+        };
+        Grouper {
+            binder: Binder::resume(ast, top_level_fields),
+            open_expressions: vec![source],
+            start_auto_block: false,
+        }
+    }
+
+    ///
+    /// The fields declared in the source's scope, if nothing but the source is
+    /// open.
+    ///
+    pub fn top_level_fields(&self) -> Option<&[FieldIndex]> {
+        match self.open_expressions.len() {
+            1 if !self.start_auto_block => self.binder.top_level_fields(),
+            _ => None,
+        }
+    }
+
     pub fn ast(&self) -> &Ast {
         &self.binder.ast
     }
@@ -118,8 +148,22 @@ impl Grouper {
         }
     }
 
-    pub fn on_indent_mismatch(&self, _mismatch_level: usize) {
-        // TODO fill this in
+    ///
+    /// Marks the block we just closed as closed by a line that undented more
+    /// than one level.
+    ///
+    pub fn on_multiple_undent(&mut self) {
+        let ast = self.ast_mut();
+        let close_index = ast.tokens.last_index();
+        let delta = match ast.tokens[close_index] {
+            Token::Operator(Close(delta, _)) => delta,
+            Token::Operator(CloseBlock(block_index, _)) => ast.blocks[block_index].delta,
+            _ => unreachable!("{}: {:?}", close_index, ast.tokens[close_index]),
+        };
+        match ast.tokens[close_index - delta] {
+            Token::Expression(Open(ref mut error @ None, ..)) => *error = Some(MultipleUndent),
+            _ => unreachable!(),
+        }
     }
 
     pub fn on_source_end(self) -> Ast {
@@ -135,14 +179,14 @@ impl Grouper {
         // e.g. 1+2>3*4 -> 1+2>(3* ...
         // e.g. 1>2+3*4 -> 1>(2+(3* ...
         let open_expression = self.open_expression();
-        if let Some((infix, index)) = open_expression.infix {
-            if infix.takes_right_child(next_infix) {
-                self.open_expressions.push(OpenExpression {
-                    open_index: index + 1,
-                    boundary: PrecedenceGroup,
-                    infix: None,
-                });
-            }
+        if let Some((infix, index)) = open_expression.infix
+            && self.ast().operators.takes_right_child(infix, next_infix)
+        {
+            self.open_expressions.push(OpenExpression {
+                open_index: index + 1,
+                boundary: PrecedenceGroup,
+                infix: None,
+            });
         }
     }
 
@@ -155,7 +199,7 @@ impl Grouper {
             _ => return true,
         };
         if let Some((infix, _)) = infix {
-            infix.takes_right_child(next_infix)
+            self.ast().operators.takes_right_child(infix, next_infix)
         } else {
             true
         }
@@ -368,11 +412,14 @@ impl Grouper {
             ExpressionBoundary::PrecedenceGroup => {
                 match open_expression.infix {
                     Some((infix, infix_index)) => {
+                        let operators = &self.binder.ast.operators;
                         let parent_index = self.open_expressions.len() - 1;
                         let parent = &mut self.open_expressions[parent_index];
                         match parent.infix {
                             // If this parent has an infix and takes us as a right child, we are definitely needed.
-                            Some((parent_infix, _)) if parent_infix.takes_right_child(infix) => {
+                            Some((parent_infix, _))
+                                if operators.takes_right_child(parent_infix, infix) =>
+                            {
                                 Some(open_expression)
                             }
                             // If the parent has no infix, or if our infix is the new parent, we are not needed,
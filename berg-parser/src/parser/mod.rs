@@ -1,14 +1,27 @@
+mod ast_builder;
 mod binder;
 mod grouper;
+mod parse_cache;
+mod reparse;
 mod scanner;
 mod sequencer;
 mod tokenizer;
 
 use std::borrow::Cow;
+use std::fmt;
 
+pub use ast_builder::{AstBuilder, Expression};
+use berg_util::to_indexed_cow;
+pub use parse_cache::ParseCache;
 use sequencer::Sequencer;
+pub use sequencer::{MAX_IDENTIFIER_LENGTH, MAX_LINE_LENGTH};
 
-use crate::syntax::{ast::Ast, bytes::ByteSlice};
+use crate::syntax::{
+    ast::Ast,
+    bytes::{ByteRange, ByteSlice},
+    precedence::OperatorTable,
+    source_formatter::SourceFormatter,
+};
 
 ///
 /// Opens and parses the source into an AST.
@@ -22,34 +35,89 @@ use crate::syntax::{ast::Ast, bytes::ByteSlice};
 /// Errors placed in the AST include any parse error or open error.
 ///
 pub fn parse(buffer: Cow<'static, ByteSlice>) -> Ast {
-    let ast = Sequencer::new(buffer).parse();
-    println!();
-    println!("Parsed:");
-    let mut level = 0;
-    for i in 0..ast.tokens.len() {
-        use crate::syntax::token::{ExpressionToken, OperatorToken, Token};
-        let token = ast.token(i.into());
-        let token_range = ast.token_range(i.into());
-        if let Token::Operator(OperatorToken::Close(..)) = token {
-            level -= 1
-        }
-        if let Token::Operator(OperatorToken::CloseBlock(..)) = token {
-            level -= 1
-        }
-        println!(
-            "{:>3} {:<indent$}{:<16} | {:?}  at {}..{}",
-            i,
-            "",
-            ast.visible_token_string(i.into()),
-            token,
-            token_range.start,
-            token_range.end,
-            indent = level * 4
-        );
-        if let Token::Expression(ExpressionToken::Open(..)) = token {
-            level += 1
+    parse_with_operators(buffer, OperatorTable::default())
+}
+
+///
+/// Parses the source into an AST, grouping infix operators according to the
+/// declarations in `operators`.
+///
+/// The declarations are kept in the AST, so [`reparse()`] and
+/// [`format_source()`] group operators the same way.
+///
+pub fn parse_with_operators(buffer: Cow<'static, ByteSlice>, operators: OperatorTable) -> Ast {
+    Sequencer::new(buffer, operators).parse()
+}
+
+///
+/// Reparses an AST after the bytes in `edit` are replaced with `text`, with
+/// the same operator declarations. The result is the same as parsing the
+/// edited source from scratch.
+///
+/// Only the top-level statements around the edit are parsed again: parsing
+/// picks up at the last unindented statement before the edit, and stops at
+/// the first one after it where the old parse was in the same state, taking
+/// the rest from the old AST. Sources with operators declared looser than a
+/// newline, or with a close that has no open, are parsed again in full.
+///
+/// # Panics
+///
+/// Panics if `edit` is not inside the source.
+///
+pub fn reparse(ast: Ast, edit: ByteRange, text: &[u8]) -> Ast {
+    assert!(edit.start <= edit.end && edit.end <= ast.char_data.size);
+    reparse::reparse(ast, edit, text)
+}
+
+///
+/// Formats the source of an AST into canonical layout (see [`SourceFormatter`]).
+///
+/// # Errors
+///
+/// Formatting never changes what the source means: the result is parsed again,
+/// and if its expression tree is different from the original,
+/// [`FormatError::ChangesMeaning`] is returned instead.
+///
+pub fn format_source(ast: &Ast) -> Result<Vec<u8>, FormatError> {
+    let formatted = SourceFormatter::new(ast).to_bytes();
+    let formatted_ast = parse_with_operators(
+        to_indexed_cow(Cow::Owned(formatted.clone())),
+        ast.operators.clone(),
+    );
+    if same_expression_tree(ast, &formatted_ast) {
+        Ok(formatted)
+    } else {
+        Err(FormatError::ChangesMeaning)
+    }
+}
+
+///
+/// Why source couldn't be formatted.
+///
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum FormatError {
+    ///
+    /// The formatted source parses differently from the original.
+    ///
+    ChangesMeaning,
+}
+
+impl fmt::Display for FormatError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            FormatError::ChangesMeaning => {
+                write!(f, "could not be formatted without changing what it means")
+            }
         }
     }
-    println!();
-    ast
+}
+
+// Whether two ASTs have the same tokens, blocks and fields, ignoring where they are.
+fn same_expression_tree(a: &Ast, b: &Ast) -> bool {
+    a.tokens == b.tokens
+        && a.blocks == b.blocks
+        && a.fields == b.fields
+        && a.identifiers.iter().eq(b.identifiers.iter())
+        && a.literals.iter().eq(b.literals.iter())
+        && a.raw_literals == b.raw_literals
 }
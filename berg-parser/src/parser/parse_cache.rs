@@ -0,0 +1,91 @@
+use std::borrow::Cow;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process;
+
+use crate::syntax::{
+    ast::Ast,
+    ast_binary::content_hash,
+    bytes::ByteSlice,
+    precedence::OperatorTable,
+};
+
+///
+/// A directory of parsed ASTs, so that source that hasn't changed doesn't
+/// have to be parsed again.
+///
+/// Each AST is stored in the binary AST format, in a file named after the
+/// [`content_hash()`] of its source. If the file is missing, unreadable,
+/// from another format version, or for different source, the source is
+/// parsed and the file is written again.
+///
+#[derive(Debug, Clone)]
+pub struct ParseCache {
+    dir: PathBuf,
+}
+
+impl ParseCache {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        ParseCache { dir: dir.into() }
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    ///
+    /// The file the AST for source with the given hash is stored in.
+    ///
+    pub fn path(&self, hash: u64) -> PathBuf {
+        self.dir.join(format!("{:016x}.bast", hash))
+    }
+
+    ///
+    /// Loads the AST for the source from the cache, or parses it (and stores
+    /// it in the cache) if it isn't there.
+    ///
+    /// Like [`parse()`](crate::parse), this always returns an AST. Failing to
+    /// read or write the cache just means the source gets parsed.
+    ///
+    pub fn parse(&self, buffer: Cow<'static, ByteSlice>) -> Ast {
+        self.parse_with_operators(buffer, OperatorTable::default())
+    }
+
+    ///
+    /// Like [`parse()`](Self::parse), but grouping infix operators according
+    /// to `operators`. A cached AST parsed with different operators is
+    /// parsed again (and replaced).
+    ///
+    pub fn parse_with_operators(
+        &self,
+        buffer: Cow<'static, ByteSlice>,
+        operators: OperatorTable,
+    ) -> Ast {
+        let hash = content_hash(buffer.as_raw_slice());
+        let path = self.path(hash);
+        if let Ok(bytes) = fs::read(&path)
+            && let Ok(ast) = Ast::from_binary(&bytes, hash)
+            // The AST has the whole source, so a different source with the
+            // same hash can be ruled out for sure.
+            && ast.operators == operators
+            && ast.to_bytes() == buffer.as_raw_slice()
+        {
+            return ast;
+        }
+        let ast = super::parse_with_operators(buffer, operators);
+        // A cache we can't write to is no reason to fail the parse.
+        let _ = self.store(&path, &ast.to_binary(hash));
+        ast
+    }
+
+    fn store(&self, path: &Path, bytes: &[u8]) -> io::Result<()> {
+        fs::create_dir_all(&self.dir)?;
+        // Write to a temporary file first, so that nobody reads half an AST.
+        let temp_path = path.with_extension(format!("{}.tmp", process::id()));
+        fs::write(&temp_path, bytes)?;
+        fs::rename(&temp_path, path).inspect_err(|_| {
+            let _ = fs::remove_file(&temp_path);
+        })
+    }
+}
@@ -0,0 +1,688 @@
+use std::borrow::Cow;
+use std::collections::{HashMap, HashSet};
+use std::mem;
+
+use berg_util::to_indexed_cow;
+use string_interner::backend::StringBackend;
+use string_interner::{StringInterner, Symbol};
+
+use crate::syntax::{
+    ast::{Ast, AstIndex, LiteralIndex, RawLiteralIndex, WhitespaceIndex},
+    block::{AstBlock, BlockIndex, FieldIndex},
+    bytes::{ByteIndex, ByteRange},
+    identifiers::{keywords, IdentifierIndex, COLON, NEWLINE_SEQUENCE},
+    token::{
+        ExpressionBoundary, ExpressionBoundaryError, ExpressionToken, OperatorToken, TermToken,
+        Token,
+    },
+};
+use ExpressionBoundary::*;
+use ExpressionBoundaryError::*;
+use ExpressionToken::*;
+use OperatorToken::*;
+use TermToken::*;
+
+use super::{parse_with_operators, sequencer::Sequencer, tokenizer::Tokenizer};
+
+///
+/// Reparses `old` after the bytes in `edit` are replaced with `text`.
+///
+/// Everything up to the last top-level statement that ends before the edit is
+/// kept, and sequencing picks up from there. Once it reaches an unindented
+/// line after the edit where the old parse was in the same state (nothing
+/// open but the source, and the same fields in scope), the rest of the old
+/// AST is spliced on with its indices and positions shifted.
+///
+pub fn reparse(mut old: Ast, edit: ByteRange, text: &[u8]) -> Ast {
+    let old_source = old.source.take().unwrap_or_else(|| old.to_bytes());
+    let mut buffer = old_source[..usize::from(edit.start)].to_vec();
+    buffer.extend_from_slice(text);
+    buffer.extend_from_slice(&old_source[usize::from(edit.end)..]);
+    let source = buffer.clone();
+    let buffer = to_indexed_cow(Cow::Owned(buffer));
+
+    let mut ast = if can_resume(&old) {
+        let new_edit_end = edit.start + text.len();
+        let resume = resume_point(&old, &old_source, edit.start);
+        let mut scan = OldScan::new();
+        let mut ast = Ast::with_operators(old.operators.clone());
+        mem::swap(&mut ast.identifiers, &mut old.identifiers);
+        mem::swap(&mut ast.literals, &mut old.literals);
+        mem::swap(
+            &mut ast.char_data.whitespace_characters,
+            &mut old.char_data.whitespace_characters,
+        );
+        match resume {
+            Some(newline_index) => {
+                while scan.index <= newline_index {
+                    scan.step(&old);
+                }
+                let start = old.token_ranges[newline_index].start;
+                copy_prefix(&old, &scan, start, &mut ast);
+                let tokenizer = Tokenizer::resume(ast, &scan.top_level_fields, newline_index);
+                let resync = Resync::new(old, scan, edit.end, new_edit_end);
+                Sequencer::resume(buffer, tokenizer, start, Some(resync)).parse_rest()
+            }
+            None => {
+                let mut tokenizer = Tokenizer::default();
+                *tokenizer.ast_mut() = ast;
+                let resync = Resync::new(old, scan, edit.end, new_edit_end);
+                Sequencer::resume(buffer, tokenizer, 0.into(), Some(resync)).parse()
+            }
+        }
+    } else {
+        parse_with_operators(buffer, old.operators)
+    };
+
+    mark_public_fields(&mut ast);
+    intern_in_first_use_order(&mut ast);
+    ast.source.set(source).unwrap();
+    ast
+}
+
+///
+/// Watches a reparse for an unindented line where it is back in step with the
+/// old parse, and splices the rest of the old AST on when it gets there.
+///
+#[derive(Debug)]
+pub struct Resync {
+    old: Ast,
+    /// How far the old tokens have been walked.
+    scan: OldScan,
+    /// The end of the edit in the old source.
+    old_edit_end: ByteIndex,
+    /// The end of the edit in the new source.
+    new_edit_end: ByteIndex,
+    /// How many top-level fields were declared before the reparsed statements.
+    kept_fields: usize,
+    /// The names of the fields the old parse refers to after the edit.
+    names_after_edit: Option<HashSet<IdentifierIndex>>,
+    /// Set once the parses can no longer get back in step.
+    gave_up: bool,
+    found: Option<Found>,
+}
+
+///
+/// A statement start where both parses are in the same state.
+///
+#[derive(Debug)]
+struct Found {
+    old_newline: AstIndex,
+    old_start: ByteIndex,
+    new_start: ByteIndex,
+    top_level_fields: Vec<FieldIndex>,
+}
+
+///
+/// A walk through the old tokens, counting what they declare.
+///
+#[derive(Debug)]
+struct OldScan {
+    /// The next token to look at.
+    index: AstIndex,
+    /// Open groups of any kind, except the source.
+    group_depth: usize,
+    /// Open blocks, except the source.
+    block_depth: usize,
+    fields_end: FieldIndex,
+    blocks_end: BlockIndex,
+    raw_literals_end: RawLiteralIndex,
+    /// The fields declared in the source's scope, in the order they were declared.
+    top_level_fields: Vec<FieldIndex>,
+    /// Whether an interpolation was closed by something other than its `}`.
+    /// The sequencer still counts it as open, so nothing after it can be reused.
+    stale_interpolation: bool,
+}
+
+impl Resync {
+    fn new(old: Ast, scan: OldScan, old_edit_end: ByteIndex, new_edit_end: ByteIndex) -> Self {
+        Resync {
+            kept_fields: scan.top_level_fields.len(),
+            names_after_edit: None,
+            old,
+            scan,
+            old_edit_end,
+            new_edit_end,
+            gave_up: false,
+            found: None,
+        }
+    }
+
+    pub fn is_found(&self) -> bool {
+        self.found.is_some()
+    }
+
+    ///
+    /// Checks whether the unindented line at `start` starts a statement in the
+    /// same state the old parse started it in. If the old parse did start a
+    /// statement there, the separating newline is emitted right away.
+    ///
+    pub fn on_line_start(
+        &mut self,
+        start: ByteIndex,
+        tokenizer: &mut Tokenizer,
+        open_interpolations: &[usize],
+    ) {
+        if self.gave_up || start < self.new_edit_end || tokenizer.prev_was_operator {
+            return;
+        }
+        let old_start = self.old_edit_end + (start - self.new_edit_end);
+        let Some(old_newline) = self.scan.advance_to_statement(&self.old, old_start) else {
+            return;
+        };
+        if self.scan.stale_interpolation {
+            self.gave_up = true;
+            return;
+        }
+
+        // The source after this is the same, so this parse starts a statement here too.
+        tokenizer.on_statement_start(start);
+        if !open_interpolations.is_empty() {
+            return;
+        }
+        let Some(new_fields) = tokenizer.top_level_fields() else {
+            return;
+        };
+        if self.same_scope(tokenizer.ast(), new_fields) {
+            self.found = Some(Found {
+                old_newline,
+                old_start,
+                new_start: start,
+                top_level_fields: new_fields.to_vec(),
+            });
+        }
+    }
+
+    ///
+    /// Whether the rest of the old source would find the same fields in
+    /// `new_fields` that it found in the old top-level fields.
+    ///
+    /// The reparsed statements may declare different fields, as long as the
+    /// rest of the source doesn't refer to any name one declares and the other
+    /// doesn't.
+    ///
+    fn same_scope(&mut self, ast: &Ast, new_fields: &[FieldIndex]) -> bool {
+        let new_names = new_fields[self.kept_fields..]
+            .iter()
+            .map(|&field| ast.fields[field].name);
+        let old_names = self.scan.top_level_fields[self.kept_fields..]
+            .iter()
+            .map(|&field| self.old.fields[field].name);
+        if new_names.clone().eq(old_names.clone()) {
+            return true;
+        }
+
+        let (scan, old) = (&self.scan, &self.old);
+        let used = self.names_after_edit.get_or_insert_with(|| {
+            let tokens = &old.tokens[scan.index..];
+            tokens
+                .iter()
+                .filter_map(|token| match token {
+                    Token::Expression(Term(FieldReference(field))) => Some(old.fields[*field].name),
+                    _ => None,
+                })
+                .collect()
+        });
+        let new_names: HashSet<_> = new_names.filter(|name| used.contains(name)).collect();
+        let old_names: HashSet<_> = old_names.filter(|name| used.contains(name)).collect();
+        new_names == old_names
+    }
+
+    ///
+    /// Appends the old tokens after the statement start that was found to
+    /// `ast`, which has just emitted the newline starting the same statement.
+    ///
+    pub fn splice(self, mut ast: Ast, size: ByteIndex) -> Ast {
+        let Resync {
+            old, scan, found, ..
+        } = self;
+        let found = found.unwrap();
+        let old_fields_end = scan.fields_end;
+        let (new_fields_end, new_blocks_end, new_raw_literals_end) = (
+            ast.fields.next_index(),
+            ast.blocks.next_index(),
+            ast.raw_literals.next_index(),
+        );
+        let position = |index: ByteIndex| found.new_start + (index - found.old_start);
+        // Names refer to the last top-level field declared with them, or else a
+        // root field, in both parses.
+        let top_level_fields: HashMap<_, _> = found
+            .top_level_fields
+            .iter()
+            .map(|&field| (ast.fields[field].name, field))
+            .collect();
+        let field = |field: FieldIndex| {
+            if field >= old_fields_end {
+                new_fields_end + (field - old_fields_end)
+            } else {
+                let name = old.fields[field].name;
+                top_level_fields.get(&name).copied().unwrap_or(field)
+            }
+        };
+        let block = |block: BlockIndex| {
+            if block >= scan.blocks_end {
+                new_blocks_end + (block - scan.blocks_end)
+            } else {
+                block
+            }
+        };
+
+        let mut index = found.old_newline + 1;
+        while index < old.tokens.len() {
+            let token = match old.tokens[index] {
+                Token::Expression(Term(FieldReference(index))) => {
+                    FieldReference(field(index)).into()
+                }
+                Token::Expression(Term(RawErrorTerm(error, index))) => {
+                    let index = new_raw_literals_end + (index - scan.raw_literals_end);
+                    RawErrorTerm(error, index).into()
+                }
+                Token::Operator(CloseBlock(index, boundary)) => {
+                    CloseBlock(block(index), boundary).into()
+                }
+                token => token,
+            };
+            let range = &old.token_ranges[index];
+            ast.tokens.push(token);
+            ast.token_ranges
+                .push(position(range.start)..position(range.end));
+            index += 1;
+        }
+        for (index, old_block) in old.blocks.iter().enumerate().skip(scan.blocks_end.into()) {
+            let new_index = block(index);
+            ast.blocks.push(AstBlock {
+                boundary: old_block.boundary,
+                parent: new_index - block(index - old_block.parent),
+                delta: old_block.delta,
+                scope_start: field(old_block.scope_start),
+                scope_count: old_block.scope_count,
+            });
+        }
+        for old_field in &old.fields[old_fields_end..] {
+            ast.fields.push(old_field.clone());
+        }
+        for raw_literal in &old.raw_literals[scan.raw_literals_end..] {
+            ast.raw_literals.push(raw_literal.clone());
+        }
+
+        let (old_chars, chars) = (&old.char_data, &mut ast.char_data);
+        let after = |index: ByteIndex| index > found.old_start;
+        let from = |index: ByteIndex| index >= found.old_start;
+        let line_starts = &old_chars.line_starts;
+        let line_starts = &line_starts[line_starts.partition_point(|&index| !after(index))..];
+        chars
+            .line_starts
+            .extend(line_starts.iter().map(|&index| position(index)));
+        let ranges = &old_chars.whitespace_ranges;
+        let ranges = &ranges[ranges.partition_point(|&(_, index)| !from(index))..];
+        (chars.whitespace_ranges).extend(
+            ranges
+                .iter()
+                .map(|&(whitespace, index)| (whitespace, position(index))),
+        );
+        let comments = &old_chars.comments;
+        let comments = &comments[comments.partition_point(|(_, index)| !from(*index))..];
+        (chars.comments).extend(
+            comments
+                .iter()
+                .map(|(comment, index)| (comment.clone(), position(*index))),
+        );
+        let separators = &old_chars.digit_separators;
+        let separators = &separators[separators.partition_point(|&index| !from(index))..];
+        chars
+            .digit_separators
+            .extend(separators.iter().map(|&index| position(index)));
+        chars.size = size;
+
+        // The source block now ends at the old close.
+        let delta = ast.tokens.last_index() - AstIndex(0);
+        match ast.tokens[AstIndex(0)] {
+            Token::Expression(Open(_, Source, ref mut open_delta)) => *open_delta = delta,
+            _ => unreachable!(),
+        }
+        let source = &mut ast.blocks[BlockIndex(0)];
+        source.delta = delta;
+        source.scope_count = ast.fields.next_index() - source.scope_start;
+        ast
+    }
+}
+
+impl OldScan {
+    // Starts right after the source's open token.
+    fn new() -> Self {
+        OldScan {
+            index: AstIndex(1),
+            group_depth: 0,
+            block_depth: 0,
+            fields_end: keywords::FIELD_NAMES.len().into(),
+            blocks_end: BlockIndex(1),
+            raw_literals_end: RawLiteralIndex(0),
+            top_level_fields: Vec::new(),
+            stale_interpolation: false,
+        }
+    }
+
+    fn step(&mut self, old: &Ast) {
+        match old.tokens[self.index] {
+            Token::Expression(Open(error, boundary, _)) => {
+                self.group_depth += 1;
+                if boundary.is_block() {
+                    self.block_depth += 1;
+                    self.blocks_end += 1;
+                }
+                if boundary == Interpolation && error == Some(OpenWithoutClose) {
+                    self.stale_interpolation = true;
+                }
+            }
+            Token::Operator(Close(..)) => self.group_depth -= 1,
+            Token::Operator(CloseBlock(..)) => {
+                self.group_depth -= 1;
+                self.block_depth -= 1;
+            }
+            Token::Expression(Term(FieldReference(field))) if field >= self.fields_end => {
+                self.fields_end = field + 1;
+                if self.block_depth == 0 {
+                    self.top_level_fields.push(field);
+                }
+            }
+            Token::Expression(Term(RawErrorTerm(_, raw_literal))) => {
+                self.raw_literals_end = raw_literal + 1;
+            }
+            _ => {}
+        }
+        self.index += 1;
+    }
+
+    ///
+    /// Walks the old tokens up to `start`, returning the newline there if the
+    /// old parse started a top-level statement at `start`.
+    ///
+    fn advance_to_statement(&mut self, old: &Ast, start: ByteIndex) -> Option<AstIndex> {
+        // The last token closes the source, which is never walked past.
+        let end = old.tokens.last_index();
+        while self.index < end {
+            let token_start = old.token_ranges[self.index].start;
+            let is_close = matches!(
+                old.tokens[self.index],
+                Token::Operator(Close(..) | CloseBlock(..))
+            );
+            if token_start > start || (token_start == start && !is_close) {
+                break;
+            }
+            self.step(old);
+        }
+        let is_newline = self.index < end
+            && matches!(
+                old.tokens[self.index],
+                Token::Operator(InfixOperator(NEWLINE_SEQUENCE))
+            )
+            && old.token_ranges[self.index].start == start
+            && self.group_depth == 0
+            && old.char_data.line_starts.binary_search(&start).is_ok();
+        if is_newline {
+            self.step(old);
+            Some(self.index - 1)
+        } else {
+            None
+        }
+    }
+}
+
+///
+/// Whether any statement of `old` can be reused: the grouping of the source
+/// can't depend on anything but the statements directly in it.
+///
+fn can_resume(old: &Ast) -> bool {
+    let newline = InfixOperator(NEWLINE_SEQUENCE);
+    // Operators looser than a newline can group across statements.
+    let looser_than_newline = old.operators.declarations().any(|(name, _)| {
+        let operator = old.operators.identifiers().get(name).unwrap();
+        old.operators
+            .takes_right_child(InfixOperator(operator), newline)
+    });
+    // An unmatched close inserts its open at the start of the source, which
+    // changes everything before it.
+    let close_without_open = old
+        .tokens
+        .iter()
+        .any(|token| matches!(token, Token::Expression(Open(Some(CloseWithoutOpen), ..))));
+    !looser_than_newline
+        && !old.operators.takes_right_child(newline, newline)
+        && !close_without_open
+}
+
+///
+/// The newline starting the last top-level statement whose first line ends
+/// before `edit_start`.
+///
+fn resume_point(old: &Ast, old_source: &[u8], edit_start: ByteIndex) -> Option<AstIndex> {
+    let line_starts = &old.char_data.line_starts;
+    let mut resume = None;
+    let mut depth = 0;
+    let mut index = AstIndex(1);
+    while index < old.tokens.last_index() && old.token_ranges[index].start < edit_start {
+        match old.tokens[index] {
+            Token::Expression(Open(Some(OpenWithoutClose), Interpolation, _)) => break,
+            Token::Expression(Open(..)) => depth += 1,
+            Token::Operator(Close(..) | CloseBlock(..)) => depth -= 1,
+            Token::Operator(InfixOperator(NEWLINE_SEQUENCE)) if depth == 0 => {
+                // A block comment only starts a statement if it is never
+                // closed, which the edit can change.
+                let start = old.token_ranges[index].start;
+                if let Ok(line) = line_starts.binary_search(&start)
+                    && line_starts
+                        .get(line + 1)
+                        .is_some_and(|&next| next <= edit_start)
+                    && old_source[usize::from(start)] != b'#'
+                {
+                    resume = Some(index);
+                }
+            }
+            _ => {}
+        }
+        index += 1;
+    }
+    resume
+}
+
+///
+/// Copies everything the old parse produced before `start` into `ast`.
+///
+fn copy_prefix(old: &Ast, scan: &OldScan, start: ByteIndex, ast: &mut Ast) {
+    ast.tokens = old.tokens[..scan.index].to_vec().into();
+    ast.token_ranges = old.token_ranges[..scan.index].to_vec().into();
+    ast.blocks = old.blocks[..scan.blocks_end].to_vec().into();
+    ast.fields = old.fields[..scan.fields_end].to_vec().into();
+    ast.raw_literals = old.raw_literals[..scan.raw_literals_end].to_vec().into();
+    let (old_chars, chars) = (&old.char_data, &mut ast.char_data);
+    let line_starts = &old_chars.line_starts;
+    chars.line_starts =
+        line_starts[..line_starts.partition_point(|&index| index <= start)].to_vec();
+    let ranges = &old_chars.whitespace_ranges;
+    chars.whitespace_ranges =
+        ranges[..ranges.partition_point(|&(_, index)| index < start)].to_vec();
+    let comments = &old_chars.comments;
+    chars.comments = comments[..comments.partition_point(|(_, index)| *index < start)].to_vec();
+    let separators = &old_chars.digit_separators;
+    chars.digit_separators =
+        separators[..separators.partition_point(|&index| index < start)].to_vec();
+}
+
+///
+/// Marks the fields that are declared with `:` somewhere, since statements
+/// reparsed or reused from the old parse may have changed which are.
+///
+/// The binder sees `:` and the field next to each other, so groups opened
+/// between them after the fact are skipped.
+///
+fn mark_public_fields(ast: &mut Ast) {
+    for field in ast.fields.iter_mut() {
+        field.is_public = false;
+    }
+    let mut prev = None;
+    for &token in ast.tokens.iter() {
+        let declared = match (prev, token) {
+            (_, Token::Expression(Open(_, PrecedenceGroup | CompoundTerm, _)))
+            | (_, Token::Expression(Open(Some(CloseWithoutOpen), ..))) => continue,
+            (
+                Some(Token::Expression(PrefixOperator(COLON))),
+                Token::Expression(Term(FieldReference(field))),
+            )
+            | (
+                Some(Token::Expression(Term(FieldReference(field)))),
+                Token::Operator(InfixOperator(COLON)),
+            ) => Some(field),
+            _ => None,
+        };
+        if let Some(field) = declared {
+            ast.fields[field].is_public = true;
+        }
+        prev = Some(token);
+    }
+}
+
+///
+/// Puts the interned identifiers, literals and whitespace in the order they
+/// are first used, like a parse from scratch would: a reparse adds new ones at
+/// the end, and keeps ones that are no longer used.
+///
+fn intern_in_first_use_order(ast: &mut Ast) {
+    let tokens = ast.tokens.as_raw_slice();
+    let identifiers = tokens.iter().filter_map(|token| identifier(token, ast));
+    let field_names = ast.fields.as_raw_slice().iter().map(|field| field.name);
+    let base = ast.operators.identifiers().clone();
+    if let Some((interner, map)) =
+        first_use_order(&ast.identifiers, base, identifiers.chain(field_names))
+    {
+        let map = |name: IdentifierIndex| map[name.to_usize()].unwrap();
+        ast.identifiers = interner;
+        for token in ast.tokens.iter_mut() {
+            *token = match *token {
+                Token::Expression(Term(RawIdentifier(name))) => RawIdentifier(map(name)).into(),
+                Token::Expression(PrefixOperator(name)) => PrefixOperator(map(name)).into(),
+                Token::Operator(InfixOperator(name)) => InfixOperator(map(name)).into(),
+                Token::Operator(InfixAssignment(name)) => InfixAssignment(map(name)).into(),
+                Token::Operator(PostfixOperator(name)) => PostfixOperator(map(name)).into(),
+                token => token,
+            };
+        }
+        for field in ast.fields.iter_mut() {
+            field.name = map(field.name);
+        }
+    }
+
+    let literals = ast.tokens.as_raw_slice().iter().filter_map(literal);
+    if let Some((interner, map)) = first_use_order(&ast.literals, StringInterner::new(), literals) {
+        let map = |literal: LiteralIndex| map[literal.to_usize()].unwrap();
+        ast.literals = interner;
+        for token in ast.tokens.iter_mut() {
+            *token = match *token {
+                Token::Expression(Term(IntegerLiteral(literal))) => {
+                    IntegerLiteral(map(literal)).into()
+                }
+                Token::Expression(Term(HexadecimalLiteral(literal))) => {
+                    HexadecimalLiteral(map(literal)).into()
+                }
+                Token::Expression(Term(OctalLiteral(literal))) => OctalLiteral(map(literal)).into(),
+                Token::Expression(Term(BinaryLiteral(literal))) => {
+                    BinaryLiteral(map(literal)).into()
+                }
+                Token::Expression(Term(FloatLiteral(literal, parts))) => {
+                    FloatLiteral(map(literal), parts).into()
+                }
+                Token::Expression(Term(ImaginaryLiteral(literal, parts))) => {
+                    ImaginaryLiteral(map(literal), parts).into()
+                }
+                Token::Expression(Term(StringLiteral(literal))) => {
+                    StringLiteral(map(literal)).into()
+                }
+                Token::Expression(Term(StringFragment(literal))) => {
+                    StringFragment(map(literal)).into()
+                }
+                Token::Expression(Term(TextBlock(literal))) => TextBlock(map(literal)).into(),
+                Token::Expression(Term(ErrorTerm(error, literal))) => {
+                    ErrorTerm(error, map(literal)).into()
+                }
+                token => token,
+            };
+        }
+    }
+
+    let chars = &mut ast.char_data;
+    let whitespace = chars
+        .whitespace_ranges
+        .iter()
+        .map(|&(whitespace, _)| whitespace);
+    if let Some((interner, map)) = first_use_order(
+        &chars.whitespace_characters,
+        StringInterner::new(),
+        whitespace,
+    ) {
+        let map = |whitespace: WhitespaceIndex| map[whitespace.to_usize()].unwrap();
+        chars.whitespace_characters = interner;
+        for (whitespace, _) in chars.whitespace_ranges.iter_mut() {
+            *whitespace = map(*whitespace);
+        }
+    }
+}
+
+fn identifier(token: &Token, ast: &Ast) -> Option<IdentifierIndex> {
+    match *token {
+        Token::Expression(Term(RawIdentifier(name)) | PrefixOperator(name))
+        | Token::Operator(InfixOperator(name) | InfixAssignment(name) | PostfixOperator(name)) => {
+            Some(name)
+        }
+        Token::Expression(Term(FieldReference(field))) => Some(ast.fields[field].name),
+        _ => None,
+    }
+}
+
+fn literal(token: &Token) -> Option<LiteralIndex> {
+    match *token {
+        Token::Expression(Term(
+            IntegerLiteral(literal)
+            | HexadecimalLiteral(literal)
+            | OctalLiteral(literal)
+            | BinaryLiteral(literal)
+            | FloatLiteral(literal, _)
+            | ImaginaryLiteral(literal, _)
+            | StringLiteral(literal)
+            | StringFragment(literal)
+            | TextBlock(literal)
+            | ErrorTerm(_, literal),
+        )) => Some(literal),
+        _ => None,
+    }
+}
+
+///
+/// Re-interns `strings` in the order `used` first uses them, after the ones
+/// `base` starts with, along with what each old symbol became. Returns
+/// nothing if they are already in that order.
+///
+#[allow(clippy::type_complexity)]
+fn first_use_order<S: Symbol>(
+    strings: &StringInterner<StringBackend<S>>,
+    base: StringInterner<StringBackend<S>>,
+    used: impl Iterator<Item = S> + Clone,
+) -> Option<(StringInterner<StringBackend<S>>, Vec<Option<S>>)> {
+    let mut next = base.len();
+    let in_order = used.clone().all(|symbol| {
+        let symbol = symbol.to_usize();
+        if symbol == next {
+            next += 1;
+        }
+        symbol < next
+    });
+    if in_order && next == strings.len() {
+        return None;
+    }
+
+    let mut reordered = base;
+    let mut map = vec![None; strings.len()];
+    for symbol in used {
+        let string = strings.resolve(symbol).unwrap();
+        map[symbol.to_usize()].get_or_insert_with(|| reordered.get_or_intern(string));
+    }
+    Some((reordered, map))
+}
@@ -1,6 +1,8 @@
 use std::borrow::Cow;
 
 use berg_util::Delta;
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_xid::UnicodeXID;
 use ByteType::*;
 use CharType::*;
 
@@ -21,14 +23,21 @@ pub struct Scanner {
 pub enum CharType {
     Digit,
     Identifier,
+    /// A non-ASCII character that can continue an identifier, but not start
+    /// one (like a combining accent).
+    IdentifierContinue,
     Operator,
     OpenParen,
     CloseParen,
     OpenCurly,
     CloseCurly,
+    OpenSquare,
+    CloseSquare,
     Separator,
     Colon,
     Hash,
+    Quote,
+    Backslash,
     Newline,
     LineEnding,
     Space,
@@ -75,6 +84,38 @@ impl Scanner {
         CharType::peek(self.buffer(), self.index + delta.into())
     }
 
+    pub fn peek_byte_at<At: Into<Delta<ByteIndex>>>(&self, delta: At) -> Option<u8> {
+        self.buffer().get(self.index + delta.into()).copied()
+    }
+
+    ///
+    /// The next character, if it is valid UTF-8.
+    ///
+    pub fn peek_char(&self) -> Option<char> {
+        Self::char_at(self.buffer(), self.index)
+    }
+
+    ///
+    /// Reads characters that would be part of the same grapheme as the one
+    /// before them (like `U+20DD COMBINING ENCLOSING CIRCLE` after `e`).
+    ///
+    pub fn next_while_grapheme_extend(&mut self, mut previous: char) -> bool {
+        let mut found = false;
+        while let Some(next) = self.peek_char() {
+            let mut pair = [0; 8];
+            let len = previous.encode_utf8(&mut pair).len();
+            let len = len + next.encode_utf8(&mut pair[len..]).len();
+            let pair = std::str::from_utf8(&pair[..len]).unwrap();
+            if pair.graphemes(true).nth(1).is_some() {
+                break;
+            }
+            self.advance(next.len_utf8().into());
+            previous = next;
+            found = true;
+        }
+        found
+    }
+
     pub fn next_while(&mut self, if_type: CharType) -> bool {
         if self.next_if(if_type) {
             while self.next_if(if_type) {}
@@ -94,6 +135,29 @@ impl Scanner {
         }
     }
 
+    ///
+    /// Reads the rest of a `#[ ... ]#` comment after its `#[`, including any
+    /// comments nested in it. Returns false if the source ends before it closes.
+    ///
+    pub fn next_block_comment(&mut self) -> bool {
+        let mut depth = 1;
+        while depth > 0 {
+            match self.next() {
+                Hash if self.peek() == OpenSquare => {
+                    self.next();
+                    depth += 1;
+                }
+                CloseSquare if self.peek() == Hash => {
+                    self.next();
+                    depth -= 1;
+                }
+                Eof => return false,
+                _ => {}
+            }
+        }
+        true
+    }
+
     pub fn next_while_horizontal_whitespace(&mut self) -> bool {
         let mut found = false;
         loop {
@@ -108,6 +172,20 @@ impl Scanner {
         found
     }
 
+    pub fn next_while_unsupported(&mut self) -> bool {
+        let mut found = false;
+        loop {
+            let (char_type, char_length) = CharType::read(self.buffer(), self.index);
+            if char_type.is_unsupported() {
+                self.advance(char_length);
+                found = true;
+            } else {
+                break;
+            }
+        }
+        found
+    }
+
     pub fn next_while_identifier(&mut self) -> bool {
         let mut found = false;
         loop {
@@ -140,6 +218,16 @@ impl Scanner {
     pub(crate) fn at_end(&self) -> bool {
         self.index == self.buffer().len()
     }
+
+    fn char_at(buffer: &ByteSlice, index: ByteIndex) -> Option<char> {
+        match CharType::read(buffer, index) {
+            (InvalidUtf8 | Eof, _) => None,
+            (_, char_length) => std::str::from_utf8(&buffer[index..index + char_length])
+                .ok()?
+                .chars()
+                .next(),
+        }
+    }
 }
 
 impl CharType {
@@ -157,7 +245,7 @@ impl CharType {
                 }
                 ByteType::Utf8LeadingByte(char_length) => {
                     if Self::is_valid_utf8_char(buffer, index, char_length) {
-                        (Unsupported, char_length)
+                        (Self::from_char(buffer, index, char_length), char_length)
                     } else {
                         (InvalidUtf8, 1.into())
                     }
@@ -168,6 +256,19 @@ impl CharType {
         }
     }
 
+    ///
+    /// Identifies a valid multi-byte UTF-8 character. Identifiers follow
+    /// Unicode XID_Start and XID_Continue; everything else is unsupported.
+    ///
+    fn from_char(buffer: &ByteSlice, index: ByteIndex, char_length: Delta<ByteIndex>) -> CharType {
+        let utf8 = std::str::from_utf8(&buffer[index..index + char_length]);
+        match utf8.ok().and_then(|utf8| utf8.chars().next()) {
+            Some(ch) if ch.is_xid_start() => Identifier,
+            Some(ch) if ch.is_xid_continue() => IdentifierContinue,
+            _ => Unsupported,
+        }
+    }
+
     fn peek(buffer: &ByteSlice, index: ByteIndex) -> CharType {
         CharType::read(buffer, index).0
     }
@@ -196,13 +297,18 @@ impl CharType {
     }
 
     pub(crate) fn is_identifier_middle(self) -> bool {
-        matches!(self, Identifier | Digit)
+        matches!(self, Identifier | IdentifierContinue | Digit)
+    }
+
+    pub(crate) fn is_unsupported(self) -> bool {
+        matches!(self, Unsupported | IdentifierContinue | Backslash)
     }
 
     pub(crate) fn is_whitespace(self) -> bool {
         matches!(
             self,
-            Space | Newline | HorizontalWhitespace | Unsupported | InvalidUtf8 | Hash | Eof
+            Space | Newline | HorizontalWhitespace | Unsupported | IdentifierContinue | Backslash
+                | InvalidUtf8 | Hash | Eof
         )
     }
 
@@ -215,11 +321,11 @@ impl CharType {
     }
 
     pub(crate) fn is_close(self) -> bool {
-        matches!(self, CloseParen | CloseCurly)
+        matches!(self, CloseParen | CloseCurly | CloseSquare)
     }
 
     pub(crate) fn is_open(self) -> bool {
-        matches!(self, OpenParen | OpenCurly)
+        matches!(self, OpenParen | OpenCurly | OpenSquare)
     }
 
     pub(crate) fn is_separator(self) -> bool {
@@ -227,7 +333,7 @@ impl CharType {
     }
 
     pub(crate) fn is_always_operand(self) -> bool {
-        matches!(self, Digit | Identifier)
+        matches!(self, Digit | Identifier | Quote)
     }
 
     pub(crate) fn is_always_right_operand(self) -> bool {
@@ -255,9 +361,13 @@ impl ByteType {
             b'{' => Char(OpenCurly),
             b')' => Char(CloseParen),
             b'}' => Char(CloseCurly),
+            b'[' => Char(OpenSquare),
+            b']' => Char(CloseSquare),
             b';' | b',' => Char(Separator),
             b':' => Char(Colon),
             b'#' => Char(Hash),
+            b'"' => Char(Quote),
+            b'\\' => Char(Backslash),
             b' ' => Char(Space),
             b'\t' => Char(HorizontalWhitespace),
             b'\n' => Char(Newline),
@@ -277,6 +387,6 @@ impl ByteType {
     }
 
     fn is_utf8_cont(byte: u8) -> bool {
-        (0b1000_0000..0b1011_1111).contains(&byte)
+        (0b1000_0000..=0b1011_1111).contains(&byte)
     }
 }
@@ -1,15 +1,18 @@
 use crate::syntax::{
     ast::{Ast, LiteralIndex, WhitespaceIndex},
     bytes::{ByteIndex, ByteRange, ByteSlice},
-    identifiers::IdentifierIndex,
+    identifiers::{IdentifierIndex, IN},
+    precedence::OperatorTable,
     token::{
-        ErrorTermError, ExpressionBoundary, ExpressionToken, OperatorToken, RawErrorTermError,
-        TermToken,
+        ErrorTermError, ExpressionBoundary, ExpressionToken, FloatParts, OperatorToken,
+        RawErrorTermError, TermToken,
     },
 };
 use berg_util::Delta;
 use std::borrow::Cow;
 use std::cmp::min;
+use std::mem;
+use unicode_normalization::is_nfc;
 use CharType::*;
 use ErrorTermError::*;
 use ExpressionBoundary::*;
@@ -18,6 +21,7 @@ use OperatorToken::*;
 use TermToken::*;
 
 use super::{
+    reparse::Resync,
     scanner::{CharType, Scanner},
     tokenizer::Tokenizer,
 };
@@ -30,8 +34,12 @@ use super::{
 /// In general, the sequencer chunks *character runs*--sequences containing all
 /// of the same type of character. The most straightforward of these include:
 ///
-/// | Integer | `1234` | A run of digit characters. | Term |
-/// | Identifier | `ThisIsAnIdentifier` | A run of alphanumeric characters, or `_`. | Term |
+/// | Integer | `1234` `1_000` | A run of digit characters. Single `_` separators may go between digits in any number; they are left out of the literal. | Term |
+/// | Radix Integer | `0x1F` `0o17` `0b1010_1010` | `0x`, `0o` or `0b` followed by a run of digits in that radix. | Term |
+/// | Float | `12.34` `1e10` `1.2E-3` | Digits, followed by `.` and digits and/or an exponent. | Term |
+/// | Imaginary | `12i` `1.2e3i` | An integer or float followed by `i`. | Term |
+/// | Identifier | `ThisIsAnIdentifier` `café` | A run of alphanumeric characters, or `_`. Non-ASCII characters follow Unicode XID_Start and XID_Continue. | Term |
+/// | String | `"abc"` `"a\"b"` | Everything between two `"`, including newlines and `\` escapes. | Term |
 /// | Operator | `+` `-` `*` `++` `+=` `<=>` `--->` | A run of operator characters. | Prefix if unbalanced like `+1`, postfix if unbalanced like If it's unbalanced like `+1` or `2*`, it's a postfix/prefix operator. Otherwise it's infix.
 /// | Space | ` ` | A run of space characters. | Space |
 /// | Whitespace | ` ` `\t` | A run of space and other whitespace characters. | Space |
@@ -42,9 +50,12 @@ use super::{
 /// special operators and newlines, including:
 ///
 /// | Separator | `;` `,` | Infix |
-/// | Colon | `:` | Prefix if unbalanced like `:x`, otherwise infix.
+/// | In | `in` | Infix if it comes after an operand like `x in y`, otherwise an identifier. |
+/// | Colon | `:` | Prefix if unbalanced like `:x`, otherwise infix. `:<=>` declares a field named by the operator.
+/// | Text Block | `sql:` | A declared text block name and `:` at the end of a line. The indented block after it is kept as text, in the same term. |
 /// | Open | `{` `(` | Open |
 /// | Close | `}` `)` | Close |
+/// | Block Comment | `#[ a ]#` `#[ a #[ b ]# ]#` | A comment that can span lines and nest. It separates terms like space does, and lines with nothing but block comments are blank. |
 /// | Newline | `\r` `\n` `\r\n` | Newlines are treated separately from other space, so that they can be counted for line #'s and possibly used to separate statements.
 ///
 #[derive(Debug)]
@@ -57,6 +68,11 @@ pub struct Sequencer {
     current_indent: IndentLevel,
     /// Whitespace for current indent level.
     current_indent_whitespace: Option<WhitespaceIndex>,
+    /// The number of unclosed `{` inside each `#{` we are in, so we know which
+    /// `}` goes back to the string.
+    open_interpolations: Vec<usize>,
+    /// When reparsing, watches for the parse to get back in step with the old one.
+    resync: Option<Resync>,
 }
 
 ///
@@ -64,26 +80,66 @@ pub struct Sequencer {
 ///
 pub type IndentLevel = Delta<ByteIndex>;
 
+///
+/// The most characters a line can have. Anything after that is reported as
+/// [`RawErrorTermError::LineTooLong`].
+///
+pub const MAX_LINE_LENGTH: usize = 4096;
+
+///
+/// The most characters an identifier can have. Longer identifiers are reported
+/// as [`ErrorTermError::IdentifierTooLarge`].
+///
+pub const MAX_IDENTIFIER_LENGTH: usize = 1024;
+
 impl Sequencer {
-    pub fn new(buffer: Cow<'static, ByteSlice>) -> Self {
-        let tokenizer = Tokenizer::default();
-        let scanner = Scanner::new(buffer);
+    pub fn new(buffer: Cow<'static, ByteSlice>, operators: OperatorTable) -> Self {
+        let mut tokenizer = Tokenizer::default();
+        *tokenizer.ast_mut() = Ast::with_operators(operators);
+        Sequencer::resume(buffer, tokenizer, 0.into(), None)
+    }
+
+    ///
+    /// Picks up sequencing at `start`, which must be the start of an
+    /// unindented line, with a tokenizer that has seen everything before it.
+    ///
+    /// With `resync`, sequencing stops as soon as it is back in step with the
+    /// old parse, and the rest of the AST is taken from that.
+    ///
+    pub fn resume(
+        buffer: Cow<'static, ByteSlice>,
+        tokenizer: Tokenizer,
+        start: ByteIndex,
+        resync: Option<Resync>,
+    ) -> Self {
+        let mut scanner = Scanner::new(buffer);
+        scanner.index = start;
         Sequencer {
             tokenizer,
             scanner,
             current_indent: 0.into(),
             current_indent_whitespace: None,
+            open_interpolations: Vec::new(),
+            resync,
         }
     }
 
     pub fn parse(mut self) -> Ast {
         self.tokenizer.on_source_start(self.scanner.index);
         self.line_start();
+        self.parse_rest()
+    }
 
+    pub fn parse_rest(mut self) -> Ast {
         let mut start = self.scanner.index;
         loop {
-            let char_type = self.scanner.next();
-            match char_type {
+            if self.is_past_max_line_length(start) {
+                self.line_too_long(start);
+                start = self.scanner.index;
+                continue;
+            }
+
+            match self.scanner.next() {
                 Digit => self.integer(start),
                 Identifier => self.identifier(start),
                 Operator => self.operator(start),
@@ -91,19 +147,26 @@ impl Sequencer {
                 Colon => self.colon(start),
                 OpenParen => self.tokenizer.on_open(Parentheses, self.range(start)),
                 CloseParen => self.tokenizer.on_close(Parentheses, self.range(start)),
-                OpenCurly => self.tokenizer.on_open(CurlyBraces, self.range(start)),
-                CloseCurly => self.tokenizer.on_close(CurlyBraces, self.range(start)),
+                OpenCurly => self.open_curly(start),
+                CloseCurly => self.close_curly(start),
+                OpenSquare => self.tokenizer.on_open(SquareBrackets, self.range(start)),
+                CloseSquare => self.tokenizer.on_close(SquareBrackets, self.range(start)),
                 Hash => self.comment(start),
+                Quote => self.string(start),
                 Newline => self.newline(start),
                 LineEnding => self.line_ending(start),
                 Space => self.space(start),
                 HorizontalWhitespace => self.horizontal_whitespace(start),
-                Unsupported => self.unsupported(start),
+                Unsupported | IdentifierContinue | Backslash => self.unsupported(start),
                 InvalidUtf8 => self.invalid_utf8(start),
                 Eof => break,
             };
 
             start = self.scanner.index;
+            if let Some(resync) = self.resync.take_if(|resync| resync.is_found()) {
+                let size = self.scanner.buffer().len().into();
+                return resync.splice(mem::take(self.tokenizer.ast_mut()), size);
+            }
         }
 
         assert!(start == self.scanner.index);
@@ -134,6 +197,18 @@ impl Sequencer {
     unsafe fn intern_utf8_literal(&mut self, start: ByteIndex) -> LiteralIndex {
         unsafe { self.with_utf8(start, |utf8, ast| ast.intern_literal(utf8)) }
     }
+    // Numbers are interned without their `_` separators (1_000 is "1000").
+    unsafe fn intern_number_literal(&mut self, start: ByteIndex) -> LiteralIndex {
+        unsafe {
+            self.with_utf8(start, |utf8, ast| {
+                if utf8.contains('_') {
+                    ast.intern_literal(utf8.replace('_', ""))
+                } else {
+                    ast.intern_literal(utf8)
+                }
+            })
+        }
+    }
 
     pub fn ast(&self) -> &Ast {
         self.tokenizer.ast()
@@ -152,22 +227,300 @@ impl Sequencer {
     }
 
     fn integer(&mut self, start: ByteIndex) {
-        self.scanner.next_while(Digit);
+        if self.scanner.buffer()[start] == b'0' && self.radix_integer(start) {
+            return;
+        }
+
+        if let Err(error) = self.digits(|byte| byte.is_ascii_digit()) {
+            return self.digit_separator_error(error, start);
+        }
+        let mut is_float = false;
+
+        // 1.2 (but not 1.a or 1.)
+        if self.scanner.peek_byte_at(0) == Some(b'.') && self.scanner.peek_at(1) == Digit {
+            self.scanner.next();
+            if let Err(error) = self.digits(|byte| byte.is_ascii_digit()) {
+                return self.digit_separator_error(error, start);
+            }
+            is_float = true;
+        }
+
+        // 1e10, 1.2E-10, 1e+10
+        if let Some(b'e') | Some(b'E') = self.scanner.peek_byte_at(0) {
+            let sign: usize = match self.scanner.peek_byte_at(1) {
+                Some(b'+') | Some(b'-') => 1,
+                _ => 0,
+            };
+            match (sign, self.scanner.peek_at(1 + sign)) {
+                (_, Digit) => {
+                    for _ in 0..=sign {
+                        self.scanner.next();
+                    }
+                    if let Err(error) = self.digits(|byte| byte.is_ascii_digit()) {
+                        return self.digit_separator_error(error, start);
+                    }
+                    is_float = true;
+                }
+                // 1e_10
+                _ if self.scanner.peek_byte_at(1 + sign) == Some(b'_') => {
+                    for _ in 0..=sign {
+                        self.scanner.next();
+                    }
+                    return self.digit_separator_error(LeadingDigitSeparator, start);
+                }
+                // 1eabc is an identifier starting with a number, handled below.
+                (0, char_type) if char_type.is_identifier_middle() => {}
+                // 1e, 1e+, 1e-a, 1e 1 (the error is at 1e; the sign is an operator)
+                _ => {
+                    self.scanner.next();
+                    return self.utf8_syntax_error(MissingExponent, start);
+                }
+            }
+        }
+
+        // 12i, 1.2e3i (but not 12in)
+        let mut is_imaginary = false;
+        if let Some(suffix @ (b'i' | b'I')) = self.scanner.peek_byte_at(0)
+            && !self.scanner.peek_at(1).is_identifier_middle()
+        {
+            self.scanner.next();
+            if suffix == b'I' {
+                return self.utf8_syntax_error(ImaginaryWithUppercaseI, start);
+            }
+            is_imaginary = true;
+        }
+
         if self.scanner.next_while_identifier() {
             return self.utf8_syntax_error(IdentifierStartsWithNumber, start);
         }
-        let literal = unsafe { self.intern_utf8_literal(start) };
-        self.tokenizer
-            .on_expression_token(IntegerLiteral(literal), self.range(start))
+        // A number that runs past the end of the line's maximum length
+        // (which also keeps FloatParts small).
+        let line_start = *self.ast().char_data.line_starts.last().unwrap();
+        if self.char_count(line_start) > MAX_LINE_LENGTH {
+            return self.line_too_long(start);
+        }
+        let literal = unsafe { self.intern_number_literal(start) };
+        let token = if is_imaginary || is_float {
+            let parts = FloatParts::new(self.ast().literal_string(literal));
+            if is_imaginary {
+                ImaginaryLiteral(literal, parts)
+            } else {
+                FloatLiteral(literal, parts)
+            }
+        } else {
+            IntegerLiteral(literal)
+        };
+        self.number_token(token, start)
+    }
+
+    // Digit separators are left out of number literals, so CharData keeps them.
+    fn number_token(&mut self, token: TermToken, start: ByteIndex) {
+        self.with_bytes(start, |bytes, ast| {
+            ast.char_data.append_digit_separators(bytes, start)
+        });
+        self.tokenizer.on_expression_token(token, self.range(start))
+    }
+
+    // 0x1F, 0o17, 0b101
+    // Returns false if this isn't a radix literal after all (0, 0x, 0xg).
+    fn radix_integer(&mut self, start: ByteIndex) -> bool {
+        // 0_x1F
+        if self.scanner.peek_byte_at(0) == Some(b'_')
+            && let Some(b'x' | b'X' | b'o' | b'O' | b'b' | b'B') = self.scanner.peek_byte_at(1)
+        {
+            self.digit_separator_error(DigitSeparatorNextToRadixPrefix, start);
+            return true;
+        }
+
+        let prefix = match self.scanner.peek_byte_at(0) {
+            Some(prefix @ (b'x' | b'X' | b'o' | b'O' | b'b' | b'B')) => prefix,
+            _ => return false,
+        };
+        let radix = match prefix.to_ascii_lowercase() {
+            b'x' => 16,
+            b'o' => 8,
+            _ => 2,
+        };
+        let is_radix_digit = |byte: u8| (byte as char).is_digit(radix);
+
+        match self.scanner.peek_byte_at(1) {
+            Some(byte) if byte.is_ascii_digit() || is_radix_digit(byte) => {}
+            // 0x_1F
+            Some(b'_') => {
+                self.scanner.next();
+                self.digit_separator_error(DigitSeparatorNextToRadixPrefix, start);
+                return true;
+            }
+            // 0xg is an identifier starting with a number.
+            _ if self.scanner.peek_at(1).is_identifier_middle() => return false,
+            // 0x by itself
+            _ => {
+                self.scanner.next();
+                self.utf8_syntax_error(MissingRadixDigits, start);
+                return true;
+            }
+        }
+        self.scanner.next();
+
+        // Read the digits, including any that are too big for the radix (like 0b2).
+        let digits_start = self.scanner.index;
+        if let Err(error) = self.digits(|byte| byte.is_ascii_digit() || is_radix_digit(byte)) {
+            self.digit_separator_error(error, start);
+            return true;
+        }
+        let digit_out_of_range = self.scanner.buffer()[self.range(digits_start)]
+            .iter()
+            .any(|&byte| byte.is_ascii_digit() && !is_radix_digit(byte));
+
+        if self.scanner.next_while_identifier() {
+            self.utf8_syntax_error(IdentifierStartsWithNumber, start);
+        } else if prefix.is_ascii_uppercase() {
+            let error = match radix {
+                16 => UppercaseHexadecimalPrefix,
+                8 => UppercaseOctalPrefix,
+                _ => UppercaseBinaryPrefix,
+            };
+            self.utf8_syntax_error(error, start);
+        } else if digit_out_of_range {
+            self.utf8_syntax_error(DigitOutOfRange, start);
+        } else {
+            let literal = unsafe { self.intern_number_literal(start) };
+            let token = match radix {
+                16 => HexadecimalLiteral(literal),
+                8 => OctalLiteral(literal),
+                _ => BinaryLiteral(literal),
+            };
+            self.number_token(token, start);
+        }
+        true
+    }
+
+    //
+    // Reads a run of digits, which may have single `_` separators between them
+    // (1_000_000). A separator that isn't followed by a digit is an error.
+    //
+    fn digits(&mut self, is_digit: impl Fn(u8) -> bool) -> Result<(), ErrorTermError> {
+        loop {
+            match self.scanner.peek_byte_at(0) {
+                Some(byte) if is_digit(byte) => {}
+                Some(b'_') => match self.scanner.peek_byte_at(1) {
+                    Some(b'_') => return Err(DoubledDigitSeparator),
+                    Some(byte) if is_digit(byte) => {}
+                    _ => return Err(TrailingDigitSeparator),
+                },
+                _ => return Ok(()),
+            }
+            self.scanner.next();
+        }
+    }
+
+    // A number with a misplaced `_`. The rest of the term is part of the error.
+    fn digit_separator_error(&mut self, error: ErrorTermError, start: ByteIndex) {
+        self.scanner.next_while_identifier();
+        self.utf8_syntax_error(error, start)
     }
 
     fn identifier(&mut self, start: ByteIndex) {
         self.scanner.next_while_identifier();
+
+        // Identifiers must be whole graphemes in NFC form, so that two
+        // identifiers that look the same are always the same identifier.
+        if self
+            .scanner
+            .peek_byte_at(0)
+            .is_some_and(|byte| !byte.is_ascii())
+        {
+            let last = unsafe { self.with_utf8(start, |utf8, _| utf8.chars().next_back()) };
+            if self.scanner.next_while_grapheme_extend(last.unwrap()) {
+                return self.utf8_syntax_error(IncompleteGrapheme, start);
+            }
+        }
+        if !unsafe { self.with_utf8(start, |utf8, _| utf8.is_ascii() || is_nfc(utf8)) } {
+            return self.utf8_syntax_error(DenormalizedIdentifier, start);
+        }
+        if self.char_count(start) > MAX_IDENTIFIER_LENGTH {
+            return self.utf8_syntax_error(IdentifierTooLarge, start);
+        }
+
         let identifier = unsafe { self.intern_utf8_identifier(start) };
+
+        // sql:
+        //     SELECT * FROM table
+        if self.ast().operators.is_text_block(identifier)
+            && !self.tokenizer.in_term()
+            && self.text_block(start)
+        {
+            return;
+        }
+
+        // `in` after an operand is an operator: `x in 1..10`. Anywhere else,
+        // it's just an identifier.
+        if identifier == IN && !self.tokenizer.prev_was_operator {
+            return self
+                .tokenizer
+                .on_separator(InfixOperator(IN), self.range(start));
+        }
+
         self.tokenizer
             .on_expression_token(RawIdentifier(identifier), self.range(start))
     }
 
+    //
+    // Reads a text block, if the declared name we just read is followed by `:`
+    // at the end of the line and an indented block. The block is kept as text,
+    // up to its last non-blank line, so the DSL it is for can handle it.
+    //
+    // Returns false (and reads nothing) if it isn't a text block after all.
+    //
+    fn text_block(&mut self, start: ByteIndex) -> bool {
+        let name_end = self.scanner.index;
+        let end = self.text_block_end();
+        self.scanner.index = name_end;
+        let end = match end {
+            Some(end) if std::str::from_utf8(&self.scanner.buffer()[start..end]).is_ok() => end,
+            _ => return false,
+        };
+
+        // Newlines inside the block still count as lines, like in strings.
+        while self.scanner.index < end {
+            if self.scanner.next().ends_line() {
+                self.string_line_start();
+            }
+        }
+        let literal = unsafe { self.intern_utf8_literal(start) };
+        self.tokenizer
+            .on_expression_token(TextBlock(literal), self.range(start));
+        true
+    }
+
+    // Where the text block starting with the `:` we are at ends, if it is one.
+    fn text_block_end(&mut self) -> Option<ByteIndex> {
+        if self.scanner.peek() != Colon {
+            return None;
+        }
+        self.scanner.next();
+        self.scanner.next_while_horizontal_whitespace();
+        if !matches!(self.scanner.peek(), Newline | LineEnding) {
+            return None;
+        }
+
+        // Take every line indented past this one, and blank lines between them.
+        let mut end = None;
+        while self.scanner.next() != Eof {
+            let line_start = self.scanner.index;
+            self.scanner.next_while_horizontal_whitespace();
+            if self.scanner.peek().ends_line() {
+                continue;
+            }
+            if self.scanner.index - line_start <= self.current_indent {
+                break;
+            }
+            self.scanner.next_until_eol();
+            end = Some(self.scanner.index);
+        }
+        end
+    }
+
     fn operator(&mut self, start: ByteIndex) {
         self.scanner.next_while(CharType::Operator);
 
@@ -225,22 +578,47 @@ impl Sequencer {
     // Else, we are separator. ("a:b", a:-b", "a: b", "a:")
     // See where the "operator" function calculates whether the term is about to end for the other
     // relevant silliness to ensure "a+:b" means "(a) + (:b)".
+    //
+    // `:<=> = ...` declares a field named after an operator (so objects can
+    // define `<=>`). That only happens when the operator stands alone; `a:-b`
+    // is still a separator.
     fn colon(&mut self, start: ByteIndex) {
         let operator = unsafe { self.intern_utf8_identifier(start) };
-        if (!self.tokenizer.in_term() || self.tokenizer.prev_was_operator)
-            && self.scanner.peek().is_always_right_operand()
-        {
+        let is_prefix = !self.tokenizer.in_term() || self.tokenizer.prev_was_operator;
+        if is_prefix && self.scanner.peek().is_always_right_operand() {
+            self.tokenizer
+                .on_expression_token(PrefixOperator(operator), self.range(start));
+        } else if is_prefix && self.is_operator_name_next() {
             self.tokenizer
                 .on_expression_token(PrefixOperator(operator), self.range(start));
+            let name_start = self.scanner.index;
+            self.scanner.next_while(CharType::Operator);
+            let name = unsafe { self.intern_utf8_identifier(name_start) };
+            self.tokenizer
+                .on_expression_token(RawIdentifier(name), self.range(name_start));
         } else {
             self.tokenizer
                 .on_separator(InfixOperator(operator), self.range(start));
         }
     }
 
+    // Whether the next thing is an operator all by itself, like the `<=>` in
+    // `:<=> = ...`.
+    fn is_operator_name_next(&self) -> bool {
+        let mut length = 0;
+        while self.scanner.peek_at(length) == CharType::Operator {
+            length += 1;
+        }
+        if length == 0 {
+            return false;
+        }
+        let after = self.scanner.peek_at(length);
+        after.is_whitespace() || after.is_close() || after.is_separator()
+    }
+
     // Anything ending with exactly one = is assignment, EXCEPT
     // >=, != and <=.
-    fn is_assignment_operator(slice: &[u8]) -> bool {
+    pub(super) fn is_assignment_operator(slice: &[u8]) -> bool {
         if slice[slice.len() - 1] != b'=' {
             return false;
         }
@@ -270,72 +648,62 @@ impl Sequencer {
 
     fn line_start(&mut self) {
         let start = self.scanner.index;
-        self.tokenizer.ast_mut().char_data.line_starts.push(start);
+        // CharData already starts with the first line.
+        if start > 0 {
+            self.tokenizer.ast_mut().char_data.line_starts.push(start);
+        }
 
         // Get the indent level.
-        let indent_whitespace = self.read_space(start);
-
-        // Send "indent" unless we're a blank line.
-        if !self.scanner.peek().ends_line() {
-            let indent = self.scanner.index - start;
-            self.tokenizer.on_line_start(
-                start,
-                indent,
-                self.matching_indent(indent, indent_whitespace),
-            );
-            self.current_indent = indent;
-            self.current_indent_whitespace = indent_whitespace;
-        }
-    }
-
-    // Get the matching indent level--the number of characters shared by indent and indent_whitespace.
-    fn matching_indent(
-        &self,
-        indent: IndentLevel,
-        indent_whitespace: Option<WhitespaceIndex>,
-    ) -> IndentLevel {
-        match (indent_whitespace, self.current_indent_whitespace) {
-            // The old indent and new indent are entirely space characters.
-            (None, None) => indent,
-            // The old indent and new indent both have non-space characters.
-            (Some(indent_whitespace), Some(current_whitespace)) => {
-                let indent_whitespace = self.ast().whitespace_string(indent_whitespace).as_bytes();
-                let current_whitespace =
-                    self.ast().whitespace_string(current_whitespace).as_bytes();
-                let current_whitespace =
-                    &current_whitespace[0..min(indent_whitespace.len(), current_whitespace.len())];
-                indent_whitespace
-                    .iter()
-                    .zip(current_whitespace.iter())
-                    .position(|(a, b)| a != b)
-                    .unwrap_or_else(|| indent.into())
-                    .into()
-            }
-            // The old indent is all spaces, and the new indent has other space characters in it.
-            // As long as the
-            (Some(indent_whitespace), None) => {
-                let indent_whitespace = self.ast().whitespace_string(indent_whitespace).as_bytes();
-                let indent_whitespace =
-                    &indent_whitespace[0..min(indent.into(), indent_whitespace.len())];
-                indent_whitespace
-                    .iter()
-                    .position(|b| *b != b' ')
-                    .unwrap_or_else(|| indent.into())
-                    .into()
-            }
-            // The new indent is all spaces, and the old indent has other space characters in it.
-            (None, Some(current_whitespace)) => {
-                let current_whitespace =
-                    self.ast().whitespace_string(current_whitespace).as_bytes();
-                let current_whitespace =
-                    &current_whitespace[0..min(indent.into(), current_whitespace.len())];
-                current_whitespace
-                    .iter()
-                    .position(|b| *b != b' ')
-                    .unwrap_or_else(|| indent.into())
-                    .into()
+        self.scanner.next_while(Space);
+        let has_whitespace = self.scanner.next_while_horizontal_whitespace();
+
+        // Blank lines (even with block comments on them) don't affect indent.
+        if self.rest_of_line_is_blank() {
+            if has_whitespace {
+                self.store_whitespace_in_char_data(start);
             }
+            return;
+        }
+
+        // If this indent and the last one differ before either one ends (like a
+        // tab where the last line had a space), we can't tell which is deeper.
+        // Leave the block structure alone and report the indent.
+        let indent = self.scanner.index - start;
+        if self.matching_indent(start) < min(indent, self.current_indent) {
+            self.tokenizer.on_line_start(start, self.current_indent);
+            return self.utf8_syntax_error(InconsistentIndentCharacters, start);
+        }
+
+        let indent_whitespace = has_whitespace.then(|| self.store_whitespace_in_char_data(start));
+        self.tokenizer.on_line_start(start, indent);
+
+        // Comment lines can close blocks, but not open them, so the next line
+        // is still compared with the last line of code.
+        if self.scanner.peek() == Hash && self.scanner.peek_at(1) != OpenSquare {
+            return;
         }
+        self.current_indent = indent;
+        self.current_indent_whitespace = indent_whitespace;
+
+        if indent == 0
+            && let Some(resync) = &mut self.resync
+        {
+            resync.on_line_start(start, &mut self.tokenizer, &self.open_interpolations);
+        }
+    }
+
+    // Get the matching indent level--the number of characters shared by this line's indent
+    // (from start to the current index) and the current indent.
+    fn matching_indent(&self, start: ByteIndex) -> IndentLevel {
+        let indent = &self.scanner.buffer()[self.range(start)];
+        let current_whitespace = self
+            .current_indent_whitespace
+            .map(|whitespace| self.ast().whitespace_string(whitespace).as_bytes());
+        let current_byte = |index: usize| current_whitespace.map_or(b' ', |current| current[index]);
+        (0..min(indent.len(), self.current_indent.into()))
+            .find(|&index| indent[index] != current_byte(index))
+            .unwrap_or(indent.len())
+            .into()
     }
 
     fn read_space(&mut self, start: ByteIndex) -> Option<WhitespaceIndex> {
@@ -360,6 +728,9 @@ impl Sequencer {
 
     // # <comment>
     fn comment(&mut self, start: ByteIndex) {
+        if self.scanner.peek() == OpenSquare {
+            return self.block_comment(start);
+        }
         self.scanner.next_until_eol();
         self.with_bytes(start, |bytes, ast| {
             ast.char_data.append_comment(bytes, start)
@@ -367,8 +738,209 @@ impl Sequencer {
         self.tokenizer.on_comment(self.range(start));
     }
 
+    //
+    // #[ <comment> ]#
+    //
+    // Block comments nest, so #[ a #[ b ]# c ]# is a single comment. Newlines
+    // inside them still count as lines, like in strings.
+    //
+    fn block_comment(&mut self, start: ByteIndex) {
+        self.scanner.next();
+        let is_closed = self.scanner.next_block_comment();
+        let end = self.scanner.index;
+        self.scanner.index = start + 2;
+
+        // If it never closes, the #[ is the error, and the rest is still a comment.
+        let mut comment_start = start;
+        if !is_closed {
+            self.utf8_syntax_error(UnclosedBlockComment, start);
+            comment_start = self.scanner.index;
+        }
+        while self.scanner.index < end {
+            if self.scanner.next().ends_line() {
+                self.string_line_start();
+            }
+        }
+        if end > comment_start {
+            self.with_bytes(comment_start, |bytes, ast| {
+                ast.char_data.append_comment(bytes, comment_start)
+            });
+            self.tokenizer.on_comment(self.range(comment_start));
+        }
+    }
+
+    //
+    // Whether the rest of the line has nothing but space and block comments.
+    // Reads nothing.
+    //
+    fn rest_of_line_is_blank(&mut self) -> bool {
+        let index = self.scanner.index;
+        let mut is_blank = true;
+        while !self.scanner.peek().ends_line() {
+            if self.scanner.peek() != Hash || self.scanner.peek_at(1) != OpenSquare {
+                is_blank = false;
+                break;
+            }
+            self.scanner.next();
+            self.scanner.next();
+            if !self.scanner.next_block_comment() {
+                is_blank = false;
+                break;
+            }
+            self.scanner.next_while_horizontal_whitespace();
+        }
+        self.scanner.index = index;
+        is_blank
+    }
+
+    fn open_curly(&mut self, start: ByteIndex) {
+        if let Some(open_curlies) = self.open_interpolations.last_mut() {
+            *open_curlies += 1;
+        }
+        self.tokenizer.on_open(CurlyBraces, self.range(start))
+    }
+
+    fn close_curly(&mut self, start: ByteIndex) {
+        match self.open_interpolations.last_mut() {
+            // "a#{b}c": the } goes back to the string
+            Some(0) => {
+                self.open_interpolations.pop();
+                self.tokenizer.on_interpolation_close(self.range(start));
+                self.string_text(self.scanner.index, true)
+            }
+            Some(open_curlies) => {
+                *open_curlies -= 1;
+                self.tokenizer.on_close(CurlyBraces, self.range(start))
+            }
+            None => self.tokenizer.on_close(CurlyBraces, self.range(start)),
+        }
+    }
+
+    // "<string>"
+    fn string(&mut self, start: ByteIndex) {
+        self.string_text(start, false)
+    }
+
+    //
+    // Reads string text up to the closing " or the next #{.
+    //
+    // If the string has a #{...} in it, it is sent as an interpolated string:
+    // the quotes, text fragments and #{...} expressions are all separate tokens.
+    // `is_interpolated` means we are picking up after the } of a #{...}.
+    //
+    fn string_text(&mut self, start: ByteIndex, is_interpolated: bool) {
+        let mut is_valid_utf8 = true;
+        loop {
+            match self.scanner.next() {
+                Quote => break,
+                Backslash => match self.scanner.next() {
+                    Newline | LineEnding => self.string_line_start(),
+                    InvalidUtf8 => is_valid_utf8 = false,
+                    Eof => return self.unclosed_string(start, is_interpolated, is_valid_utf8),
+                    _ => {}
+                },
+                // "a#{b}c"
+                Hash if self.scanner.peek() == OpenCurly => {
+                    let interpolation_start = self.scanner.index - 1;
+                    let mut text_start = start;
+                    if !is_interpolated {
+                        text_start = start + 1;
+                        self.tokenizer.on_interpolated_string_open(start..start + 1);
+                    }
+                    self.string_fragment(text_start..interpolation_start, is_valid_utf8);
+                    self.scanner.next();
+                    self.tokenizer
+                        .on_interpolation_open(self.range(interpolation_start));
+                    self.open_interpolations.push(0);
+                    return;
+                }
+                Newline | LineEnding => self.string_line_start(),
+                InvalidUtf8 => is_valid_utf8 = false,
+                Eof => return self.unclosed_string(start, is_interpolated, is_valid_utf8),
+                _ => {}
+            }
+        }
+
+        if is_interpolated {
+            let end = self.scanner.index - 1;
+            self.string_fragment(start..end, is_valid_utf8);
+            return self.tokenizer.on_interpolated_string_close(self.range(end));
+        }
+        if !is_valid_utf8 {
+            return self.raw_syntax_error(RawErrorTermError::InvalidUtf8InString, start);
+        }
+        let literal = unsafe { self.intern_utf8_literal(start) };
+        self.tokenizer
+            .on_expression_token(StringLiteral(literal), self.range(start))
+    }
+
+    // The "abc" in "abc#{...}". Empty fragments are left out entirely.
+    fn string_fragment(&mut self, range: ByteRange, is_valid_utf8: bool) {
+        if range.start == range.end {
+            return;
+        }
+        let bytes = &self.scanner.buffer()[range.clone()];
+        let ast = self.tokenizer.ast_mut();
+        let token = if is_valid_utf8 {
+            let literal = ast.intern_literal(unsafe { std::str::from_utf8_unchecked(bytes) });
+            StringFragment(literal)
+        } else {
+            let raw_literal = ast.raw_literals.push(bytes.into());
+            RawErrorTerm(RawErrorTermError::InvalidUtf8InString, raw_literal)
+        };
+        self.tokenizer.on_string_fragment(token, range)
+    }
+
+    fn unclosed_string(&mut self, start: ByteIndex, is_interpolated: bool, is_valid_utf8: bool) {
+        // The interpolated string's open " will be reported as unclosed.
+        if is_interpolated {
+            self.string_fragment(self.range(start), is_valid_utf8)
+        } else if is_valid_utf8 {
+            self.utf8_syntax_error(UnclosedString, start)
+        } else {
+            self.raw_syntax_error(RawErrorTermError::InvalidUtf8InString, start)
+        }
+    }
+
+    // Newlines inside strings and block comments still count as lines, but
+    // don't affect indent.
+    fn string_line_start(&mut self) {
+        let start = self.scanner.index;
+        self.tokenizer.ast_mut().char_data.line_starts.push(start);
+    }
+
+    ///
+    /// Whether the next token would start past [`MAX_LINE_LENGTH`] characters
+    /// into the current line.
+    ///
+    fn is_past_max_line_length(&self, start: ByteIndex) -> bool {
+        let line_start = *self.ast().char_data.line_starts.last().unwrap();
+        // Check the byte length first, since there can't be more characters than bytes.
+        usize::from(start - line_start) >= MAX_LINE_LENGTH
+            && !self.scanner.peek().ends_line()
+            && self.char_count(line_start) >= MAX_LINE_LENGTH
+    }
+
+    // Everything past the maximum line length, up to the end of the line.
+    fn line_too_long(&mut self, start: ByteIndex) {
+        self.scanner.next_until_eol();
+        self.raw_syntax_error(RawErrorTermError::LineTooLong, start)
+    }
+
+    ///
+    /// The number of characters from `start` to the current index.
+    ///
+    /// Invalid UTF-8 bytes count as one character each.
+    ///
+    fn char_count(&self, start: ByteIndex) -> usize {
+        self.scanner.buffer()[self.range(start)]
+            .iter()
+            .filter(|byte| (**byte & 0b1100_0000) != 0b1000_0000)
+            .count()
+    }
+
     fn unsupported(&mut self, start: ByteIndex) {
-        self.scanner.next_while(Unsupported);
+        self.scanner.next_while_unsupported();
         self.utf8_syntax_error(ErrorTermError::UnsupportedCharacters, start)
     }
 
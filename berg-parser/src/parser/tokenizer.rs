@@ -1,7 +1,8 @@
 use crate::syntax::{
-    ast::Ast,
+    ast::{Ast, AstIndex},
+    block::FieldIndex,
     bytes::{ByteIndex, ByteRange},
-    identifiers::{APPLY, FOLLOWED_BY, NEWLINE_SEQUENCE},
+    identifiers::{APPLY, FOLLOWED_BY, INDEX, INTERPOLATE, NEWLINE_SEQUENCE},
     token::{ExpressionBoundary, ExpressionToken, OperatorToken, TermToken},
 };
 use OperatorToken::*;
@@ -133,6 +134,30 @@ impl Default for Tokenizer {
 }
 
 impl Tokenizer {
+    ///
+    /// Picks up tokenizing at the start of an unindented line, right after the
+    /// newline at `newline_index` separated it from the statement before.
+    ///
+    pub fn resume(ast: Ast, top_level_fields: &[FieldIndex], newline_index: AstIndex) -> Self {
+        Tokenizer {
+            grouper: Grouper::resume(ast, top_level_fields, newline_index),
+            prev_was_operator: true,
+            whitespace_state: NextLine,
+            indented_blocks: vec![(0.into(), ExpressionBoundary::Source)],
+        }
+    }
+
+    ///
+    /// The fields declared in the source's scope, if nothing but the source is
+    /// open.
+    ///
+    pub fn top_level_fields(&self) -> Option<&[FieldIndex]> {
+        match self.indented_blocks.len() {
+            1 => self.grouper.top_level_fields(),
+            _ => None,
+        }
+    }
+
     pub fn ast(&self) -> &Ast {
         self.grouper.ast()
     }
@@ -200,6 +225,15 @@ impl Tokenizer {
         self.emit_expression_token(token, range);
     }
 
+    ///
+    /// Separates the statement on the line starting at `start` from the one
+    /// before, as the first expression token on the line would.
+    ///
+    pub fn on_statement_start(&mut self, start: ByteIndex) {
+        assert!(!self.prev_was_operator && self.whitespace_state == NextLine);
+        self.emit_operator_token(InfixOperator(NEWLINE_SEQUENCE), start..start);
+    }
+
     pub fn on_operator_token(&mut self, token: OperatorToken, range: ByteRange) {
         assert!(range.start < range.end);
         self.emit_operator_token(token, range);
@@ -216,8 +250,7 @@ impl Tokenizer {
     /// Handle space, which closes any compound terms.
     ///
     pub fn on_comment(&mut self, range: ByteRange) {
-        // Comment after a term closes it.
-        // We don't have delimited comments, so a+/*comment*/b is impossible.
+        // Comment after a term closes it, so a#[comment]#b is two terms.
         self.close_term(range.start);
     }
 
@@ -227,29 +260,26 @@ impl Tokenizer {
     /// This closes any open blocks with lower indent, and then prepares us to possibly create a
     /// new block (if the next token is an expression rather than an operator or comment).
     ///
-    pub fn on_line_start(
-        &mut self,
-        start: ByteIndex,
-        indent: IndentLevel,
-        matching_indent: IndentLevel,
-    ) {
+    pub fn on_line_start(&mut self, start: ByteIndex, indent: IndentLevel) {
         // Close any open indented blocks (at least one block will be equal to 0, and that will never be closed).
+        // Closing more than two statement blocks at a time is an error, so every one after the
+        // second we close is marked. Continuations can be indented anywhere, so their blocks don't
+        // count.
         let mut top = self.indented_blocks.last().unwrap();
+        let mut undents = 0;
         while indent < top.0 {
             let close_token = top.1.placeholder_close_token();
+            let is_statement_block = top.1 == ExpressionBoundary::IndentedBlock;
             self.emit_operator_token(close_token, start..start);
+            if is_statement_block {
+                undents += 1;
+                if undents > 2 {
+                    self.grouper.on_multiple_undent();
+                }
+            }
             self.indented_blocks.pop();
             top = self.indented_blocks.last().unwrap();
         }
-        // Mark indented blocks with errors on mismatched indent (spaces vs tabs, etc.).
-        if matching_indent < top.0 {
-            let mismatch_level = self
-                .indented_blocks
-                .iter()
-                .position(|(block_indent, _)| matching_indent < *block_indent)
-                .unwrap();
-            self.grouper.on_indent_mismatch(mismatch_level);
-        }
         // Remember this indent; we'll start a new block if we ever get an expression token.
         if indent == top.0 {
             self.whitespace_state = NextLine;
@@ -259,17 +289,24 @@ impl Tokenizer {
         }
     }
 
-    // ( or {.
+    // (, [ or {.
     pub fn on_open(&mut self, boundary: ExpressionBoundary, range: ByteRange) {
         // `f(x,y)` is f APPLY x, y (two arguments), to distinguish it from `f (x,y)`,
         // which is f with a single argument, the tuple `(x,y)`.
         //
-        // For comparison, this will also be used for the distinction between
-        // `f[1]` and `f [1]` (one is an indexer, the other means "call f with
-        // the first arg being a single-element array `[1]`").
-        if !self.prev_was_operator && self.in_term() && boundary == ExpressionBoundary::Parentheses
-        {
-            self.emit_operator_token(InfixOperator(APPLY), range.start..range.start);
+        // In the same way, `f[1]` is f INDEX 1, while `f [1]` means "call f with
+        // the first arg being a single-element array `[1]`".
+        if !self.prev_was_operator && self.in_term() {
+            use ExpressionBoundary::*;
+            match boundary {
+                Parentheses => {
+                    self.emit_operator_token(InfixOperator(APPLY), range.start..range.start)
+                }
+                SquareBrackets => {
+                    self.emit_operator_token(InfixOperator(INDEX), range.start..range.start)
+                }
+                _ => {}
+            }
         }
 
         // Otherwise, just put the open token like normal.
@@ -286,6 +323,42 @@ impl Tokenizer {
         self.whitespace_state = InTerm;
     }
 
+    // The " at the start of a string with #{...} in it. The string is a term
+    // by itself, just like (...).
+    pub fn on_interpolated_string_open(&mut self, range: ByteRange) {
+        self.on_expression_token(
+            ExpressionBoundary::InterpolatedString.placeholder_open_token(None),
+            range,
+        );
+        self.whitespace_state = NotInTerm;
+    }
+
+    // The " at the end of a string with #{...} in it.
+    pub fn on_interpolated_string_close(&mut self, range: ByteRange) {
+        self.on_close(ExpressionBoundary::InterpolatedString, range);
+    }
+
+    // The text between the quotes and the #{...}'s of an interpolated string.
+    pub fn on_string_fragment(&mut self, token: impl Into<ExpressionToken>, range: ByteRange) {
+        assert!(range.start < range.end);
+        self.interpolate(range.start);
+        self.emit_expression_token(token.into(), range);
+    }
+
+    // #{ inside a string. Starts a new expression, just like (.
+    pub fn on_interpolation_open(&mut self, range: ByteRange) {
+        self.interpolate(range.start);
+        let open_token = ExpressionBoundary::Interpolation.placeholder_open_token(None);
+        self.emit_expression_token(open_token, range);
+        self.whitespace_state = NotInTerm;
+    }
+
+    // The } that ends a #{. Afterwards we're back in the string's text.
+    pub fn on_interpolation_close(&mut self, range: ByteRange) {
+        self.on_operator_token(ExpressionBoundary::Interpolation.placeholder_close_token(), range);
+        self.whitespace_state = NotInTerm;
+    }
+
     // ; , or :. If it is in a term, the term is closed before the separator.
     // Afterwards, we are looking to start a new term, so it's still closed.
     pub fn on_separator(&mut self, token: OperatorToken, range: ByteRange) {
@@ -309,6 +382,13 @@ impl Tokenizer {
         }
     }
 
+    // Join the pieces of an interpolated string: "a#{b}c" is "a" INTERPOLATE (b) INTERPOLATE "c".
+    fn interpolate(&mut self, index: ByteIndex) {
+        if !self.prev_was_operator {
+            self.emit_operator_token(InfixOperator(INTERPOLATE), index..index);
+        }
+    }
+
     fn emit_expression_token(&mut self, token: ExpressionToken, range: ByteRange) {
         self.grouper.on_expression_token(token, range);
         self.prev_was_operator = token.has_right_operand();
@@ -1,13 +1,17 @@
 use berg_util::{index_type, IndexedVec};
 use std::borrow::Cow;
+use std::cell::OnceCell;
 use std::num::NonZeroU32;
 use string_interner::backend::StringBackend;
 use string_interner::{DefaultSymbol, StringInterner, Symbol};
 
 use super::block::{AstBlock, BlockIndex, Field, FieldIndex};
-use super::bytes::ByteRange;
-use super::char_data::CharData;
+use super::bytes::{ByteIndex, ByteRange};
+use super::char_data::{CharData, ColumnUnit, LineColumn, LineColumnRange};
+use super::concrete_syntax_tree::ConcreteSyntaxTree;
 use super::identifiers::{keywords, IdentifierIndex};
+use super::position_index::PositionIndex;
+use super::precedence::OperatorTable;
 use super::source_reconstruction::{SourceReconstruction, SourceReconstructionReader};
 use super::token::{ExpressionToken, OperatorToken, Token};
 use OperandPosition::*;
@@ -38,6 +42,16 @@ pub struct Ast {
     pub token_ranges: TokenRanges,
     pub blocks: IndexedVec<AstBlock, BlockIndex>,
     pub fields: IndexedVec<Field, FieldIndex>,
+    ///
+    /// The operators declared for this parse (see [`OperatorTable`]).
+    ///
+    pub operators: OperatorTable,
+    ///
+    /// The source, reconstructed the first time a column is counted in
+    /// characters or UTF-16 (which needs the text of the line). An AST isn't
+    /// changed once it's parsed, so this doesn't go stale.
+    ///
+    pub(crate) source: OnceCell<Vec<u8>>,
 }
 
 #[derive(Debug, Copy, Clone, PartialEq)]
@@ -50,7 +64,16 @@ pub enum OperandPosition {
 
 impl Default for Ast {
     fn default() -> Ast {
-        let identifiers = super::identifiers::intern_all();
+        Ast::with_operators(OperatorTable::default())
+    }
+}
+
+impl Ast {
+    ///
+    /// An empty AST whose infix operators group according to `operators`.
+    ///
+    pub fn with_operators(operators: OperatorTable) -> Ast {
+        let identifiers = operators.identifiers().clone();
         let fields = keywords::FIELD_NAMES
             .iter()
             .map(|name| Field {
@@ -67,11 +90,11 @@ impl Default for Ast {
             blocks: Default::default(),
             tokens: Default::default(),
             token_ranges: Default::default(),
+            operators,
+            source: OnceCell::new(),
         }
     }
-}
 
-impl Ast {
     pub fn token(&self, index: AstIndex) -> Token {
         self.tokens[index]
     }
@@ -93,10 +116,10 @@ impl Ast {
             _ => unreachable!(),
         }
     }
-    pub fn token_string(&self, index: AstIndex) -> Cow<str> {
+    pub fn token_string(&self, index: AstIndex) -> Cow<'_, str> {
         self.tokens[index].to_string(self)
     }
-    pub fn visible_token_string(&self, index: AstIndex) -> Cow<str> {
+    pub fn visible_token_string(&self, index: AstIndex) -> Cow<'_, str> {
         self.tokens[index].to_visible_string(self)
     }
     pub fn token_range(&self, index: AstIndex) -> ByteRange {
@@ -108,6 +131,37 @@ impl Ast {
     pub fn literal_string(&self, index: LiteralIndex) -> &str {
         self.literals.resolve(index).unwrap()
     }
+    ///
+    /// The value of a string literal, with quotes removed and escapes resolved.
+    ///
+    /// Unrecognized escapes (like `\q`) are left as-is.
+    ///
+    pub fn string_value(&self, index: LiteralIndex) -> Cow<'_, str> {
+        let literal = self.literal_string(index);
+        unescape(&literal[1..literal.len() - 1])
+    }
+    ///
+    /// The value of a piece of an interpolated string, with escapes resolved.
+    ///
+    pub fn string_fragment_value(&self, index: LiteralIndex) -> Cow<'_, str> {
+        unescape(self.literal_string(index))
+    }
+    ///
+    /// The name and text of a text block: `sql` and the indented lines after
+    /// `sql:`. The text is exactly as it is in the source, indent and all; the
+    /// returned index is where it starts in the literal.
+    ///
+    pub fn text_block_parts(&self, index: LiteralIndex) -> (&str, &str, usize) {
+        let literal = self.literal_string(index);
+        let name_end = literal.find(':').unwrap();
+        let line_end = literal.find(['\r', '\n']).unwrap();
+        let text_start = if literal[line_end..].starts_with("\r\n") {
+            line_end + 2
+        } else {
+            line_end + 1
+        };
+        (&literal[..name_end], &literal[text_start..], text_start)
+    }
     pub fn raw_literal_string(&self, index: RawLiteralIndex) -> &[u8] {
         &self.raw_literals[index]
     }
@@ -122,16 +176,49 @@ impl Ast {
         AstIndex(0)
     }
 
-    pub fn read_bytes(&self) -> SourceReconstructionReader {
+    pub fn read_bytes(&self) -> SourceReconstructionReader<'_> {
         SourceReconstructionReader::new(self, 0.into()..self.char_data.size)
     }
     pub fn to_bytes(&self) -> Vec<u8> {
         SourceReconstruction::new(self, 0.into()..self.char_data.size).to_bytes()
     }
+    pub fn concrete_syntax_tree(&self) -> ConcreteSyntaxTree<'_> {
+        ConcreteSyntaxTree::new(self)
+    }
+
+    ///
+    /// An index to find the expression (and field) at a position.
+    ///
+    pub fn position_index(&self) -> PositionIndex<'_> {
+        PositionIndex::new(self)
+    }
+
+    ///
+    /// The line and columns of a range, with the columns in `unit`.
+    ///
+    pub fn line_column_range(&self, range: &ByteRange, unit: ColumnUnit) -> LineColumnRange {
+        match unit {
+            ColumnUnit::Bytes => self.char_data.range(range),
+            _ => self.char_data.range_in(self.source(), range, unit),
+        }
+    }
+
+    ///
+    /// The byte at a line and column, whatever unit the column is in.
+    ///
+    pub fn byte_index(&self, location: LineColumn) -> ByteIndex {
+        match location.unit {
+            ColumnUnit::Bytes => self.char_data.byte_index(location),
+            _ => self.char_data.byte_index_in(self.source(), location),
+        }
+    }
+
+    fn source(&self) -> &[u8] {
+        self.source.get_or_init(|| self.to_bytes())
+    }
 
     pub fn push_token(&mut self, token: impl Into<Token>, range: ByteRange) -> AstIndex {
         let token = token.into();
-        println!("PUSH {:?}", token);
         // Validate that we push tokens in increasing order
         assert!(
             match self.token_ranges.last() {
@@ -149,7 +236,6 @@ impl Ast {
 
     pub fn insert_token(&mut self, index: AstIndex, token: impl Into<Token>, range: ByteRange) {
         let token = token.into();
-        println!("INSERT {:?} AT {}", token, index);
         assert!(index == 0 || range.start >= self.token_ranges[index - 1].end);
         assert!(index == self.token_ranges.len() || range.end <= self.token_ranges[index].start);
         self.tokens.insert(index, token);
@@ -168,6 +254,40 @@ impl Ast {
     }
 }
 
+///
+/// Resolves the escapes in the text of a string.
+///
+/// Unrecognized escapes (like `\q`) are left as-is.
+///
+fn unescape(literal: &str) -> Cow<'_, str> {
+    if !literal.contains('\\') {
+        return literal.into();
+    }
+    let mut value = String::with_capacity(literal.len());
+    let mut chars = literal.chars();
+    while let Some(ch) = chars.next() {
+        if ch != '\\' {
+            value.push(ch);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => value.push('\n'),
+            Some('r') => value.push('\r'),
+            Some('t') => value.push('\t'),
+            Some('0') => value.push('\0'),
+            Some('\\') => value.push('\\'),
+            Some('"') => value.push('"'),
+            Some('#') => value.push('#'),
+            Some(ch) => {
+                value.push('\\');
+                value.push(ch);
+            }
+            None => value.push('\\'),
+        }
+    }
+    value.into()
+}
+
 impl fmt::Display for Ast {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         writeln!(f, "Tokens:")?;
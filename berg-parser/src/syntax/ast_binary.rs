@@ -0,0 +1,617 @@
+use berg_util::{Delta, IndexType, IndexedVec};
+use std::cell::OnceCell;
+use std::ops::Range;
+use string_interner::backend::StringBackend;
+use string_interner::{DefaultSymbol, StringInterner, Symbol};
+
+use super::ast::{Ast, AstIndex, RawLiteralIndex, WhitespaceIndex};
+use super::block::{AstBlock, BlockIndex, Field, FieldIndex};
+use super::bytes::ByteIndex;
+use super::char_data::CharData;
+use super::identifiers::IdentifierIndex;
+use super::precedence::{
+    Associativity, OperatorDeclaration, OperatorTable, Precedence, RelativePrecedence,
+};
+use super::token::{
+    ErrorTermError, ExpressionBoundary, ExpressionBoundaryError, ExpressionToken, FloatParts,
+    OperatorToken, RawErrorTermError, TermToken, Token,
+};
+
+///
+/// The version of the binary AST format.
+///
+/// Bump this whenever the layout changes, including when a token is added, so that old cached ASTs are parsed again instead of misread.
+///
+pub const AST_FORMAT_VERSION: u32 = 12;
+
+///
+/// The first bytes of every binary AST.
+///
+const MAGIC: &[u8; 4] = b"BAST";
+
+///
+/// Why a binary AST couldn't be loaded.
+///
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum AstFormatError {
+    ///
+    /// The bytes don't start with the binary AST header.
+    ///
+    NotAnAst,
+    ///
+    /// The AST was written with a different format version.
+    ///
+    WrongVersion(u32),
+    ///
+    /// The AST was parsed from different source.
+    ///
+    WrongHash(u64),
+    ///
+    /// The bytes are truncated or otherwise don't make sense.
+    ///
+    Corrupt,
+}
+
+///
+/// A hash of source code, used to tell whether a binary AST belongs to it.
+///
+/// This is 64-bit FNV-1a. It is stable across platforms and builds, so it can
+/// be used to name files on disk.
+///
+pub fn content_hash(source: &[u8]) -> u64 {
+    source.iter().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+impl Ast {
+    ///
+    /// Writes this AST in the binary AST format.
+    ///
+    /// `hash` is the [`content_hash()`] of the source the AST was parsed
+    /// from; [`Ast::from_binary()`] checks it.
+    ///
+    pub fn to_binary(&self, hash: u64) -> Vec<u8> {
+        let mut out = MAGIC.to_vec();
+        out.extend_from_slice(&AST_FORMAT_VERSION.to_le_bytes());
+        out.extend_from_slice(&hash.to_le_bytes());
+        self.write(&mut out);
+        out
+    }
+
+    ///
+    /// Reads an AST written by [`Ast::to_binary()`].
+    ///
+    /// # Errors
+    ///
+    /// Fails if the bytes aren't a binary AST, were written with another
+    /// [`AST_FORMAT_VERSION`], were parsed from source with a different
+    /// `hash`, or are truncated. This checks the format, not whether the AST
+    /// itself makes sense.
+    ///
+    pub fn from_binary(bytes: &[u8], hash: u64) -> Result<Ast, AstFormatError> {
+        use AstFormatError::*;
+        let mut input = Reader(bytes);
+        if input.bytes(MAGIC.len()).ok() != Some(&MAGIC[..]) {
+            return Err(NotAnAst);
+        }
+        let version = u32::from_le_bytes(input.array()?);
+        if version != AST_FORMAT_VERSION {
+            return Err(WrongVersion(version));
+        }
+        let actual_hash = u64::from_le_bytes(input.array()?);
+        if actual_hash != hash {
+            return Err(WrongHash(actual_hash));
+        }
+        let ast = Ast::read(&mut input)?;
+        if !input.0.is_empty() || ast.tokens.len() != ast.token_ranges.len() {
+            return Err(Corrupt);
+        }
+        Ok(ast)
+    }
+}
+
+///
+/// The bytes of a binary AST that haven't been read yet.
+///
+struct Reader<'a>(&'a [u8]);
+
+impl<'a> Reader<'a> {
+    fn bytes(&mut self, len: usize) -> Result<&'a [u8], AstFormatError> {
+        if len > self.0.len() {
+            return Err(AstFormatError::Corrupt);
+        }
+        let (bytes, rest) = self.0.split_at(len);
+        self.0 = rest;
+        Ok(bytes)
+    }
+    fn array<const N: usize>(&mut self) -> Result<[u8; N], AstFormatError> {
+        Ok(self.bytes(N)?.try_into().unwrap())
+    }
+    fn tag(&mut self) -> Result<u8, AstFormatError> {
+        Ok(self.bytes(1)?[0])
+    }
+}
+
+///
+/// A value that can be written to and read from a binary AST.
+///
+trait Binary: Sized {
+    fn write(&self, out: &mut Vec<u8>);
+    fn read(input: &mut Reader) -> Result<Self, AstFormatError>;
+}
+
+//
+// Numbers are written as LEB128 varints: 7 bits at a time, low bits first,
+// with the high bit set on every byte but the last.
+//
+impl Binary for u64 {
+    fn write(&self, out: &mut Vec<u8>) {
+        let mut value = *self;
+        while value >= 0x80 {
+            out.push(value as u8 | 0x80);
+            value >>= 7;
+        }
+        out.push(value as u8);
+    }
+    fn read(input: &mut Reader) -> Result<Self, AstFormatError> {
+        let mut value = 0u64;
+        for shift in (0..64).step_by(7) {
+            let byte = input.tag()?;
+            value |= u64::from(byte & 0x7F) << shift;
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+        Err(AstFormatError::Corrupt)
+    }
+}
+impl Binary for u32 {
+    fn write(&self, out: &mut Vec<u8>) {
+        u64::from(*self).write(out)
+    }
+    fn read(input: &mut Reader) -> Result<Self, AstFormatError> {
+        u32::try_from(u64::read(input)?).map_err(|_| AstFormatError::Corrupt)
+    }
+}
+impl Binary for u16 {
+    fn write(&self, out: &mut Vec<u8>) {
+        u64::from(*self).write(out)
+    }
+    fn read(input: &mut Reader) -> Result<Self, AstFormatError> {
+        u16::try_from(u64::read(input)?).map_err(|_| AstFormatError::Corrupt)
+    }
+}
+impl Binary for usize {
+    fn write(&self, out: &mut Vec<u8>) {
+        (*self as u64).write(out)
+    }
+    fn read(input: &mut Reader) -> Result<Self, AstFormatError> {
+        usize::try_from(u64::read(input)?).map_err(|_| AstFormatError::Corrupt)
+    }
+}
+impl Binary for u8 {
+    fn write(&self, out: &mut Vec<u8>) {
+        out.push(*self)
+    }
+    fn read(input: &mut Reader) -> Result<Self, AstFormatError> {
+        input.tag()
+    }
+}
+impl Binary for bool {
+    fn write(&self, out: &mut Vec<u8>) {
+        out.push(*self as u8)
+    }
+    fn read(input: &mut Reader) -> Result<Self, AstFormatError> {
+        match input.tag()? {
+            0 => Ok(false),
+            1 => Ok(true),
+            _ => Err(AstFormatError::Corrupt),
+        }
+    }
+}
+fn write_str(string: &str, out: &mut Vec<u8>) {
+    string.len().write(out);
+    out.extend_from_slice(string.as_bytes());
+}
+impl Binary for String {
+    fn write(&self, out: &mut Vec<u8>) {
+        write_str(self, out)
+    }
+    fn read(input: &mut Reader) -> Result<Self, AstFormatError> {
+        let len = usize::read(input)?;
+        let bytes = input.bytes(len)?;
+        String::from_utf8(bytes.to_vec()).map_err(|_| AstFormatError::Corrupt)
+    }
+}
+impl<T: Binary> Binary for Vec<T> {
+    fn write(&self, out: &mut Vec<u8>) {
+        self.len().write(out);
+        for value in self {
+            value.write(out);
+        }
+    }
+    fn read(input: &mut Reader) -> Result<Self, AstFormatError> {
+        let len = usize::read(input)?;
+        // Every value takes at least a byte, so don't trust a longer length.
+        if len > input.0.len() {
+            return Err(AstFormatError::Corrupt);
+        }
+        (0..len).map(|_| T::read(input)).collect()
+    }
+}
+impl<T: Binary, Idx: IndexType> Binary for IndexedVec<T, Idx> {
+    fn write(&self, out: &mut Vec<u8>) {
+        self.as_raw_vec().write(out)
+    }
+    fn read(input: &mut Reader) -> Result<Self, AstFormatError> {
+        Ok(Vec::read(input)?.into())
+    }
+}
+impl<T: Binary> Binary for Option<T> {
+    fn write(&self, out: &mut Vec<u8>) {
+        self.is_some().write(out);
+        if let Some(value) = self {
+            value.write(out);
+        }
+    }
+    fn read(input: &mut Reader) -> Result<Self, AstFormatError> {
+        match bool::read(input)? {
+            true => Ok(Some(T::read(input)?)),
+            false => Ok(None),
+        }
+    }
+}
+impl<A: Binary, B: Binary> Binary for (A, B) {
+    fn write(&self, out: &mut Vec<u8>) {
+        self.0.write(out);
+        self.1.write(out);
+    }
+    fn read(input: &mut Reader) -> Result<Self, AstFormatError> {
+        Ok((A::read(input)?, B::read(input)?))
+    }
+}
+impl<T: Binary> Binary for Range<T> {
+    fn write(&self, out: &mut Vec<u8>) {
+        self.start.write(out);
+        self.end.write(out);
+    }
+    fn read(input: &mut Reader) -> Result<Self, AstFormatError> {
+        Ok(T::read(input)?..T::read(input)?)
+    }
+}
+impl<T: Binary> Binary for Delta<T> {
+    fn write(&self, out: &mut Vec<u8>) {
+        self.0.write(out)
+    }
+    fn read(input: &mut Reader) -> Result<Self, AstFormatError> {
+        Ok(Delta(T::read(input)?))
+    }
+}
+
+///
+/// Interned strings are written in symbol order, so interning them again in
+/// the same order gives them the same symbols.
+///
+impl<S: Symbol> Binary for StringInterner<StringBackend<S>> {
+    fn write(&self, out: &mut Vec<u8>) {
+        self.len().write(out);
+        for (_, string) in self.iter() {
+            write_str(string, out);
+        }
+    }
+    fn read(input: &mut Reader) -> Result<Self, AstFormatError> {
+        let len = usize::read(input)?;
+        let mut interner = StringInterner::<StringBackend<S>>::new();
+        for index in 0..len {
+            let symbol = interner.get_or_intern(String::read(input)?);
+            // A repeated string would shift every symbol after it.
+            if symbol.to_usize() != index {
+                return Err(AstFormatError::Corrupt);
+            }
+        }
+        Ok(interner)
+    }
+}
+
+macro_rules! binary_index {
+    ($($name:ident),*) => {
+        $(
+            impl Binary for $name {
+                fn write(&self, out: &mut Vec<u8>) {
+                    self.0.write(out)
+                }
+                fn read(input: &mut Reader) -> Result<Self, AstFormatError> {
+                    Ok($name(u32::read(input)?))
+                }
+            }
+        )*
+    };
+}
+binary_index!(AstIndex, BlockIndex, ByteIndex, FieldIndex, RawLiteralIndex);
+
+macro_rules! binary_symbol {
+    ($($name:ident),*) => {
+        $(
+            impl Binary for $name {
+                fn write(&self, out: &mut Vec<u8>) {
+                    self.to_usize().write(out)
+                }
+                fn read(input: &mut Reader) -> Result<Self, AstFormatError> {
+                    $name::try_from_usize(usize::read(input)?).ok_or(AstFormatError::Corrupt)
+                }
+            }
+        )*
+    };
+}
+binary_symbol!(DefaultSymbol, IdentifierIndex, WhitespaceIndex);
+
+///
+/// Enums without fields are written as their variant's index in the list.
+///
+macro_rules! binary_enum {
+    ($($name:ident { $($variant:ident),* $(,)? })*) => {
+        $(
+            impl $name {
+                const BINARY_VARIANTS: &'static [$name] = &[$($name::$variant),*];
+            }
+            impl Binary for $name {
+                fn write(&self, out: &mut Vec<u8>) {
+                    let index = Self::BINARY_VARIANTS.iter().position(|variant| variant == self);
+                    out.push(index.unwrap() as u8)
+                }
+                fn read(input: &mut Reader) -> Result<Self, AstFormatError> {
+                    Self::BINARY_VARIANTS
+                        .get(input.tag()? as usize)
+                        .copied()
+                        .ok_or(AstFormatError::Corrupt)
+                }
+            }
+        )*
+    };
+}
+binary_enum! {
+    ExpressionBoundary {
+        PrecedenceGroup,
+        CompoundTerm,
+        SquareBrackets,
+        Parentheses,
+        AutoBlock,
+        CurlyBraces,
+        Interpolation,
+        InterpolatedString,
+        IndentedExpression,
+        IndentedBlock,
+        Source,
+        Root,
+    }
+    ExpressionBoundaryError {
+        CloseWithoutOpen,
+        OpenWithoutClose,
+        MultipleUndent,
+    }
+    ErrorTermError {
+        IdentifierStartsWithNumber,
+        UnsupportedCharacters,
+        UnclosedString,
+        MissingExponent,
+        UppercaseHexadecimalPrefix,
+        UppercaseOctalPrefix,
+        UppercaseBinaryPrefix,
+        DigitOutOfRange,
+        ImaginaryWithUppercaseI,
+        DenormalizedIdentifier,
+        IncompleteGrapheme,
+        IdentifierTooLarge,
+        InconsistentIndentCharacters,
+        UnclosedBlockComment,
+        DoubledDigitSeparator,
+        TrailingDigitSeparator,
+        LeadingDigitSeparator,
+        DigitSeparatorNextToRadixPrefix,
+        MissingRadixDigits,
+    }
+    RawErrorTermError {
+        InvalidUtf8,
+        InvalidUtf8InString,
+        LineTooLong,
+    }
+    Precedence {
+        Dot,
+        TimesDivide,
+        PlusMinus,
+        Range,
+        Comparison,
+        And,
+        Or,
+        CommaSequence,
+        Assign,
+        ColonDeclaration,
+        FollowedBy,
+        MatchArm,
+        SemicolonSequence,
+        NewlineSequence,
+    }
+    Associativity {
+        Left,
+        Right,
+    }
+}
+
+///
+/// Enums with fields are written as a tag byte followed by the fields.
+///
+macro_rules! binary_variants {
+    ($($name:ident { $($tag:literal => $variant:ident $(($($field:ident),*))?,)* })*) => {
+        $(
+            impl Binary for $name {
+                fn write(&self, out: &mut Vec<u8>) {
+                    match self {
+                        $($name::$variant $(($($field),*))? => {
+                            out.push($tag);
+                            $($($field.write(out);)*)?
+                        })*
+                    }
+                }
+                fn read(input: &mut Reader) -> Result<Self, AstFormatError> {
+                    match input.tag()? {
+                        $($tag => Ok($name::$variant $(($(binary_variants!(@read $field input)),*))?),)*
+                        _ => Err(AstFormatError::Corrupt),
+                    }
+                }
+            }
+        )*
+    };
+    (@read $field:ident $input:ident) => { Binary::read($input)? };
+}
+binary_variants! {
+    Token {
+        0 => Expression(token),
+        1 => Operator(token),
+    }
+    ExpressionToken {
+        0 => Term(token),
+        1 => PrefixOperator(identifier),
+        2 => Open(error, boundary, delta),
+    }
+    OperatorToken {
+        0 => InfixOperator(identifier),
+        1 => InfixAssignment(identifier),
+        2 => PostfixOperator(identifier),
+        3 => Close(delta, boundary),
+        4 => CloseBlock(block, boundary),
+    }
+    TermToken {
+        0 => IntegerLiteral(literal),
+        1 => HexadecimalLiteral(literal),
+        2 => OctalLiteral(literal),
+        3 => BinaryLiteral(literal),
+        4 => FloatLiteral(literal, parts),
+        5 => ImaginaryLiteral(literal, parts),
+        6 => StringLiteral(literal),
+        7 => StringFragment(literal),
+        8 => FieldReference(field),
+        9 => RawIdentifier(identifier),
+        10 => ErrorTerm(error, literal),
+        11 => RawErrorTerm(error, literal),
+        12 => MissingExpression,
+        13 => TextBlock(literal),
+    }
+    RelativePrecedence {
+        0 => TighterThan(level),
+        1 => SameAs(level),
+        2 => LooserThan(level),
+    }
+}
+
+///
+/// Operators (and then text blocks) are written by name, in the order they
+/// were declared, so declaring them again gives them the same identifiers.
+///
+impl Binary for OperatorTable {
+    fn write(&self, out: &mut Vec<u8>) {
+        self.declarations().len().write(out);
+        for (name, declaration) in self.declarations() {
+            write_str(name, out);
+            declaration.write(out);
+        }
+        self.text_blocks().len().write(out);
+        for name in self.text_blocks() {
+            write_str(name, out);
+        }
+    }
+    fn read(input: &mut Reader) -> Result<Self, AstFormatError> {
+        let mut operators = OperatorTable::default();
+        for _ in 0..usize::read(input)? {
+            let name = String::read(input)?;
+            let OperatorDeclaration {
+                precedence,
+                associativity,
+            } = OperatorDeclaration::read(input)?;
+            operators.declare(&name, precedence, associativity);
+        }
+        for _ in 0..usize::read(input)? {
+            operators.declare_text_block(&String::read(input)?);
+        }
+        Ok(operators)
+    }
+}
+
+///
+/// Structs are written field by field, in order.
+///
+macro_rules! binary_struct {
+    ($($name:ident { $($field:ident),* $(,)? })*) => {
+        $(
+            impl Binary for $name {
+                fn write(&self, out: &mut Vec<u8>) {
+                    $(self.$field.write(out);)*
+                }
+                fn read(input: &mut Reader) -> Result<Self, AstFormatError> {
+                    Ok($name { $($field: Binary::read(input)?),* })
+                }
+            }
+        )*
+    };
+}
+///
+/// The AST is written field by field too, except for its cached source,
+/// which is rebuilt from the rest when it's needed.
+///
+impl Binary for Ast {
+    fn write(&self, out: &mut Vec<u8>) {
+        self.char_data.write(out);
+        self.identifiers.write(out);
+        self.literals.write(out);
+        self.raw_literals.write(out);
+        self.tokens.write(out);
+        self.token_ranges.write(out);
+        self.blocks.write(out);
+        self.fields.write(out);
+        self.operators.write(out);
+    }
+    fn read(input: &mut Reader) -> Result<Self, AstFormatError> {
+        Ok(Ast {
+            char_data: Binary::read(input)?,
+            identifiers: Binary::read(input)?,
+            literals: Binary::read(input)?,
+            raw_literals: Binary::read(input)?,
+            tokens: Binary::read(input)?,
+            token_ranges: Binary::read(input)?,
+            blocks: Binary::read(input)?,
+            fields: Binary::read(input)?,
+            operators: Binary::read(input)?,
+            source: OnceCell::new(),
+        })
+    }
+}
+
+binary_struct! {
+    CharData {
+        size,
+        line_starts,
+        whitespace_characters,
+        whitespace_ranges,
+        comments,
+        digit_separators,
+    }
+    AstBlock {
+        boundary,
+        parent,
+        delta,
+        scope_start,
+        scope_count,
+    }
+    Field {
+        name,
+        is_public,
+    }
+    FloatParts {
+        mantissa_len,
+        fraction_len,
+    }
+    OperatorDeclaration {
+        precedence,
+        associativity,
+    }
+}
@@ -11,7 +11,7 @@ index_type! {
     pub struct FieldIndex(pub u32) with Display,Debug <= u32::MAX;
 }
 
-#[derive(Debug)]
+#[derive(Clone, Debug, PartialEq)]
 pub struct AstBlock {
     pub boundary: ExpressionBoundary,
     pub parent: Delta<BlockIndex>,
@@ -20,7 +20,7 @@ pub struct AstBlock {
     pub scope_count: Delta<FieldIndex>,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq)]
 pub struct Field {
     pub name: IdentifierIndex,
     pub is_public: bool,
@@ -67,30 +67,43 @@ impl AstBlock {
         name: IdentifierIndex,
         ast: &Ast,
     ) -> Result<FieldIndex, FieldError> {
+        match self.local_field_index(index, name, ast) {
+            Some(field_index) if ast.fields[field_index].is_public => Ok(field_index),
+            Some(_) => Err(FieldError::PrivateField),
+            None => Err(FieldError::NoSuchPublicField),
+        }
+    }
+
+    ///
+    /// The field with the given name that belongs to this block (and not to
+    /// one of its child blocks), whether it is public or private.
+    ///
+    pub fn local_field_index(
+        &self,
+        index: BlockIndex,
+        name: IdentifierIndex,
+        ast: &Ast,
+    ) -> Option<FieldIndex> {
         let mut child_index = index + 1;
         let mut field_index = self.scope_start;
         let scope_end = self.scope_start + self.scope_count;
         while field_index < scope_end {
-            // Bypass any indices that are owned by child blocks.
-            if let Some(child) = ast.blocks.get(child_index) {
-                if field_index >= child.scope_start {
-                    field_index = child.scope_start + child.scope_count;
-                    child_index += 1;
-                    continue;
-                }
+            // Bypass any indices that are owned by child blocks. (Grandchildren
+            // are inside their parent's range, which has already been skipped.)
+            if let Some(child) = ast.blocks.get(child_index)
+                && field_index >= child.scope_start
+            {
+                field_index = field_index.max(child.scope_start + child.scope_count);
+                child_index += 1;
+                continue;
             }
 
-            let field = &ast.fields[field_index];
-            if field.name == name {
-                if field.is_public {
-                    return Ok(field_index);
-                } else {
-                    return Err(FieldError::PrivateField);
-                };
+            if ast.fields[field_index].name == name {
+                return Some(field_index);
             }
             field_index += 1;
         }
 
-        Err(FieldError::NoSuchPublicField)
+        None
     }
 }
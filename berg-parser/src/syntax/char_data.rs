@@ -1,4 +1,5 @@
 use berg_util::Delta;
+use std::borrow::Cow;
 use std::cmp::Ordering;
 use std::fmt::{Display, Formatter, Result};
 use std::str;
@@ -46,18 +47,53 @@ pub struct CharData {
     /// Ordered list of comments in the document.
     ///
     /// These include the # character at the beginning of the comment and do *not*
-    /// include the line ending character.
+    /// include the line ending character. Block comments include the `#[` and
+    /// `]#` and any line endings inside them. (If a block comment is never
+    /// closed, its `#[` is an error token and the rest is the comment.)
     ///
     /// Comments may include non-UTF-8 characters. (This is why it's Vec<u8> and
     /// not String.)
     ///
     pub comments: Vec<(Vec<u8>, ByteIndex)>,
+
+    ///
+    /// Ordered list of the `_` separators in numbers (like `1_000`).
+    ///
+    /// Numbers are interned without them, so this is what puts them back.
+    ///
+    pub digit_separators: Vec<ByteIndex>,
+}
+
+///
+/// What a column counts.
+///
+/// Bytes are what the parser works in; editors and the Language Server
+/// Protocol count UTF-16 code units, and people count characters. They only
+/// differ on lines with non-ASCII text.
+///
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ColumnUnit {
+    #[default]
+    Bytes,
+    ///
+    /// Unicode scalar values (Rust `char`s).
+    ///
+    Chars,
+    ///
+    /// UTF-16 code units: characters outside the Basic Multilingual Plane
+    /// (like most emoji) take two.
+    ///
+    Utf16,
 }
 
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub struct LineColumn {
     pub line: u32,
-    pub column: Delta<ByteIndex>,
+    ///
+    /// The 1-based column, in `unit`s.
+    ///
+    pub column: u32,
+    pub unit: ColumnUnit,
 }
 
 // Inclusive line/column range
@@ -78,6 +114,7 @@ impl Default for CharData {
             whitespace_characters: StringInterner::new(),
             whitespace_ranges: Default::default(),
             comments: Default::default(),
+            digit_separators: Default::default(),
         }
     }
 }
@@ -102,48 +139,186 @@ impl CharData {
         self.comments.push((bytes.into(), start))
     }
 
+    ///
+    /// Add the `_` separators in a number to the separator list.
+    ///
+    pub fn append_digit_separators(&mut self, number: &[u8], start: ByteIndex) {
+        let separators = number.iter().enumerate().filter(|(_, byte)| **byte == b'_');
+        self.digit_separators
+            .extend(separators.map(|(index, _)| start + index));
+    }
+
+    ///
+    /// Puts the `_` separators back into the bytes of the token at `start`.
+    ///
+    pub fn with_digit_separators<'a>(
+        &self,
+        bytes: Cow<'a, [u8]>,
+        start: ByteIndex,
+    ) -> Cow<'a, [u8]> {
+        let first = self
+            .digit_separators
+            .partition_point(|index| *index < start);
+        let mut separators = self.digit_separators[first..]
+            .iter()
+            .map(|index| usize::from(*index - start))
+            .peekable();
+        if separators
+            .peek()
+            .is_none_or(|offset| *offset >= bytes.len())
+        {
+            return bytes;
+        }
+        let mut bytes = bytes.into_owned();
+        // Separators are always between two digits, so each one we put back
+        // is followed by at least one byte we already have.
+        while let Some(offset) = separators.next_if(|offset| *offset < bytes.len()) {
+            bytes.insert(offset, b'_');
+        }
+        bytes.into()
+    }
+
     pub fn location(&self, index: ByteIndex) -> LineColumn {
-        let line = match self.line_starts.binary_search(&index) {
-            // If the index happens to be at the start of a line, we'll get
-            // the 0-based index of that line, and we want 1-based.
-            Ok(line) => line + 1,
-            // If the index is not the start of a line, we'll get the index of the
-            // *next* line, which is the same as a 1-based index to our line.
-            Err(line) => line,
-        };
+        let line = self.line(index);
+        let column = usize::from(index + 1 - self.line_starts[line - 1]) as u32;
+        LineColumn::new(line as u32, column, ColumnUnit::Bytes)
+    }
 
-        let column = index + 1 - self.line_starts[line - 1];
-        let line = line as u32;
-        LineColumn { line, column }
+    ///
+    /// The location of the byte, with the column counted in `unit`. A byte in
+    /// the middle of a character is at that character's column.
+    ///
+    /// `source` is the whole source.
+    ///
+    pub fn location_in(&self, source: &[u8], index: ByteIndex, unit: ColumnUnit) -> LineColumn {
+        if unit == ColumnUnit::Bytes {
+            return self.location(index);
+        }
+        let line = self.line(index);
+        let line_start = self.line_starts[line - 1];
+        let mut index = index;
+        while index > line_start && is_continuation_byte(source.get(usize::from(index))) {
+            index -= 1;
+        }
+        let before = &source[usize::from(line_start)..usize::from(index).min(source.len())];
+        let column = unit_widths(before, unit)
+            .map(|(_, width)| width)
+            .sum::<u32>()
+            + 1;
+        LineColumn::new(line as u32, column, unit)
     }
 
     pub fn range(&self, range: &ByteRange) -> LineColumnRange {
-        let start = self.location(range.start);
+        self.range_in(&[], range, ColumnUnit::Bytes)
+    }
+
+    ///
+    /// The location of the range, with columns counted in `unit`.
+    ///
+    /// `source` is the whole source (it isn't needed for bytes).
+    ///
+    pub fn range_in(&self, source: &[u8], range: &ByteRange, unit: ColumnUnit) -> LineColumnRange {
+        let start = self.location_in(source, range.start, unit);
         if range.start == range.end {
             LineColumnRange { start, end: None }
         } else {
-            let end = Some(self.location(range.end - 1));
+            let end = Some(self.location_in(source, range.end - 1, unit));
             LineColumnRange { start, end }
         }
     }
 
+    ///
+    /// The byte at a location with the column in bytes.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the column isn't in bytes; use [`CharData::byte_index_in()`]
+    /// for other units.
+    ///
     pub fn byte_index(&self, location: LineColumn) -> ByteIndex {
-        self.line_starts[(location.line - 1) as usize] + location.column - 1
+        assert_eq!(location.unit, ColumnUnit::Bytes);
+        self.line_starts[(location.line - 1) as usize] + Delta::from(location.column as usize - 1)
+    }
+
+    ///
+    /// The byte at a location, whatever unit its column is in. Columns past
+    /// the end of the line count as one byte each.
+    ///
+    /// `source` is the whole source (it isn't needed for bytes).
+    ///
+    pub fn byte_index_in(&self, source: &[u8], location: LineColumn) -> ByteIndex {
+        if location.unit == ColumnUnit::Bytes {
+            return self.byte_index(location);
+        }
+        let line = (location.line - 1) as usize;
+        let line_start = usize::from(self.line_starts[line]);
+        let line_end = match self.line_starts.get(line + 1) {
+            Some(&next_line_start) => usize::from(next_line_start),
+            None => source.len(),
+        };
+        let mut column = 1;
+        for (offset, width) in unit_widths(&source[line_start..line_end], location.unit) {
+            if column >= location.column {
+                return (line_start + offset).into();
+            }
+            column += width;
+        }
+        (line_end + (location.column.saturating_sub(column)) as usize).into()
     }
 
-    #[allow(clippy::range_plus_one)]
     pub fn byte_range(&self, range: LineColumnRange) -> ByteRange {
-        let start = self.byte_index(range.start);
+        self.byte_range_in(&[], range)
+    }
+
+    ///
+    /// The bytes in a range, whatever unit its columns are in. The end is
+    /// past the whole last character.
+    ///
+    /// `source` is the whole source (it isn't needed for bytes).
+    ///
+    #[allow(clippy::range_plus_one)]
+    pub fn byte_range_in(&self, source: &[u8], range: LineColumnRange) -> ByteRange {
+        let start = self.byte_index_in(source, range.start);
         match range.end {
-            Some(end) => start..(self.byte_index(end) + 1),
+            Some(end) => {
+                let mut end = self.byte_index_in(source, end) + 1;
+                while is_continuation_byte(source.get(usize::from(end))) {
+                    end += 1;
+                }
+                start..end
+            }
             None => start..start,
         }
     }
+
+    ///
+    /// The 1-based line the byte is on.
+    ///
+    fn line(&self, index: ByteIndex) -> usize {
+        match self.line_starts.binary_search(&index) {
+            // If the index happens to be at the start of a line, we'll get
+            // the 0-based index of that line, and we want 1-based.
+            Ok(line) => line + 1,
+            // If the index is not the start of a line, we'll get the index of the
+            // *next* line, which is the same as a 1-based index to our line.
+            Err(line) => line,
+        }
+    }
 }
 
 impl LineColumn {
-    pub fn new(line: u32, column: Delta<ByteIndex>) -> LineColumn {
-        LineColumn { line, column }
+    pub fn new(line: u32, column: u32, unit: ColumnUnit) -> LineColumn {
+        LineColumn { line, column, unit }
+    }
+
+    ///
+    /// The same location, with the column counted in `unit`.
+    ///
+    pub fn to_unit(self, char_data: &CharData, source: &[u8], unit: ColumnUnit) -> LineColumn {
+        if self.unit == unit {
+            return self;
+        }
+        char_data.location_in(source, char_data.byte_index_in(source, self), unit)
     }
 }
 
@@ -157,6 +332,16 @@ impl LineColumnRange {
     pub fn zero_width(start: LineColumn) -> LineColumnRange {
         LineColumnRange { start, end: None }
     }
+
+    ///
+    /// The same range, with the columns counted in `unit`.
+    ///
+    pub fn to_unit(self, char_data: &CharData, source: &[u8], unit: ColumnUnit) -> LineColumnRange {
+        LineColumnRange {
+            start: self.start.to_unit(char_data, source, unit),
+            end: self.end.map(|end| end.to_unit(char_data, source, unit)),
+        }
+    }
 }
 
 impl PartialOrd for LineColumn {
@@ -200,3 +385,34 @@ impl Display for LineColumnRange {
         }
     }
 }
+
+fn is_continuation_byte(byte: Option<&u8>) -> bool {
+    matches!(byte, Some(byte) if byte & 0xC0 == 0x80)
+}
+
+///
+/// Where each character in the bytes starts, and how many `unit`s it takes.
+/// Invalid UTF-8 counts as one character for each invalid sequence (the way
+/// it is shown, as a replacement character).
+///
+fn unit_widths(bytes: &[u8], unit: ColumnUnit) -> impl Iterator<Item = (usize, u32)> + '_ {
+    let mut offset = 0;
+    bytes.utf8_chunks().flat_map(move |chunk| {
+        let start = offset;
+        offset += chunk.valid().len() + chunk.invalid().len();
+        let valid = chunk.valid().char_indices().map(move |(index, ch)| {
+            let width = match unit {
+                ColumnUnit::Bytes => ch.len_utf8(),
+                ColumnUnit::Chars => 1,
+                ColumnUnit::Utf16 => ch.len_utf16(),
+            };
+            (start + index, width as u32)
+        });
+        let invalid = match (chunk.invalid().len(), unit) {
+            (0, _) => None,
+            (len, ColumnUnit::Bytes) => Some((start + chunk.valid().len(), len as u32)),
+            (_, _) => Some((start + chunk.valid().len(), 1)),
+        };
+        valid.chain(invalid)
+    })
+}
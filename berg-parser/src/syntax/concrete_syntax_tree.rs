@@ -0,0 +1,495 @@
+use berg_util::IndexedVec;
+use std::borrow::Cow;
+use std::fmt;
+use std::ops::Range;
+
+use super::ast::{Ast, AstIndex};
+use super::bytes::{ByteIndex, ByteRange};
+use super::token::{ExpressionBoundary, ExpressionToken, Token};
+
+///
+/// A lossless view of an AST as a tree, where every token owns the whitespace
+/// and comments around it (its "trivia").
+///
+/// A token's trailing trivia is everything after it up to the end of its line.
+/// Its leading trivia is everything after that: line endings, indent, and
+/// comments on lines of their own. Trivia after the last token is the leading
+/// trivia of the (empty) token that closes the source.
+///
+/// Writing out each token's leading trivia, bytes and trailing trivia, in
+/// order, always yields the original source.
+///
+pub struct ConcreteSyntaxTree<'a> {
+    ast: &'a Ast,
+    /// All trivia, in order.
+    trivia: Vec<Trivia>,
+    /// The leading and trailing trivia of each token (indexes into `trivia`).
+    token_trivia: IndexedVec<(Range<usize>, Range<usize>), AstIndex>,
+}
+
+///
+/// A piece of whitespace or a comment.
+///
+#[derive(Debug, Clone, PartialEq)]
+pub enum Trivia {
+    /// A run of ` ` characters.
+    Space(ByteRange),
+    /// A run of other horizontal whitespace, like tabs (possibly with spaces).
+    Whitespace(ByteRange),
+    /// A line ending: `\n`, `\r\n` or `\r`.
+    Newline(ByteRange),
+    /// A `#` comment, not including the line ending, or a `#[ ... ]#` comment
+    /// (which may span lines).
+    Comment(ByteRange),
+}
+
+///
+/// A node in the tree: a term, an operator, or a group or block with the
+/// nodes inside it.
+///
+#[derive(Debug, Copy, Clone)]
+pub enum CstNode<'a> {
+    /// A term, like `1`, `a` or `"abc"`.
+    Term(CstToken<'a>),
+    /// A prefix, infix or postfix operator, like the `+` in `a + b`.
+    Operator(CstToken<'a>),
+    /// A group that is not a block, like `(...)`, a compound term or a
+    /// precedence group.
+    Group(CstGroup<'a>),
+    /// A block, like `{...}`, an indented block or the source itself.
+    Block(CstGroup<'a>),
+}
+
+///
+/// A single token and its trivia.
+///
+/// Tokens the parser inserts (like implicit groups, or the operator between
+/// `a` and `b` in `a b`) have no bytes, and no trivia of their own.
+///
+#[derive(Copy, Clone)]
+pub struct CstToken<'a> {
+    tree: &'a ConcreteSyntaxTree<'a>,
+    index: AstIndex,
+}
+
+///
+/// A group or block: its open and close tokens, and the nodes between them.
+///
+#[derive(Copy, Clone)]
+pub struct CstGroup<'a> {
+    tree: &'a ConcreteSyntaxTree<'a>,
+    open: AstIndex,
+    close: AstIndex,
+}
+
+///
+/// Iterates over the nodes directly inside a group.
+///
+pub struct CstChildren<'a> {
+    tree: &'a ConcreteSyntaxTree<'a>,
+    next: AstIndex,
+    close: AstIndex,
+}
+
+impl<'a> ConcreteSyntaxTree<'a> {
+    pub fn new(ast: &'a Ast) -> Self {
+        let mut reader = TriviaReader::new(ast);
+        let mut token_trivia: IndexedVec<_, AstIndex> =
+            ast.tokens.iter().map(|_| (0..0, 0..0)).collect();
+        let last = ast.tokens.last_index();
+        let mut prev: Option<AstIndex> = None;
+        let mut end = ByteIndex(0);
+        for index in 0..ast.tokens.len() {
+            let index = AstIndex::from(index);
+            let range = ast.token_range(index);
+            if range.start == range.end && index != last {
+                continue;
+            }
+
+            // The trivia up to the first line ending trails the previous token;
+            // the rest leads this one.
+            let start = reader.trivia.len();
+            reader.read(end, range.start);
+            let mut split = start;
+            if let Some(prev) = prev {
+                split = reader.trivia[start..]
+                    .iter()
+                    .position(|trivia| matches!(trivia, Trivia::Newline(_)))
+                    .map_or(reader.trivia.len(), |newline| start + newline);
+                token_trivia[prev].1 = start..split;
+            }
+            token_trivia[index].0 = split..reader.trivia.len();
+
+            prev = Some(index);
+            end = range.end;
+        }
+        assert_eq!(end, ast.char_data.size);
+        ConcreteSyntaxTree {
+            ast,
+            trivia: reader.trivia,
+            token_trivia,
+        }
+    }
+
+    pub fn ast(&self) -> &'a Ast {
+        self.ast
+    }
+
+    ///
+    /// The block for the whole source.
+    ///
+    pub fn root(&'a self) -> CstNode<'a> {
+        self.node(self.ast.root_expression())
+    }
+
+    ///
+    /// The node starting at the given token (which must not be a close token).
+    ///
+    pub fn node(&'a self, index: AstIndex) -> CstNode<'a> {
+        use Token::*;
+        match self.ast.tokens[index] {
+            Expression(ExpressionToken::Open(_, boundary, delta)) => {
+                let group = CstGroup {
+                    tree: self,
+                    open: index,
+                    close: index + delta,
+                };
+                if boundary.is_block() {
+                    CstNode::Block(group)
+                } else {
+                    CstNode::Group(group)
+                }
+            }
+            Expression(ExpressionToken::Term(_)) => CstNode::Term(self.token(index)),
+            Expression(ExpressionToken::PrefixOperator(_)) | Operator(_) => {
+                CstNode::Operator(self.token(index))
+            }
+        }
+    }
+
+    pub fn token(&'a self, index: AstIndex) -> CstToken<'a> {
+        CstToken { tree: self, index }
+    }
+
+    pub fn tokens(&'a self) -> impl DoubleEndedIterator<Item = CstToken<'a>> + 'a {
+        self.token_range(0.into(), self.ast.tokens.last_index())
+    }
+
+    ///
+    /// All the trivia in the source, in order.
+    ///
+    pub fn trivia(&self) -> &[Trivia] {
+        &self.trivia
+    }
+
+    ///
+    /// The original bytes of a piece of trivia.
+    ///
+    pub fn trivia_bytes(&self, trivia: &Trivia) -> Cow<'a, [u8]> {
+        let char_data = &self.ast.char_data;
+        let whitespace = |start: ByteIndex| {
+            let index = char_data
+                .whitespace_ranges
+                .binary_search_by_key(&start, |(_, start)| *start)
+                .ok()?;
+            let (whitespace, _) = char_data.whitespace_ranges[index];
+            Some(self.ast.whitespace_string(whitespace).as_bytes())
+        };
+        match trivia {
+            Trivia::Space(range) => vec![b' '; usize::from(range.end - range.start)].into(),
+            Trivia::Whitespace(range) => whitespace(range.start).unwrap().into(),
+            Trivia::Newline(range) => whitespace(range.start).unwrap_or(b"\n").into(),
+            Trivia::Comment(range) => {
+                let index = char_data
+                    .comments
+                    .binary_search_by_key(&range.start, |(_, start)| *start)
+                    .unwrap();
+                char_data.comments[index].0.as_slice().into()
+            }
+        }
+    }
+
+    pub fn write_to(&'a self, buffer: &mut Vec<u8>) {
+        self.root().write_to(buffer)
+    }
+
+    pub fn to_bytes(&'a self) -> Vec<u8> {
+        self.root().to_bytes()
+    }
+
+    fn token_range(
+        &'a self,
+        first: AstIndex,
+        last: AstIndex,
+    ) -> impl DoubleEndedIterator<Item = CstToken<'a>> + 'a {
+        (usize::from(first)..=usize::from(last)).map(move |index| self.token(index.into()))
+    }
+}
+
+impl<'a> fmt::Debug for ConcreteSyntaxTree<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("ConcreteSyntaxTree")
+            .field("trivia", &self.trivia)
+            .field("token_trivia", &self.token_trivia)
+            .finish()
+    }
+}
+
+impl Trivia {
+    pub fn byte_range(&self) -> ByteRange {
+        match self {
+            Trivia::Space(range)
+            | Trivia::Whitespace(range)
+            | Trivia::Newline(range)
+            | Trivia::Comment(range) => range.clone(),
+        }
+    }
+}
+
+impl<'a> CstNode<'a> {
+    ///
+    /// The tokens in this node, including the open and close of a group.
+    ///
+    pub fn tokens(self) -> impl DoubleEndedIterator<Item = CstToken<'a>> + 'a {
+        match self {
+            CstNode::Term(token) | CstNode::Operator(token) => {
+                token.tree.token_range(token.index, token.index)
+            }
+            CstNode::Group(group) | CstNode::Block(group) => {
+                group.tree.token_range(group.open, group.close)
+            }
+        }
+    }
+
+    pub fn byte_range(self) -> ByteRange {
+        let start = self.tokens().next().unwrap().byte_range().start;
+        let end = self.tokens().next_back().unwrap().byte_range().end;
+        start..end
+    }
+
+    ///
+    /// The leading trivia of the first token in this node that has any.
+    ///
+    pub fn leading_trivia(self) -> &'a [Trivia] {
+        self.tokens()
+            .find(|token| token.owns_trivia())
+            .map_or(&[], |token| token.leading_trivia())
+    }
+
+    ///
+    /// The trailing trivia of the last token in this node that has any.
+    ///
+    pub fn trailing_trivia(self) -> &'a [Trivia] {
+        self.tokens()
+            .rev()
+            .find(|token| token.owns_trivia())
+            .map_or(&[], |token| token.trailing_trivia())
+    }
+
+    ///
+    /// Writes the node's source, including its leading and trailing trivia.
+    ///
+    pub fn write_to(self, buffer: &mut Vec<u8>) {
+        for token in self.tokens() {
+            token.write_to(buffer)
+        }
+    }
+
+    pub fn to_bytes(self) -> Vec<u8> {
+        let mut buffer = Vec::new();
+        self.write_to(&mut buffer);
+        buffer
+    }
+}
+
+impl<'a> CstToken<'a> {
+    pub fn index(self) -> AstIndex {
+        self.index
+    }
+
+    pub fn token(self) -> Token {
+        self.tree.ast.tokens[self.index]
+    }
+
+    pub fn byte_range(self) -> ByteRange {
+        self.tree.ast.token_range(self.index)
+    }
+
+    ///
+    /// The original bytes of the token (empty if the parser inserted it).
+    ///
+    pub fn bytes(self) -> Cow<'a, [u8]> {
+        let range = self.byte_range();
+        if range.start == range.end {
+            Cow::Borrowed(b"")
+        } else {
+            let bytes = self.token().original_bytes(self.tree.ast);
+            self.tree
+                .ast
+                .char_data
+                .with_digit_separators(bytes, range.start)
+        }
+    }
+
+    pub fn leading_trivia(self) -> &'a [Trivia] {
+        let (leading, _) = &self.tree.token_trivia[self.index];
+        &self.tree.trivia[leading.clone()]
+    }
+
+    pub fn trailing_trivia(self) -> &'a [Trivia] {
+        let (_, trailing) = &self.tree.token_trivia[self.index];
+        &self.tree.trivia[trailing.clone()]
+    }
+
+    ///
+    /// Writes the token's leading trivia, bytes and trailing trivia.
+    ///
+    pub fn write_to(self, buffer: &mut Vec<u8>) {
+        for trivia in self.leading_trivia() {
+            buffer.extend_from_slice(&self.tree.trivia_bytes(trivia));
+        }
+        buffer.extend_from_slice(&self.bytes());
+        for trivia in self.trailing_trivia() {
+            buffer.extend_from_slice(&self.tree.trivia_bytes(trivia));
+        }
+    }
+
+    // Only tokens with bytes (and the close of the source) have trivia.
+    fn owns_trivia(self) -> bool {
+        let range = self.byte_range();
+        range.start < range.end || self.index == self.tree.ast.tokens.last_index()
+    }
+}
+
+impl<'a> fmt::Debug for CstToken<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?} at {:?}", self.token(), self.byte_range())
+    }
+}
+
+impl<'a> CstGroup<'a> {
+    pub fn boundary(self) -> ExpressionBoundary {
+        match self.open().token() {
+            Token::Expression(ExpressionToken::Open(_, boundary, _)) => boundary,
+            _ => unreachable!(),
+        }
+    }
+
+    pub fn open(self) -> CstToken<'a> {
+        self.tree.token(self.open)
+    }
+
+    pub fn close(self) -> CstToken<'a> {
+        self.tree.token(self.close)
+    }
+
+    ///
+    /// The nodes between the open and close tokens.
+    ///
+    pub fn children(self) -> CstChildren<'a> {
+        CstChildren {
+            tree: self.tree,
+            next: self.open + 1,
+            close: self.close,
+        }
+    }
+}
+
+impl<'a> fmt::Debug for CstGroup<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let range = self.open().byte_range().start..self.close().byte_range().end;
+        write!(f, "{:?} at {:?}", self.boundary(), range)
+    }
+}
+
+impl<'a> Iterator for CstChildren<'a> {
+    type Item = CstNode<'a>;
+    fn next(&mut self) -> Option<CstNode<'a>> {
+        if self.next >= self.close {
+            return None;
+        }
+        let node = self.tree.node(self.next);
+        self.next = match node {
+            CstNode::Group(group) | CstNode::Block(group) => group.close + 1,
+            CstNode::Term(_) | CstNode::Operator(_) => self.next + 1,
+        };
+        Some(node)
+    }
+}
+
+///
+/// Splits the bytes between tokens into trivia, walking through the comments,
+/// whitespace and line starts in [`CharData`](super::char_data::CharData) in
+/// order.
+///
+struct TriviaReader<'a> {
+    ast: &'a Ast,
+    trivia: Vec<Trivia>,
+    comment_index: usize,
+    whitespace_index: usize,
+    line_start_index: usize,
+}
+
+impl<'a> TriviaReader<'a> {
+    fn new(ast: &'a Ast) -> Self {
+        TriviaReader {
+            ast,
+            trivia: Vec::new(),
+            comment_index: 0,
+            whitespace_index: 0,
+            line_start_index: 0,
+        }
+    }
+
+    fn read(&mut self, start: ByteIndex, end: ByteIndex) {
+        let char_data = &self.ast.char_data;
+        let mut index = start;
+        while index < end {
+            // Skip line starts we've passed (including ones inside strings).
+            while char_data
+                .line_starts
+                .get(self.line_start_index)
+                .is_some_and(|line_start| *line_start <= index)
+            {
+                self.line_start_index += 1;
+            }
+            let next_line_start = char_data.line_starts.get(self.line_start_index).copied();
+            let next_comment = char_data.comments.get(self.comment_index);
+            let next_whitespace = char_data.whitespace_ranges.get(self.whitespace_index);
+
+            let trivia = if let Some((comment, comment_start)) = next_comment
+                && *comment_start == index
+            {
+                self.comment_index += 1;
+                Trivia::Comment(index..index + comment.len())
+            } else if let Some((whitespace, whitespace_start)) = next_whitespace
+                && *whitespace_start == index
+            {
+                self.whitespace_index += 1;
+                let whitespace_end = index + self.ast.whitespace_string(*whitespace).len();
+                if next_line_start == Some(whitespace_end) {
+                    Trivia::Newline(index..whitespace_end)
+                } else {
+                    Trivia::Whitespace(index..whitespace_end)
+                }
+            } else if next_line_start == Some(index + 1) {
+                Trivia::Newline(index..index + 1)
+            } else {
+                // Anything else is spaces, up to the next comment, whitespace or newline.
+                let mut space_end = end;
+                if let Some((_, comment_start)) = next_comment {
+                    space_end = space_end.min(*comment_start);
+                }
+                if let Some((_, whitespace_start)) = next_whitespace {
+                    space_end = space_end.min(*whitespace_start);
+                }
+                if let Some(line_start) = next_line_start {
+                    space_end = space_end.min(line_start - 1);
+                }
+                Trivia::Space(index..space_end)
+            };
+            index = trivia.byte_range().end;
+            self.trivia.push(trivia);
+        }
+    }
+}
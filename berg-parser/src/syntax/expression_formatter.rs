@@ -1,7 +1,7 @@
 use std::fmt;
 
 use super::expression_tree::ExpressionTreeWalker;
-use super::identifiers::SEMICOLON;
+use super::identifiers::{INTERPOLATE, SEMICOLON};
 use super::token::{ExpressionBoundary, ExpressionToken, Fixity, OperatorToken, TermToken, Token};
 
 #[derive(Copy, Clone, Debug)]
@@ -26,7 +26,10 @@ impl<'a> ExpressionTreeWalker<'a, ExpressionFormatter> {
             PrecedenceGroup => ("prec(", ")"),
             CompoundTerm => ("term(", ")"),
             Parentheses => ("(", ")"),
+            SquareBrackets => ("[", "]"),
             CurlyBraces => ("{ ", " }"),
+            Interpolation => ("#{", "}"),
+            InterpolatedString => ("\"", "\""),
             Source => ("source{ ", " }"),
             Root => ("root{ ", " }"),
         }
@@ -71,6 +74,9 @@ impl<'a> fmt::Display for ExpressionTreeWalker<'a, ExpressionFormatter> {
                     }
                 }
                 Close(..) | CloseBlock(..) => unreachable!(),
+                InfixOperator(INTERPOLATE) => {
+                    write!(f, "{}{}", self.left_expression(), self.right_expression())
+                }
                 InfixOperator(SEMICOLON) => write!(
                     f,
                     "{}{} {}",
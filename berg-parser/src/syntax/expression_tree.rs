@@ -5,6 +5,7 @@ use std::ops::RangeInclusive;
 use super::ast::{Ast, AstIndex, OperandPosition};
 use super::bytes::ByteRange;
 use super::expression_formatter::{ExpressionFormatter, ExpressionTreeFormatter};
+use super::identifiers::COMMA;
 use super::source_reconstruction::SourceReconstruction;
 use super::token::{ExpressionBoundary, ExpressionToken, Fixity, OperatorToken, Token};
 use Fixity::*;
@@ -42,6 +43,16 @@ pub enum ExpressionPosition {
     LeftRight,
     RightLeft,
     RightRight,
+    ///
+    /// An element of the left operand, when it is a tuple like `(a, b, c)`.
+    /// If it isn't, this is the whole left operand.
+    ///
+    LeftElement(usize),
+    ///
+    /// An element of the right operand, when it is a tuple like `(a, b, c)`.
+    /// If it isn't, this is the whole right operand.
+    ///
+    RightElement(usize),
 }
 
 impl<'a, Context: Copy + Clone + fmt::Debug> fmt::Debug for ExpressionTreeWalker<'a, Context> {
@@ -268,7 +279,11 @@ impl<'a> AstExpressionTree<'a> {
         }
     }
 
-    fn skip_implicit_groups(self) -> Self {
+    ///
+    /// Skips past any groups that aren't in the source (like the precedence
+    /// group around `a*b` in `a*b+c`), to the expression inside them.
+    ///
+    pub fn skip_implicit_groups(self) -> Self {
         let mut result = self;
         while let Token::Expression(ExpressionToken::Open(_, boundary, _)) = result.token() {
             if boundary.is_required() {
@@ -279,6 +294,31 @@ impl<'a> AstExpressionTree<'a> {
         result
     }
 
+    ///
+    /// The element at `index` in a tuple like `(a, b, c)`, or the whole
+    /// expression if it isn't a tuple with that many elements.
+    ///
+    fn tuple_element(self, index: usize) -> Self {
+        let mut expression = self.skip_implicit_groups();
+        if let Token::Expression(ExpressionToken::Open(_, ExpressionBoundary::Parentheses, _)) =
+            expression.token()
+        {
+            expression = expression.inner_expression().skip_implicit_groups();
+        }
+        // Commas are left associative: (a, b), c.
+        let mut elements = vec![];
+        while let Token::Operator(OperatorToken::InfixOperator(COMMA)) = expression.token() {
+            elements.push(expression.right_expression());
+            expression = expression.left_expression().skip_implicit_groups();
+        }
+        if elements.is_empty() {
+            return self;
+        }
+        elements.push(expression);
+        elements.reverse();
+        elements.get(index).copied().unwrap_or(self)
+    }
+
     pub fn at_position(self, position: ExpressionPosition) -> Self {
         use ExpressionPosition::*;
         let expression = self.skip_implicit_groups();
@@ -302,6 +342,8 @@ impl<'a> AstExpressionTree<'a> {
                 .right_expression()
                 .skip_implicit_groups()
                 .right_expression(),
+            LeftElement(index) => expression.left_expression().tuple_element(index),
+            RightElement(index) => expression.right_expression().tuple_element(index),
         };
         result.skip_implicit_groups()
     }
@@ -520,6 +562,10 @@ impl ExpressionPosition {
             | (LeftRight, _)
             | (RightLeft, _)
             | (RightRight, _)
+            | (LeftElement(_), _)
+            | (RightElement(_), _)
+            | (_, LeftElement(_))
+            | (_, RightElement(_))
             | (_, LeftLeft)
             | (_, LeftRight)
             | (_, RightLeft)
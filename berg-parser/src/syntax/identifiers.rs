@@ -3,7 +3,7 @@ use std::num::NonZeroU32;
 use std::ops::Range;
 use string_interner::{backend::StringBackend, StringInterner, Symbol};
 
-#[derive(Copy, Clone, PartialEq, Eq, Ord, PartialOrd)]
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Ord, PartialOrd)]
 pub struct IdentifierIndex(NonZeroU32);
 
 macro_rules! identifiers {
@@ -33,15 +33,21 @@ identifiers! {
     CLOSE_PAREN = ")",
     OPEN_CURLY = "{",
     CLOSE_CURLY = "}",
+    OPEN_SQUARE = "[",
+    CLOSE_SQUARE = "]",
     SEMICOLON = ";",
     NEWLINE_SEQUENCE = "<newline sequence>",
     COLON = ":",
     DOT = ".",
     COMMA = ",",
+    FAT_ARROW = "=>",
     IMMEDIATELY_FOLLOWED_BY = "<immediately followed by>",
     FOLLOWED_BY = "<followed by>",
     APPLY = "<apply>",
+    INDEX = "<index>",
+    INTERPOLATE = "<interpolate>",
     EMPTY_STRING = "",
+    UNDERSCORE = "_",
 
     AND_AND = "&&",
     OR_OR = "||",
@@ -54,6 +60,11 @@ identifiers! {
     LESS_THAN = "<",
     GREATER_EQUAL = ">=",
     LESS_EQUAL = "<=",
+    COMPARE = "<=>",
+
+    DOT_DOT = "..",
+    DOT_DOT_DOT = "...",
+    IN = "in",
 
     STAR = "*",
     SLASH = "/",
@@ -76,6 +87,8 @@ identifiers! {
     CATCH = "catch",
     FINALLY = "finally",
     THROW = "throw",
+    MATCH = "match",
+    ARGS = "args",
 
     ERROR_CODE = "CompilerErrorCode",
 }
@@ -154,5 +167,5 @@ impl fmt::Debug for IdentifierIndex {
 #[allow(clippy::upper_case_acronyms)]
 pub mod keywords {
     use crate::fields;
-    fields! { TRUE, FALSE, IF, ELSE, WHILE, FOREACH, BREAK, CONTINUE, TRY, CATCH, FINALLY, THROW, }
+    fields! { TRUE, FALSE, IF, ELSE, WHILE, FOREACH, BREAK, CONTINUE, TRY, CATCH, FINALLY, THROW, MATCH, ARGS, }
 }
@@ -1,12 +1,16 @@
 pub mod ast;
+pub mod ast_binary;
 pub mod ast_expression;
 pub mod block;
 pub mod bytes;
 pub mod char_data;
+pub mod concrete_syntax_tree;
 pub mod expression_formatter;
 pub mod expression_tree;
 pub mod expression_visitor;
 pub mod identifiers;
+pub mod position_index;
 pub mod precedence;
+pub mod source_formatter;
 pub mod source_reconstruction;
 pub mod token;
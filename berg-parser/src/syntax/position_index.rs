@@ -0,0 +1,253 @@
+use berg_util::IndexedVec;
+
+use super::ast::{Ast, AstIndex};
+use super::block::{BlockIndex, FieldIndex};
+use super::bytes::ByteIndex;
+use super::char_data::LineColumn;
+use super::expression_tree::AstExpressionTree;
+use super::identifiers::keywords;
+use super::token::{ExpressionToken, Fixity, OperatorToken, TermToken, Token};
+
+///
+/// Finds what is at a position in the source: the token and innermost
+/// expression there, the expressions and block around it, and the field it
+/// refers to and where that field was declared. This is what hover and
+/// go-to-definition are built on.
+///
+/// Building the index walks the whole AST once; after that, each query is a
+/// binary search plus a walk up the tree.
+///
+pub struct PositionIndex<'a> {
+    ast: &'a Ast,
+    ///
+    /// The parent of each expression, by its root token (`None` for the root
+    /// expression and for close tokens, which aren't expression roots).
+    ///
+    parents: IndexedVec<Option<AstIndex>, AstIndex>,
+    ///
+    /// The token that declared each field (`None` for the root's fields, like
+    /// `true`, which aren't declared in the source).
+    ///
+    declarations: IndexedVec<Option<AstIndex>, FieldIndex>,
+}
+
+///
+/// What is at a position in the source (see [`PositionIndex::at()`]).
+///
+#[derive(Copy, Clone)]
+pub struct ExpressionAt<'a> {
+    index: &'a PositionIndex<'a>,
+    token: AstIndex,
+    expression: AstIndex,
+}
+
+impl<'a> PositionIndex<'a> {
+    pub fn new(ast: &'a Ast) -> Self {
+        let mut parents: IndexedVec<Option<AstIndex>, AstIndex> =
+            vec![None; ast.tokens.len()].into();
+        let mut unvisited = vec![(ast.root_expression(), None)];
+        while let Some((root, parent)) = unvisited.pop() {
+            parents[root] = parent;
+            let expression = AstExpressionTree::new(ast, root);
+            let children = match expression.token().fixity() {
+                Fixity::Term => vec![],
+                Fixity::Prefix => vec![expression.right_expression()],
+                Fixity::Postfix => vec![expression.left_expression()],
+                Fixity::Infix => vec![expression.left_expression(), expression.right_expression()],
+                Fixity::Open => vec![expression.inner_expression()],
+                Fixity::Close => unreachable!(),
+            };
+            unvisited.extend(
+                children
+                    .into_iter()
+                    .map(|child| (child.root_index(), Some(root))),
+            );
+        }
+
+        // The binder creates a field at the first reference to it, which is
+        // its declaration (`:a`, `a: 1` or `a = 1`). The root's fields come
+        // first, and are never declared.
+        let mut declarations: IndexedVec<Option<AstIndex>, FieldIndex> =
+            vec![None; ast.fields.len()].into();
+        for (index, token) in ast.tokens.as_raw_slice().iter().enumerate() {
+            if let Token::Expression(ExpressionToken::Term(TermToken::FieldReference(field))) =
+                token
+                && *field >= keywords::FIELD_NAMES.len()
+            {
+                declarations[*field].get_or_insert(index.into());
+            }
+        }
+
+        PositionIndex {
+            ast,
+            parents,
+            declarations,
+        }
+    }
+
+    pub fn ast(&self) -> &'a Ast {
+        self.ast
+    }
+
+    ///
+    /// What is at the byte. If the byte is in a token, that is the token;
+    /// otherwise (in whitespace or a comment), it is the innermost expression
+    /// around the byte. Returns `None` if the byte is outside all expressions.
+    ///
+    pub fn at(&'a self, index: ByteIndex) -> Option<ExpressionAt<'a>> {
+        let ranges = self.ast.token_ranges.as_raw_slice();
+        let after = ranges.partition_point(|range| range.start <= index);
+        let mut before = None;
+        for token in (0..after).rev() {
+            let range = &ranges[token];
+            if range.start < range.end && index < range.end {
+                let token = AstIndex::from(token);
+                return Some(ExpressionAt {
+                    index: self,
+                    token,
+                    expression: self.expression_of(token),
+                });
+            }
+            if range.start < range.end {
+                before = Some(AstIndex::from(token));
+                break;
+            }
+        }
+
+        // Not in a token: find the innermost expression around the byte.
+        let mut expression = self.expression_of(before?);
+        loop {
+            let range = AstExpressionTree::new(self.ast, expression).byte_range();
+            if range.start <= index && index < range.end {
+                return Some(ExpressionAt {
+                    index: self,
+                    token: expression,
+                    expression,
+                });
+            }
+            expression = self.parents[expression]?;
+        }
+    }
+
+    ///
+    /// What is at the line and column (see [`PositionIndex::at()`]).
+    ///
+    pub fn at_location(&'a self, location: LineColumn) -> Option<ExpressionAt<'a>> {
+        self.at(self.ast.byte_index(location))
+    }
+
+    ///
+    /// The parent of the expression, or `None` if it is the root.
+    ///
+    pub fn parent(&self, expression: AstIndex) -> Option<AstExpressionTree<'a>> {
+        self.parents[self.expression_of(expression)]
+            .map(|parent| AstExpressionTree::new(self.ast, parent))
+    }
+
+    ///
+    /// The token that declared the field, or `None` if it wasn't declared in
+    /// the source (like `true`).
+    ///
+    pub fn declaration(&self, field: FieldIndex) -> Option<AstIndex> {
+        self.declarations[field]
+    }
+
+    ///
+    /// Every token that refers to the field, in order, including its
+    /// declaration.
+    ///
+    pub fn references(&self, field: FieldIndex) -> impl Iterator<Item = AstIndex> + 'a {
+        let start = self.declarations[field].unwrap_or_else(|| self.ast.tokens.first_index());
+        let tokens = &self.ast.tokens.as_raw_slice()[usize::from(start)..];
+        tokens
+            .iter()
+            .enumerate()
+            .filter_map(move |(offset, token)| match token {
+                Token::Expression(ExpressionToken::Term(TermToken::FieldReference(reference)))
+                    if *reference == field =>
+                {
+                    Some(start + offset)
+                }
+                _ => None,
+            })
+    }
+
+    ///
+    /// The root of the expression the token belongs to: the token itself,
+    /// or the open token if it is a close token.
+    ///
+    fn expression_of(&self, token: AstIndex) -> AstIndex {
+        AstExpressionTree::new(self.ast, token).open_operator()
+    }
+}
+
+impl<'a> ExpressionAt<'a> {
+    ///
+    /// The token at the position (in whitespace, the root token of the
+    /// expression around it).
+    ///
+    pub fn token(&self) -> AstIndex {
+        self.token
+    }
+
+    ///
+    /// The innermost expression at the position. For an operator, this is the
+    /// operation (`a + b` for the `+`); for a close token, it is the group.
+    ///
+    pub fn expression(&self) -> AstExpressionTree<'a> {
+        AstExpressionTree::new(self.index.ast, self.expression)
+    }
+
+    ///
+    /// The expressions around this one, innermost first, up to the root.
+    /// These include groups that aren't in the source, like precedence groups
+    /// (skip them with [`AstExpressionTree::skip_implicit_groups()`]).
+    ///
+    pub fn parents(&self) -> impl Iterator<Item = AstExpressionTree<'a>> + 'a {
+        let index = self.index;
+        let mut expression = self.expression;
+        std::iter::from_fn(move || {
+            expression = index.parents[expression]?;
+            Some(AstExpressionTree::new(index.ast, expression))
+        })
+    }
+
+    ///
+    /// The innermost block the expression is in (or is, if it is a block).
+    ///
+    pub fn block(&self) -> BlockIndex {
+        let ast = self.index.ast;
+        std::iter::once(self.expression())
+            .chain(self.parents())
+            .find_map(|expression| match expression.token() {
+                Token::Expression(ExpressionToken::Open(_, boundary, _)) if boundary.is_block() => {
+                    match ast.tokens[expression.close_operator()] {
+                        Token::Operator(OperatorToken::CloseBlock(block, _)) => Some(block),
+                        _ => None,
+                    }
+                }
+                _ => None,
+            })
+            .expect("every expression is in the source block")
+    }
+
+    ///
+    /// The field the token refers to, if it is a field reference.
+    ///
+    pub fn field(&self) -> Option<FieldIndex> {
+        match self.index.ast.tokens[self.token] {
+            Token::Expression(ExpressionToken::Term(TermToken::FieldReference(field))) => {
+                Some(field)
+            }
+            _ => None,
+        }
+    }
+
+    ///
+    /// The token that declared the field the token refers to (see
+    /// [`PositionIndex::declaration()`]).
+    ///
+    pub fn declaration(&self) -> Option<AstIndex> {
+        self.field().and_then(|field| self.index.declaration(field))
+    }
+}
@@ -1,12 +1,21 @@
+use string_interner::backend::StringBackend;
+use string_interner::StringInterner;
 use Precedence::*;
 
-use super::{identifiers::IdentifierIndex, token::OperatorToken};
+use super::{
+    identifiers::{self, IdentifierIndex},
+    token::{Fixity, OperatorToken, Token},
+};
 
+///
+/// The built-in precedence levels, from tightest to loosest.
+///
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub enum Precedence {
     Dot,
     TimesDivide,
     PlusMinus,
+    Range,
     Comparison,
     And,
     Or,
@@ -14,6 +23,7 @@ pub enum Precedence {
     Assign,
     ColonDeclaration,
     FollowedBy,
+    MatchArm,
     SemicolonSequence,
     NewlineSequence,
 }
@@ -30,17 +40,18 @@ impl From<IdentifierIndex> for Precedence {
     fn from(from: IdentifierIndex) -> Self {
         use super::identifiers::*;
         match from {
-            DOT => Dot,
+            DOT | INDEX => Dot,
             STAR | SLASH => TimesDivide,
             PLUS | DASH => PlusMinus,
-            EQUAL_TO | NOT_EQUAL_TO | GREATER_THAN | GREATER_EQUAL | LESS_THAN | LESS_EQUAL => {
-                Comparison
-            }
+            DOT_DOT | DOT_DOT_DOT => Range,
+            EQUAL_TO | NOT_EQUAL_TO | GREATER_THAN | GREATER_EQUAL | LESS_THAN | LESS_EQUAL
+            | COMPARE | IN => Comparison,
             AND_AND => And,
             OR_OR => Or,
             COMMA => CommaSequence,
             COLON => ColonDeclaration,
             FOLLOWED_BY | IMMEDIATELY_FOLLOWED_BY => FollowedBy,
+            FAT_ARROW => MatchArm,
             SEMICOLON => SemicolonSequence,
             NEWLINE_SEQUENCE => NewlineSequence,
             _ => DEFAULT_PRECEDENCE,
@@ -54,33 +65,59 @@ impl Precedence {
             Dot => false,
             TimesDivide => matches!(right, Dot),
             PlusMinus => matches!(right, Dot | TimesDivide),
-            Comparison => matches!(right, Dot | TimesDivide | PlusMinus),
-            And => matches!(right, Dot | TimesDivide | PlusMinus | Comparison),
-            Or => matches!(right, Dot | TimesDivide | PlusMinus | Comparison | And),
-            CommaSequence => matches!(right, Dot | TimesDivide | PlusMinus | Comparison | And | Or),
+            Range => matches!(right, Dot | TimesDivide | PlusMinus),
+            Comparison => matches!(right, Dot | TimesDivide | PlusMinus | Range),
+            And => matches!(right, Dot | TimesDivide | PlusMinus | Range | Comparison),
+            Or => matches!(right, Dot | TimesDivide | PlusMinus | Range | Comparison | And),
+            CommaSequence => matches!(
+                right,
+                Dot | TimesDivide | PlusMinus | Range | Comparison | And | Or
+            ),
             Assign => matches!(
                 right,
-                Dot | TimesDivide | PlusMinus | Comparison | And | Or | CommaSequence
+                Dot | TimesDivide | PlusMinus | Range | Comparison | And | Or | CommaSequence
             ),
             ColonDeclaration => matches!(
                 right,
-                Dot | TimesDivide | PlusMinus | Comparison | And | Or | CommaSequence | Assign
+                Dot | TimesDivide
+                    | PlusMinus
+                    | Range
+                    | Comparison
+                    | And
+                    | Or
+                    | CommaSequence
+                    | Assign
             ),
             FollowedBy => matches!(
                 right,
                 Dot | TimesDivide
                     | PlusMinus
+                    | Range
+                    | Comparison
+                    | And
+                    | Or
+                    | CommaSequence
+                    | Assign
+                    | ColonDeclaration
+            ),
+            MatchArm => matches!(
+                right,
+                Dot | TimesDivide
+                    | PlusMinus
+                    | Range
                     | Comparison
                     | And
                     | Or
                     | CommaSequence
                     | Assign
                     | ColonDeclaration
+                    | FollowedBy
             ),
             SemicolonSequence => matches!(
                 right,
                 Dot | TimesDivide
                     | PlusMinus
+                    | Range
                     | Comparison
                     | And
                     | Or
@@ -88,11 +125,13 @@ impl Precedence {
                     | Assign
                     | ColonDeclaration
                     | FollowedBy
+                    | MatchArm
             ),
             NewlineSequence => matches!(
                 right,
                 Dot | TimesDivide
                     | PlusMinus
+                    | Range
                     | Comparison
                     | And
                     | Or
@@ -100,6 +139,7 @@ impl Precedence {
                     | Assign
                     | ColonDeclaration
                     | FollowedBy
+                    | MatchArm
                     | SemicolonSequence
             ),
         }
@@ -117,3 +157,225 @@ impl From<OperatorToken> for Precedence {
         }
     }
 }
+
+///
+/// Which side an operator groups toward when it is next to another operator
+/// with the same precedence: `a - b - c` is `(a - b) - c` because `-` is left
+/// associative.
+///
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Associativity {
+    Left,
+    Right,
+}
+
+///
+/// The precedence of a declared operator, relative to one of the built-in
+/// levels.
+///
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum RelativePrecedence {
+    ///
+    /// Binds tighter than the level, but looser than the next tighter one.
+    ///
+    TighterThan(Precedence),
+    ///
+    /// The same precedence as the level.
+    ///
+    SameAs(Precedence),
+    ///
+    /// Binds looser than the level, but tighter than the next looser one.
+    ///
+    LooserThan(Precedence),
+}
+
+///
+/// How a declared operator groups with the operators around it.
+///
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct OperatorDeclaration {
+    pub precedence: RelativePrecedence,
+    pub associativity: Associativity,
+}
+
+///
+/// The infix operators declared for a parse, on top of the built-in ones.
+///
+/// Declaring an operator decides how it groups with the operators around it;
+/// what it *does* is up to the values it is used on (the evaluator hands it
+/// to their `infix()` like any other operator). An operator that isn't
+/// declared has [`Precedence::default()`] and is left associative. Built-in
+/// operators can be declared too, which changes how they group.
+///
+/// The operators are interned before the source is parsed, so an operator
+/// has the same [`IdentifierIndex`] in every AST parsed with the same table.
+///
+/// The table also holds the names of declared text blocks (see
+/// [`OperatorTable::declare_text_block()`]), since they change how the source
+/// is parsed in the same way.
+///
+#[derive(Debug, Clone)]
+pub struct OperatorTable {
+    identifiers: StringInterner<StringBackend<IdentifierIndex>>,
+    declarations: Vec<(IdentifierIndex, OperatorDeclaration)>,
+    text_blocks: Vec<IdentifierIndex>,
+}
+
+impl Default for OperatorTable {
+    fn default() -> Self {
+        OperatorTable {
+            identifiers: identifiers::intern_all(),
+            declarations: Default::default(),
+            text_blocks: Default::default(),
+        }
+    }
+}
+
+impl PartialEq for OperatorTable {
+    fn eq(&self, other: &Self) -> bool {
+        self.declarations == other.declarations
+            && self.text_blocks == other.text_blocks
+            && self.identifiers.iter().eq(other.identifiers.iter())
+    }
+}
+
+impl OperatorTable {
+    ///
+    /// Declares an infix operator (or redeclares one), returning the
+    /// identifier it will have in the AST.
+    ///
+    pub fn declare(
+        &mut self,
+        name: &str,
+        precedence: RelativePrecedence,
+        associativity: Associativity,
+    ) -> IdentifierIndex {
+        let identifier = self.identifiers.get_or_intern(name);
+        let declaration = OperatorDeclaration {
+            precedence,
+            associativity,
+        };
+        match self.declaration_mut(identifier) {
+            Some(existing) => *existing = declaration,
+            None => self.declarations.push((identifier, declaration)),
+        }
+        identifier
+    }
+
+    ///
+    /// Declares a text block: `name:` followed by an indented block is kept as
+    /// text (a [`TermToken::TextBlock`]) instead of being parsed, so a DSL can
+    /// handle it. Returns the identifier `name` will have in the AST.
+    ///
+    /// [`TermToken::TextBlock`]: super::token::TermToken::TextBlock
+    ///
+    pub fn declare_text_block(&mut self, name: &str) -> IdentifierIndex {
+        let identifier = self.identifiers.get_or_intern(name);
+        if !self.is_text_block(identifier) {
+            self.text_blocks.push(identifier);
+        }
+        identifier
+    }
+
+    pub fn is_text_block(&self, identifier: IdentifierIndex) -> bool {
+        self.text_blocks.contains(&identifier)
+    }
+
+    ///
+    /// The declared text block names, in the order they were declared.
+    ///
+    pub fn text_blocks(&self) -> impl ExactSizeIterator<Item = &str> {
+        self.text_blocks
+            .iter()
+            .map(move |&identifier| self.identifiers.resolve(identifier).unwrap())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.declarations.is_empty() && self.text_blocks.is_empty()
+    }
+
+    ///
+    /// The declared operators, in the order they were first declared.
+    ///
+    pub fn declarations(&self) -> impl ExactSizeIterator<Item = (&str, OperatorDeclaration)> {
+        self.declarations
+            .iter()
+            .map(move |&(identifier, declaration)| {
+                (self.identifiers.resolve(identifier).unwrap(), declaration)
+            })
+    }
+
+    pub fn declaration(&self, identifier: IdentifierIndex) -> Option<OperatorDeclaration> {
+        self.declarations
+            .iter()
+            .find(|(declared, _)| *declared == identifier)
+            .map(|&(_, declaration)| declaration)
+    }
+
+    ///
+    /// The identifiers a parse with these operators starts with: the built-in
+    /// ones, then the declared ones.
+    ///
+    pub fn identifiers(&self) -> &StringInterner<StringBackend<IdentifierIndex>> {
+        &self.identifiers
+    }
+
+    ///
+    /// Whether `left` takes `right` as its right child, given the declared
+    /// operators. See [`OperatorToken::takes_right_child()`].
+    ///
+    /// When two infix operators have the same precedence, the left one's
+    /// associativity decides: a left associative operator lets its right
+    /// neighbor take it as a left child, and a right associative one takes
+    /// its right neighbor as a child.
+    ///
+    pub fn takes_right_child(&self, left: OperatorToken, right: impl Into<Token>) -> bool {
+        match right.into() {
+            Token::Operator(right)
+                if left.fixity() == Fixity::Infix && right.fixity() == Fixity::Infix =>
+            {
+                let (left_rank, associativity) = self.binding(left);
+                let (right_rank, _) = self.binding(right);
+                right_rank < left_rank
+                    || (right_rank == left_rank && associativity == Associativity::Right)
+            }
+            right => left.takes_right_child(right),
+        }
+    }
+
+    fn declaration_mut(&mut self, identifier: IdentifierIndex) -> Option<&mut OperatorDeclaration> {
+        self.declarations
+            .iter_mut()
+            .find(|(declared, _)| *declared == identifier)
+            .map(|(_, declaration)| declaration)
+    }
+
+    // The rank (lower binds tighter) and associativity of an infix operator.
+    fn binding(&self, operator: OperatorToken) -> (u32, Associativity) {
+        match operator {
+            OperatorToken::InfixOperator(identifier) => match self.declaration(identifier) {
+                Some(declaration) => (declaration.precedence.rank(), declaration.associativity),
+                None => (Precedence::from(identifier).rank(), Associativity::Left),
+            },
+            _ => (Precedence::from(operator).rank(), Associativity::Left),
+        }
+    }
+}
+
+impl Precedence {
+    // Leaves room for a rank just tighter and just looser than each level.
+    fn rank(self) -> u32 {
+        self as u32 * 3 + 1
+    }
+}
+
+impl RelativePrecedence {
+    fn rank(self) -> u32 {
+        use RelativePrecedence::*;
+        match self {
+            TighterThan(level) => level.rank() - 1,
+            SameAs(level) => level.rank(),
+            LooserThan(level) => level.rank() + 1,
+        }
+    }
+}
@@ -0,0 +1,290 @@
+use std::cmp::min;
+use super::ast::Ast;
+use super::concrete_syntax_tree::{ConcreteSyntaxTree, CstToken, Trivia};
+use super::identifiers::{COLON, COMMA, SEMICOLON};
+use super::token::{
+    ErrorTermError, ExpressionBoundary, ExpressionToken, OperatorToken, TermToken, Token,
+};
+
+///
+/// The number of spaces for each level of indent.
+///
+pub const INDENT_WIDTH: usize = 4;
+
+///
+/// Writes an AST's source in canonical layout.
+///
+/// Tokens and comments are written exactly as they were; only the whitespace
+/// between them changes:
+///
+/// - Runs of space and tabs inside a line become a single space. Where there was
+///   no space (as in compact terms like `a+b`), none is added.
+/// - There is no space before `;` or `,`, and one space after `;`, `,` and
+///   infix `:` (unless the line ends there). Trailing separators stay put.
+/// - There is no space just inside `(` and `)`.
+/// - Comments after code on a line are set off by a single space, as are block
+///   comments before code (`#[ a ]# b`) and between block comments.
+/// - Lines are indented [`INDENT_WIDTH`] spaces per level of indent. A line
+///   that undents to somewhere between two levels is indented one space less
+///   than the level it closed, so it still closes it.
+/// - Line endings become `\n`, trailing whitespace is removed, and runs of blank
+///   lines become a single blank line. Blank lines at the start and end of the
+///   source are removed, and the source ends with a single `\n`.
+///
+/// This does not check whether the result parses the same way; see
+/// [`format_source()`](crate::format_source) for that.
+///
+pub struct SourceFormatter<'a> {
+    tree: ConcreteSyntaxTree<'a>,
+}
+
+///
+/// Writes the formatted source.
+///
+struct SourceWriter<'p, 'a> {
+    tree: &'p ConcreteSyntaxTree<'a>,
+    buffer: Vec<u8>,
+    ///
+    /// The number of blank lines since the last line we wrote.
+    ///
+    blank_lines: usize,
+    ///
+    /// The original and formatted indent of each indented block we are in,
+    /// the same blocks the parser had open.
+    ///
+    indents: Vec<(usize, usize)>,
+    ///
+    /// The original and formatted indent of the last line the parser saw
+    /// (lines with only block comments don't count).
+    ///
+    line_indent: (usize, usize),
+}
+
+impl<'a> SourceFormatter<'a> {
+    pub fn new(ast: &'a Ast) -> Self {
+        SourceFormatter {
+            tree: ast.concrete_syntax_tree(),
+        }
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut writer = SourceWriter {
+            tree: &self.tree,
+            buffer: Vec::new(),
+            blank_lines: 0,
+            indents: vec![(0, 0)],
+            line_indent: (0, 0),
+        };
+        let last = self.tree.ast().tokens.last_index();
+        let mut prev = None;
+        let mut gap = Vec::new();
+        let mut opens_block = false;
+        for token in self.tree.tokens() {
+            let range = token.byte_range();
+            if range.start == range.end && token.index() != last {
+                // The block opens at the next token, on the line it starts.
+                opens_block |= matches!(
+                    token.token(),
+                    Token::Expression(ExpressionToken::Open(
+                        _,
+                        ExpressionBoundary::IndentedBlock | ExpressionBoundary::IndentedExpression,
+                        _
+                    ))
+                );
+                continue;
+            }
+            gap.extend_from_slice(token.leading_trivia());
+            if token.index() == last {
+                writer.write_gap(prev, &gap, None);
+            } else {
+                writer.write_gap(prev, &gap, Some(token));
+                if opens_block {
+                    writer.indents.push(writer.line_indent);
+                    opens_block = false;
+                }
+                writer.buffer.extend_from_slice(&token.bytes());
+            }
+            gap = token.trailing_trivia().to_vec();
+            prev = Some(token);
+        }
+        if !writer.buffer.is_empty() {
+            writer.buffer.push(b'\n');
+        }
+        writer.buffer
+    }
+}
+
+impl<'p, 'a> SourceWriter<'p, 'a> {
+    ///
+    /// Writes the whitespace and comments between two tokens.
+    ///
+    /// `prev` is `None` at the start of the source, and `next` is `None` at the
+    /// end.
+    ///
+    fn write_gap(
+        &mut self,
+        prev: Option<CstToken<'a>>,
+        gap: &[Trivia],
+        next: Option<CstToken<'a>>,
+    ) {
+        let lines: Vec<&[Trivia]> = gap
+            .split(|trivia| matches!(trivia, Trivia::Newline(_)))
+            .collect();
+        for (line_index, line) in lines.iter().enumerate() {
+            let is_last_line = line_index == lines.len() - 1;
+            let comments: Vec<&Trivia> = line
+                .iter()
+                .filter(|trivia| matches!(trivia, Trivia::Comment(_)))
+                .collect();
+            match prev {
+                // The rest of the line prev is on.
+                Some(prev) if line_index == 0 => {
+                    for comment in &comments {
+                        // The rest of an unclosed #[ stays right after it.
+                        if !matches!(
+                            prev.token(),
+                            Token::Expression(ExpressionToken::Term(TermToken::ErrorTerm(
+                                ErrorTermError::UnclosedBlockComment,
+                                _
+                            )))
+                        ) {
+                            self.buffer.push(b' ');
+                        }
+                        self.write_comment(comment);
+                    }
+                    if let (true, Some(next)) = (is_last_line, next)
+                        && (!comments.is_empty() || Self::has_space(prev, next, !line.is_empty()))
+                    {
+                        self.buffer.push(b' ');
+                    }
+                }
+                // A line by itself, or the start of next's line.
+                _ => {
+                    if comments.is_empty() && (!is_last_line || next.is_none()) {
+                        if !self.buffer.is_empty() {
+                            self.blank_lines += 1;
+                        }
+                        continue;
+                    }
+                    let starts_next = is_last_line && next.is_some();
+                    let is_parsed = starts_next
+                        || comments
+                            .iter()
+                            .any(|comment| !self.is_block_comment(comment));
+                    self.write_line_start(line, is_parsed, next.filter(|_| starts_next));
+                    for (index, comment) in comments.iter().enumerate() {
+                        if index > 0 {
+                            self.buffer.push(b' ');
+                        }
+                        self.write_comment(comment);
+                    }
+                    if is_last_line && next.is_some() && !comments.is_empty() {
+                        self.buffer.push(b' ');
+                    }
+                }
+            }
+        }
+    }
+
+    ///
+    /// Ends the previous line (keeping up to one blank line) and writes the
+    /// indent of a new line.
+    ///
+    /// `is_parsed` is false for lines the parser skips (blank but for block
+    /// comments), which don't open or close blocks. `next` is the token that
+    /// starts the line, if one does.
+    ///
+    fn write_line_start(&mut self, line: &[Trivia], is_parsed: bool, next: Option<CstToken<'a>>) {
+        if !self.buffer.is_empty() {
+            self.buffer.push(b'\n');
+            if self.blank_lines > 0 {
+                self.buffer.push(b'\n');
+            }
+        }
+        self.blank_lines = 0;
+
+        // An indent the parser couldn't compare is an error term, and the parser
+        // treated the line as indented like the last one. Keep it as it was.
+        if let Some(next) = next
+            && matches!(
+                next.token(),
+                Token::Expression(ExpressionToken::Term(TermToken::ErrorTerm(
+                    ErrorTermError::InconsistentIndentCharacters,
+                    _
+                )))
+            )
+        {
+            return;
+        }
+
+        // Map the original indent onto the formatted indent, closing the same
+        // blocks the parser closed.
+        let indent_len: usize = line
+            .iter()
+            .take_while(|trivia| matches!(trivia, Trivia::Space(_) | Trivia::Whitespace(_)))
+            .map(|trivia| self.tree.trivia_bytes(trivia).len())
+            .sum();
+        let level = self
+            .indents
+            .iter()
+            .rposition(|&(level_len, _)| level_len <= indent_len)
+            .unwrap();
+        let (level_len, level_indent) = self.indents[level];
+        let formatted_indent = if indent_len == level_len {
+            level_indent
+        } else {
+            match self.indents.get(level + 1) {
+                Some(&(_, closed_indent)) if is_parsed => {
+                    min(level_indent + INDENT_WIDTH, closed_indent - 1)
+                }
+                _ => level_indent + INDENT_WIDTH,
+            }
+        };
+        if is_parsed {
+            self.indents.truncate(level + 1);
+            self.line_indent = (indent_len, formatted_indent);
+        }
+        self.buffer
+            .resize(self.buffer.len() + formatted_indent, b' ');
+    }
+
+    ///
+    /// Whether a comment is a block comment (`#[ ... ]#`).
+    ///
+    fn is_block_comment(&self, comment: &Trivia) -> bool {
+        self.tree.trivia_bytes(comment).starts_with(b"#[")
+    }
+
+    ///
+    /// Writes a comment without its trailing whitespace (which, in a block
+    /// comment that never closes, includes the line endings at the end).
+    ///
+    fn write_comment(&mut self, comment: &Trivia) {
+        let bytes = self.tree.trivia_bytes(comment);
+        let len = bytes
+            .iter()
+            .rposition(|byte| !matches!(byte, b' ' | b'\t' | b'\r' | b'\n'))
+            .map_or(0, |index| index + 1);
+        self.buffer.extend_from_slice(&bytes[..len]);
+    }
+
+    ///
+    /// Whether there is a space between two tokens on the same line.
+    ///
+    fn has_space(prev: CstToken, next: CstToken, had_space: bool) -> bool {
+        use ExpressionBoundary::{Parentheses, SquareBrackets};
+        use ExpressionToken::Open;
+        use OperatorToken::{Close, InfixOperator};
+        use Token::*;
+        match (prev.token(), next.token()) {
+            // a; b, a, b
+            (_, Operator(InfixOperator(SEMICOLON | COMMA))) => false,
+            // (a), f(b, c), a[b]
+            (Expression(Open(_, Parentheses | SquareBrackets, _)), _)
+            | (_, Operator(Close(_, Parentheses | SquareBrackets))) => false,
+            // a: b
+            (Operator(InfixOperator(SEMICOLON | COMMA | COLON)), _) => true,
+            _ => had_space,
+        }
+    }
+}
@@ -175,7 +175,9 @@ impl<'a> SourceReconstructionIterator<'a> {
             // Skip empty tokens
             if *end > *start {
                 let token = self.ast.tokens[self.ast_index - 1];
-                return self.truncate(*start, token.original_bytes(self.ast));
+                let bytes = token.original_bytes(self.ast);
+                let bytes = self.ast.char_data.with_digit_separators(bytes, *start);
+                return self.truncate(*start, bytes);
             }
         }
         None
@@ -183,18 +185,17 @@ impl<'a> SourceReconstructionIterator<'a> {
 
     fn next_comment(&mut self) -> Option<Cow<'a, [u8]>> {
         if let Some((comment, comment_start)) = self.ast.char_data.comments.get(self.comment_index)
+            && *comment_start <= self.index
         {
-            if *comment_start <= self.index {
-                self.comment_index += 1;
-                assert!(
-                    *comment_start + comment.len() > self.index,
-                    "comment {:?} at {} got skipped somehow! Current index is {}.",
-                    comment,
-                    comment_start,
-                    self.index
-                );
-                return self.truncate(*comment_start, comment);
-            }
+            self.comment_index += 1;
+            assert!(
+                *comment_start + comment.len() > self.index,
+                "comment {:?} at {} got skipped somehow! Current index is {}.",
+                comment,
+                comment_start,
+                self.index
+            );
+            return self.truncate(*comment_start, comment);
         }
 
         None
@@ -236,19 +237,18 @@ impl<'a> SourceReconstructionIterator<'a> {
             .char_data
             .whitespace_ranges
             .get(self.whitespace_index)
+            && *whitespace_start <= self.index
         {
-            if *whitespace_start <= self.index {
-                self.whitespace_index += 1;
-                let whitespace_string = self.ast.whitespace_string(*whitespace);
-                assert!(
-                    *whitespace_start + whitespace_string.len() > self.index,
-                    "whitespace {:?} at {} got skipped somehow! Current index is {}.",
-                    whitespace_string,
-                    whitespace_start,
-                    self.index
-                );
-                return self.truncate(*whitespace_start, whitespace_string.as_bytes());
-            }
+            self.whitespace_index += 1;
+            let whitespace_string = self.ast.whitespace_string(*whitespace);
+            assert!(
+                *whitespace_start + whitespace_string.len() > self.index,
+                "whitespace {:?} at {} got skipped somehow! Current index is {}.",
+                whitespace_string,
+                whitespace_start,
+                self.index
+            );
+            return self.truncate(*whitespace_start, whitespace_string.as_bytes());
         }
 
         None
@@ -280,31 +280,30 @@ impl<'a> SourceReconstructionIterator<'a> {
 
 fn find_ast_index(ast: &Ast, index: ByteIndex) -> AstIndex {
     // Get the first token that ends after the index and is non-empty
-    let ast_index = ast
-        .token_ranges
+    // (tokens are in order, so their ends are too).
+    let token_ranges = ast.token_ranges.as_raw_slice();
+    let first = token_ranges.partition_point(|range| range.end <= index);
+    let ast_index = token_ranges[first..]
         .iter()
-        .position(|range| range.end > index && range.end > range.start);
-    ast_index.unwrap_or_else(|| ast.token_ranges.len().into())
+        .position(|range| range.end > range.start)
+        .map(|offset| first + offset);
+    ast_index.unwrap_or(token_ranges.len()).into()
 }
 
 fn find_comment_index(ast: &Ast, index: ByteIndex) -> usize {
     // Get the first comment that ends after the index
-    let comment_index = ast
-        .char_data
+    ast.char_data
         .comments
-        .iter()
-        .position(|(_, start)| *start >= index);
-    comment_index.unwrap_or(ast.char_data.comments.len())
+        .partition_point(|(comment, start)| *start + comment.len() <= index)
 }
 
 fn find_whitespace_index(ast: &Ast, index: ByteIndex) -> usize {
     // Get the first whitespace that starts *at* or *after* the given index.
-    if let Some(next_whitespace) = ast
+    let next_whitespace = ast
         .char_data
         .whitespace_ranges
-        .iter()
-        .position(|(_, start)| *start >= index)
-    {
+        .partition_point(|(_, start)| *start < index);
+    if next_whitespace < ast.char_data.whitespace_ranges.len() {
         // If there is a whitespace starting *after* the given index, check if the previous one *intersects* the index.
         if next_whitespace > 0 {
             let (whitespace, start) = ast.char_data.whitespace_ranges[next_whitespace - 1];
@@ -163,6 +163,81 @@ pub enum TermToken {
     ///
     IntegerLiteral(LiteralIndex),
     ///
+    /// Hexadecimal integer (`0x1F`).
+    ///
+    /// The [`LiteralIndex`] can be used to look up the actual string, including
+    /// the `0x` prefix, in [`AstData::literals`].
+    ///
+    HexadecimalLiteral(LiteralIndex),
+    ///
+    /// Octal integer (`0o17`).
+    ///
+    /// The [`LiteralIndex`] can be used to look up the actual string, including
+    /// the `0o` prefix, in [`AstData::literals`].
+    ///
+    OctalLiteral(LiteralIndex),
+    ///
+    /// Binary integer (`0b101`).
+    ///
+    /// The [`LiteralIndex`] can be used to look up the actual string, including
+    /// the `0b` prefix, in [`AstData::literals`].
+    ///
+    BinaryLiteral(LiteralIndex),
+    ///
+    /// Float (`12.34`, `12e56` or `12.34E-56`).
+    ///
+    /// The [`LiteralIndex`] can be used to look up the actual string in
+    /// [`AstData::literals`]. It is made of a mantissa (`12`), an optional
+    /// fraction (`.34`) and an optional exponent (`E-56`); at least one of the
+    /// fraction and exponent is always present. The [`FloatParts`] say where
+    /// each part is; use [`FloatParts::split()`] to get them.
+    ///
+    /// # Note
+    ///
+    /// `-1.2` is not a float literal: it is prefix `-` followed by float
+    /// literal `1.2`.
+    ///
+    FloatLiteral(LiteralIndex, FloatParts),
+    ///
+    /// Imaginary number (`12i`, `1.2i` or `12.34e-56i`).
+    ///
+    /// The [`LiteralIndex`] can be used to look up the actual string in
+    /// [`AstData::literals`]. It is an integer or float literal followed by
+    /// `i`, and its [`FloatParts`] split it up the same way (without the `i`).
+    ///
+    ImaginaryLiteral(LiteralIndex, FloatParts),
+    ///
+    /// String (`"abc"` or `"a\"b\nc"`).
+    ///
+    /// The [`LiteralIndex`] can be used to look up the original source of the
+    /// string, including quotes and escapes, in [`AstData::literals`]. Use
+    /// [`Ast::string_value()`] to get the actual string value.
+    ///
+    StringLiteral(LiteralIndex),
+    ///
+    /// A piece of text in an interpolated string (the `Hello ` and `!` in
+    /// `"Hello #{name}!"`).
+    ///
+    /// The [`LiteralIndex`] can be used to look up the original source of the
+    /// text, without quotes but including escapes, in [`AstData::literals`].
+    /// Use [`Ast::string_fragment_value()`] to get the actual string value.
+    ///
+    /// Fragments are always inside an [`ExpressionBoundary::InterpolatedString`],
+    /// joined to the interpolated expressions with
+    /// [`syntax::identifiers::INTERPOLATE`].
+    ///
+    StringFragment(LiteralIndex),
+    ///
+    /// A text block: a declared name, `:` and an indented block that is kept
+    /// as text instead of being parsed (`sql:` followed by an indented query).
+    /// See [`OperatorTable::declare_text_block()`].
+    ///
+    /// The [`LiteralIndex`] can be used to look up the original source,
+    /// from the name to the end of the block, in [`AstData::literals`]. Use
+    /// [`Ast::text_block_parts()`] to split it into the name and text.
+    ///
+    TextBlock(LiteralIndex),
+    ///
     /// A reference to a field.
     ///
     /// The [`FieldIndex`] can be used to look up the field name and publicity
@@ -231,6 +306,15 @@ pub enum ExpressionBoundaryError {
     /// For example, `(1 + 2`.
     ///
     OpenWithoutClose,
+    ///
+    /// Indicates an indented block was closed by a line that closed more than
+    /// two indented blocks at once. Only statement blocks count; indented
+    /// continuations don't.
+    ///
+    /// For example, the block starting at `b` in
+    /// `a\n    b\n        c\n            d\ne`.
+    ///
+    MultipleUndent,
 }
 
 ///
@@ -240,9 +324,12 @@ pub enum ExpressionBoundaryError {
 pub enum ExpressionBoundary {
     PrecedenceGroup,
     CompoundTerm,
+    SquareBrackets,
     Parentheses,
     AutoBlock,
     CurlyBraces,
+    Interpolation,
+    InterpolatedString,
     IndentedExpression,
     IndentedBlock,
     Source,
@@ -259,15 +346,45 @@ pub enum Fixity {
     Close,
 }
 
+///
+/// Where the mantissa, fraction and exponent of a float or imaginary literal
+/// are. `12.34E-56` has a 2-digit mantissa and a 2-digit fraction; the
+/// exponent is everything after the `E`.
+///
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct FloatParts {
+    pub mantissa_len: u16,
+    pub fraction_len: u16,
+}
+
 #[derive(Debug, Copy, Clone, PartialEq)]
 pub enum ErrorTermError {
     IdentifierStartsWithNumber,
     UnsupportedCharacters,
+    UnclosedString,
+    MissingExponent,
+    UppercaseHexadecimalPrefix,
+    UppercaseOctalPrefix,
+    UppercaseBinaryPrefix,
+    DigitOutOfRange,
+    ImaginaryWithUppercaseI,
+    DenormalizedIdentifier,
+    IncompleteGrapheme,
+    IdentifierTooLarge,
+    InconsistentIndentCharacters,
+    UnclosedBlockComment,
+    DoubledDigitSeparator,
+    TrailingDigitSeparator,
+    LeadingDigitSeparator,
+    DigitSeparatorNextToRadixPrefix,
+    MissingRadixDigits,
 }
 
 #[derive(Debug, Copy, Clone, PartialEq)]
 pub enum RawErrorTermError {
     InvalidUtf8,
+    InvalidUtf8InString,
+    LineTooLong,
 }
 
 impl Token {
@@ -287,14 +404,14 @@ impl Token {
     pub fn has_right_operand(self) -> bool {
         self.fixity().has_right_operand()
     }
-    pub fn to_string(self, ast: &Ast) -> Cow<str> {
+    pub fn to_string(self, ast: &Ast) -> Cow<'_, str> {
         use Token::*;
         match self {
             Expression(token) => token.to_string(ast),
             Operator(token) => token.to_string(ast),
         }
     }
-    pub fn to_visible_string(self, ast: &Ast) -> Cow<str> {
+    pub fn to_visible_string(self, ast: &Ast) -> Cow<'_, str> {
         use Token::*;
         match self {
             Expression(token) => token.to_visible_string(ast),
@@ -308,7 +425,7 @@ impl Token {
             Expression(token) => token.takes_right_child(right),
         }
     }
-    pub fn original_bytes(self, ast: &Ast) -> Cow<[u8]> {
+    pub fn original_bytes(self, ast: &Ast) -> Cow<'_, [u8]> {
         use Token::*;
         match self {
             Expression(token) => token.original_bytes(ast),
@@ -342,7 +459,7 @@ impl ExpressionToken {
     pub fn has_right_operand(self) -> bool {
         self.fixity().has_right_operand()
     }
-    pub fn to_string(self, ast: &Ast) -> Cow<str> {
+    pub fn to_string(self, ast: &Ast) -> Cow<'_, str> {
         use ExpressionBoundaryError::*;
         use ExpressionToken::*;
         match self {
@@ -354,10 +471,13 @@ impl ExpressionToken {
             Open(Some(OpenWithoutClose), boundary, _) => {
                 format!("unclosed {}", boundary.open_string()).into()
             }
+            Open(Some(MultipleUndent), boundary, _) => {
+                format!("undented {}", boundary.open_string()).into()
+            }
             Open(None, boundary, _) => boundary.open_string().into(),
         }
     }
-    pub fn to_visible_string(self, ast: &Ast) -> Cow<str> {
+    pub fn to_visible_string(self, ast: &Ast) -> Cow<'_, str> {
         use ExpressionBoundaryError::*;
         use ExpressionToken::*;
         match self {
@@ -368,6 +488,9 @@ impl ExpressionToken {
             Open(Some(OpenWithoutClose), boundary, _) => {
                 format!("unclosed {}", boundary.visible_open_string()).into()
             }
+            Open(Some(MultipleUndent), boundary, _) => {
+                format!("undented {}", boundary.visible_open_string()).into()
+            }
             Open(None, boundary, _) => boundary.visible_open_string().into(),
             _ => self.to_string(ast),
         }
@@ -375,7 +498,7 @@ impl ExpressionToken {
     pub fn takes_right_child(self, right: impl Into<Token>) -> bool {
         self.fixity().takes_right_child(right.into().fixity())
     }
-    pub fn original_bytes(self, ast: &Ast) -> Cow<[u8]> {
+    pub fn original_bytes(self, ast: &Ast) -> Cow<'_, [u8]> {
         use ExpressionToken::*;
         match self {
             Term(token) => token.original_bytes(ast),
@@ -407,7 +530,7 @@ impl OperatorToken {
         use OperatorToken::*;
         matches!(self, InfixOperator(COLON))
     }
-    pub fn to_string(self, ast: &Ast) -> Cow<str> {
+    pub fn to_string(self, ast: &Ast) -> Cow<'_, str> {
         use OperatorToken::*;
         match self {
             InfixOperator(NEWLINE_SEQUENCE)
@@ -421,7 +544,7 @@ impl OperatorToken {
             Close(_, boundary) | CloseBlock(_, boundary) => boundary.close_string().into(),
         }
     }
-    pub fn to_visible_string(self, ast: &Ast) -> Cow<str> {
+    pub fn to_visible_string(self, ast: &Ast) -> Cow<'_, str> {
         use OperatorToken::*;
         match self {
             InfixOperator(NEWLINE_SEQUENCE) => "<\\n>".into(),
@@ -441,7 +564,7 @@ impl OperatorToken {
         }
     }
 
-    pub fn original_bytes(self, ast: &Ast) -> Cow<[u8]> {
+    pub fn original_bytes(self, ast: &Ast) -> Cow<'_, [u8]> {
         use OperatorToken::*;
         match self {
             InfixOperator(NEWLINE_SEQUENCE)
@@ -469,10 +592,18 @@ impl OperatorToken {
 }
 
 impl TermToken {
-    pub fn to_string(self, ast: &Ast) -> Cow<str> {
+    pub fn to_string(self, ast: &Ast) -> Cow<'_, str> {
         use TermToken::*;
         match self {
-            IntegerLiteral(literal) => ast.literal_string(literal).into(),
+            IntegerLiteral(literal)
+            | HexadecimalLiteral(literal)
+            | OctalLiteral(literal)
+            | BinaryLiteral(literal)
+            | FloatLiteral(literal, _)
+            | ImaginaryLiteral(literal, _)
+            | StringLiteral(literal)
+            | StringFragment(literal)
+            | TextBlock(literal) => ast.literal_string(literal).into(),
             ErrorTerm(code, ..) => format!("error({:?})", code).into(),
             RawErrorTerm(code, ..) => format!("error({:?})", code).into(),
             FieldReference(field) => ast.identifier_string(ast.fields[field].name).into(),
@@ -480,17 +611,26 @@ impl TermToken {
             MissingExpression => "".into(),
         }
     }
-    pub fn to_visible_string(self, ast: &Ast) -> Cow<str> {
+    pub fn to_visible_string(self, ast: &Ast) -> Cow<'_, str> {
         use TermToken::*;
         match self {
             MissingExpression => "<missing>".into(),
             _ => self.to_string(ast),
         }
     }
-    pub fn original_bytes(self, ast: &Ast) -> Cow<[u8]> {
+    pub fn original_bytes(self, ast: &Ast) -> Cow<'_, [u8]> {
         use TermToken::*;
         match self {
-            IntegerLiteral(literal) | ErrorTerm(.., literal) => {
+            IntegerLiteral(literal)
+            | HexadecimalLiteral(literal)
+            | OctalLiteral(literal)
+            | BinaryLiteral(literal)
+            | FloatLiteral(literal, _)
+            | ImaginaryLiteral(literal, _)
+            | StringLiteral(literal)
+            | StringFragment(literal)
+            | TextBlock(literal)
+            | ErrorTerm(.., literal) => {
                 ast.literal_string(literal).as_bytes()
             }
             RawErrorTerm(.., raw_literal) => &ast.raw_literals[raw_literal],
@@ -504,20 +644,56 @@ impl TermToken {
     }
 }
 
+impl FloatParts {
+    ///
+    /// Finds the parts of a float or imaginary literal (`12.34E-56` or `12i`)
+    /// with its `_` separators already removed.
+    ///
+    pub fn new(literal: &str) -> FloatParts {
+        let digits = |bytes: &[u8]| bytes.iter().take_while(|b| b.is_ascii_digit()).count();
+        let literal = literal.as_bytes();
+        let mantissa_len = digits(literal);
+        let fraction_len = match literal.get(mantissa_len) {
+            Some(b'.') => digits(&literal[mantissa_len + 1..]),
+            _ => 0,
+        };
+        FloatParts {
+            mantissa_len: mantissa_len.try_into().unwrap(),
+            fraction_len: fraction_len.try_into().unwrap(),
+        }
+    }
+
+    ///
+    /// The mantissa, fraction and exponent of the literal, without the `.`,
+    /// `e` and `i`. `12.34E-56` yields `("12", "34", "-56")`. Missing parts are
+    /// empty.
+    ///
+    pub fn split(self, literal: &str) -> (&str, &str, &str) {
+        let literal = literal.strip_suffix(['i', 'I']).unwrap_or(literal);
+        let (mantissa, rest) = literal.split_at(self.mantissa_len.into());
+        let (fraction, rest) = match self.fraction_len {
+            0 => ("", rest),
+            len => rest[1..].split_at(len.into()),
+        };
+        (mantissa, fraction, rest.get(1..).unwrap_or(""))
+    }
+}
+
 impl ExpressionBoundary {
     /// Tells whether this expression boundary represents a block.
     pub fn is_block(self) -> bool {
         match self {
             CurlyBraces | Source | Root | AutoBlock | IndentedBlock => true,
-            Parentheses | PrecedenceGroup | CompoundTerm | IndentedExpression => false,
+            Parentheses | SquareBrackets | PrecedenceGroup | CompoundTerm | IndentedExpression
+            | Interpolation | InterpolatedString => false,
         }
     }
     /// Tells whether this boundary type MUST be in the expression tree (because
     /// it represents actual user syntax, or opens a scope).
     pub fn is_required(self) -> bool {
         match self {
-            Root | Source | CurlyBraces | Parentheses | AutoBlock | IndentedBlock
-            | IndentedExpression => true,
+            Root | Source | CurlyBraces | Parentheses | SquareBrackets | Interpolation
+            | InterpolatedString | AutoBlock | IndentedBlock | IndentedExpression => true,
             PrecedenceGroup | CompoundTerm => false,
         }
     }
@@ -526,7 +702,8 @@ impl ExpressionBoundary {
     pub fn is_closed_automatically(self) -> bool {
         match self {
             PrecedenceGroup | CompoundTerm | AutoBlock | IndentedBlock | IndentedExpression => true,
-            Root | Source | CurlyBraces | Parentheses => false,
+            Root | Source | CurlyBraces | Parentheses | SquareBrackets | Interpolation
+            | InterpolatedString => false,
         }
     }
     pub fn placeholder_open_token(self, error: Option<ExpressionBoundaryError>) -> ExpressionToken {
@@ -539,6 +716,9 @@ impl ExpressionBoundary {
         match self {
             CurlyBraces => OPEN_CURLY.well_known_str(),
             Parentheses => OPEN_PAREN.well_known_str(),
+            SquareBrackets => OPEN_SQUARE.well_known_str(),
+            Interpolation => "#{",
+            InterpolatedString => "\"",
             PrecedenceGroup | AutoBlock | IndentedBlock | IndentedExpression | CompoundTerm
             | Source | Root => "",
         }
@@ -547,6 +727,9 @@ impl ExpressionBoundary {
         match self {
             CurlyBraces => OPEN_CURLY.well_known_str(),
             Parentheses => OPEN_PAREN.well_known_str(),
+            SquareBrackets => OPEN_SQUARE.well_known_str(),
+            Interpolation => "#{",
+            InterpolatedString => "\"",
             PrecedenceGroup => "precedence (",
             AutoBlock => "auto {",
             IndentedBlock => "indent {",
@@ -560,6 +743,9 @@ impl ExpressionBoundary {
         match self {
             CurlyBraces => CLOSE_CURLY.well_known_str(),
             Parentheses => CLOSE_PAREN.well_known_str(),
+            SquareBrackets => CLOSE_SQUARE.well_known_str(),
+            Interpolation => CLOSE_CURLY.well_known_str(),
+            InterpolatedString => "\"",
             PrecedenceGroup | AutoBlock | IndentedBlock | IndentedExpression | CompoundTerm
             | Source | Root => "",
         }
@@ -568,6 +754,9 @@ impl ExpressionBoundary {
         match self {
             CurlyBraces => CLOSE_CURLY.well_known_str(),
             Parentheses => CLOSE_PAREN.well_known_str(),
+            SquareBrackets => CLOSE_SQUARE.well_known_str(),
+            Interpolation => CLOSE_CURLY.well_known_str(),
+            InterpolatedString => "\"",
             PrecedenceGroup => ") precedence",
             AutoBlock => "} auto",
             IndentedBlock => "} indent",
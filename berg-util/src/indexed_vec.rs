@@ -742,10 +742,10 @@ impl<Elem, Idx: IndexType> IndexedSlice<Elem, Idx> {
         let index: usize = index.into();
         self.slice.get(index)
     }
-    pub fn iter(&self) -> IndexedIter<Iter<Elem>, Idx> {
+    pub fn iter(&self) -> IndexedIter<Iter<'_, Elem>, Idx> {
         self.slice.iter().into()
     }
-    pub fn iter_mut(&mut self) -> IndexedIter<IterMut<Elem>, Idx> {
+    pub fn iter_mut(&mut self) -> IndexedIter<IterMut<'_, Elem>, Idx> {
         self.slice.iter_mut().into()
     }
     pub fn is_empty(&self) -> bool {
@@ -899,7 +899,7 @@ impl<Elem: Clone, Idx: IndexType> ToOwned for IndexedSlice<Elem, Idx> {
 ///
 /// A Vec with a specific index type (so you don't accidentally use one Vec's index on another Vec).
 ///
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct IndexedVec<Elem, Idx: IndexType>(Vec<Elem>, PhantomData<Idx>);
 impl<Elem, Idx: IndexType> IndexedVec<Elem, Idx> {
     pub fn push(&mut self, value: Elem) -> Idx {
@@ -1,54 +1,207 @@
 //#[macro_use] extern crate log;
 extern crate berg_compiler;
+extern crate berg_parser;
+extern crate berg_util;
 extern crate docopt;
 extern crate env_logger;
 #[macro_use]
 extern crate serde_derive;
 
 use berg_compiler::*;
+use berg_util::to_indexed_cow;
 use docopt::Docopt;
+use std::borrow::Cow;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use std::process;
 
 const USAGE: &str = "
 Berg compiler and evaluator.
 
 Usage:
-  berg check syntax <file>
-  berg check syntax -e <expr>
+  berg run [--trace=<format>] [--columns=<unit>] <file> [<args>...]
+  berg eval [--trace=<format>] [--columns=<unit>] -e <expr>
+  berg check syntax [--trace=<format>] [--columns=<unit>] <file>
+  berg check syntax [--trace=<format>] [--columns=<unit>] -e <expr>
+  berg fmt [--check] <files>...
 
 Options:
-  -h --help     Show this screen.
-  --version     Show version.
-  -e <expr>     Run this expression.
+  -h --help         Show this screen.
+  --version         Show version.
+  -e <expr>         Run this expression.
+  --trace=<format>  Trace evaluation to stderr as text or json.
+  --check           Don't write the files; fail if any of them isn't formatted.
+  --columns=<unit>  Count columns in error and warning locations as bytes,
+                    chars or utf16 [default: bytes].
+
+run passes <args> to the program, which reads them as the tuple args.
+
+run and eval print the result. They (and check syntax) exit with:
+  0  Success.
+  1  An exception was thrown and not caught.
+  2  The source has a syntax error.
+  3  The source could not be loaded.
+
+fmt exits with:
+  0  Success.
+  1  With --check, a file isn't formatted.
+  2  A file could not be read, written or formatted.
+
+Any command exits with 4 if the arguments are invalid.
 ";
 
+const EXIT_EXCEPTION: i32 = 1;
+const EXIT_SYNTAX_ERROR: i32 = 2;
+const EXIT_SOURCE_LOAD_ERROR: i32 = 3;
+const EXIT_BAD_ARGUMENTS: i32 = 4;
 
 #[derive(Debug, Deserialize)]
 struct Args {
     arg_file: Option<String>,
+    arg_args: Vec<String>,
     flag_e: Option<String>,
+    arg_files: Vec<String>,
+    flag_check: bool,
+    flag_trace: Option<TraceFormat>,
+    flag_columns: Columns,
+    cmd_run: bool,
+    cmd_eval: bool,
     cmd_check: bool,
     cmd_syntax: bool,
+    cmd_fmt: bool,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum TraceFormat {
+    Text,
+    Json,
+}
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum Columns {
+    Bytes,
+    Chars,
+    Utf16,
+}
+
+impl From<Columns> for ColumnUnit {
+    fn from(columns: Columns) -> Self {
+        match columns {
+            Columns::Bytes => ColumnUnit::Bytes,
+            Columns::Chars => ColumnUnit::Chars,
+            Columns::Utf16 => ColumnUnit::Utf16,
+        }
+    }
 }
 
 fn main() {
     env_logger::init().unwrap();
     let args: Args = Docopt::new(USAGE)
         .and_then(|d| d.deserialize())
-        .unwrap_or_else(|e| e.exit());
-    assert!(args.cmd_check);
-    assert!(args.cmd_syntax);
+        .unwrap_or_else(|error| {
+            // docopt exits with 1 for bad arguments, which means an exception.
+            if error.fatal() {
+                eprintln!("{}", error);
+                process::exit(EXIT_BAD_ARGUMENTS);
+            }
+            error.exit()
+        });
+    if args.cmd_fmt {
+        process::exit(format_files(&args.arg_files, args.flag_check));
+    }
+    assert!(args.cmd_run || args.cmd_eval || (args.cmd_check && args.cmd_syntax));
+
+    let result = match args.flag_trace {
+        None => evaluate(&args, NoTracer),
+        Some(TraceFormat::Text) => evaluate(&args, TextTracer::new(io::stderr())),
+        Some(TraceFormat::Json) => evaluate(&args, JsonTracer::new(io::stderr())),
+    };
+    match result {
+        Ok(value) if args.cmd_run || args.cmd_eval => println!("{}", value),
+        Ok(_) => {}
+        Err(error) => {
+            print_error(&error);
+            process::exit(exit_code(&error));
+        }
+    }
+}
 
-    let mut compiler = Compiler::from_env();
-    add_source(&mut compiler, &args);
+///
+/// The exit code for an exception nothing caught.
+///
+fn exit_code(error: &Exception) -> i32 {
+    match error.code() {
+        Some(code) if code.is_source_load_error() => EXIT_SOURCE_LOAD_ERROR,
+        Some(code) if code.is_syntax_error() => EXIT_SYNTAX_ERROR,
+        _ => EXIT_EXCEPTION,
+    }
 }
 
-fn add_source<'p>(compiler: &mut Compiler<'p>, args: &'p Args) {
+///
+/// Prints the error, with the source and line:column it is at if it has them.
+///
+fn print_error(error: &Exception) {
+    let location = error.location();
+    match (&location, location.source_name()) {
+        (ErrorLocation::SourceExpression(..), Some(name))
+        | (ErrorLocation::SourceRange(..), Some(name)) => {
+            eprintln!("{}:{}: {}", name, location.range(), error)
+        }
+        (_, Some(name)) => eprintln!("{}: {}", name, error),
+        (_, None) => eprintln!("{}", error),
+    }
+}
+
+fn evaluate(args: &Args, tracer: impl Tracer + 'static) -> BergResult {
     if let Some(ref file) = args.arg_file {
         assert!(args.flag_e.is_none());
-        compiler.add_file_source(file)
+        let columns = args.flag_columns.into();
+        evaluate_file_with_args(PathBuf::from(file), args.arg_args.clone(), tracer, columns)
     } else if let Some(ref expr) = args.flag_e {
-        compiler.add_memory_source("expr", expr.as_bytes())
+        let columns = args.flag_columns.into();
+        evaluate_bytes_with_columns("expr", expr.clone().into_bytes(), tracer, columns)
     } else {
         panic!("No source passed: {:?}", args)
     }
 }
+
+///
+/// Formats each file in place (or with `check`, lists the files that aren't
+/// formatted). Returns the exit code.
+///
+fn format_files(files: &[String], check: bool) -> i32 {
+    let mut exit_code = 0;
+    for file in files {
+        let source = match fs::read(file) {
+            Ok(source) => source,
+            Err(error) => {
+                eprintln!("{}: {}", file, error);
+                exit_code = 2;
+                continue;
+            }
+        };
+        let ast = berg_parser::parse(to_indexed_cow(Cow::Owned(source.clone())));
+        let formatted = match berg_parser::format_source(&ast) {
+            Ok(formatted) => formatted,
+            Err(error) => {
+                eprintln!("{}: {}", file, error);
+                exit_code = 2;
+                continue;
+            }
+        };
+        if formatted == source {
+            continue;
+        }
+        if check {
+            println!("{}", file);
+            exit_code = exit_code.max(1);
+        } else if let Err(error) = fs::write(file, formatted) {
+            eprintln!("{}: {}", file, error);
+            exit_code = 2;
+        }
+    }
+    exit_code
+}
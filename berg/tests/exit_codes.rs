@@ -0,0 +1,76 @@
+use std::env;
+use std::fs;
+use std::process::{Command, Output};
+
+fn berg(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_berg"))
+        .args(args)
+        .output()
+        .unwrap()
+}
+
+fn exit_code(args: &[&str]) -> i32 {
+    berg(args).status.code().unwrap()
+}
+
+#[test]
+fn eval_success() {
+    let output = berg(&["eval", "-e", "1+1"]);
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(output.stdout, b"2\n");
+}
+
+#[test]
+fn eval_exception() {
+    assert_eq!(exit_code(&["eval", "-e", "1/0"]), 1);
+}
+
+#[test]
+fn eval_syntax_error() {
+    assert_eq!(exit_code(&["eval", "-e", "(1"]), 2);
+}
+
+#[test]
+fn run_file() {
+    let file = env::temp_dir().join("berg_exit_codes_run_file.bg");
+    fs::write(&file, "1 + 2\n").unwrap();
+    let output = berg(&["run", file.to_str().unwrap()]);
+    fs::remove_file(&file).unwrap();
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(output.stdout, b"3\n");
+}
+
+#[test]
+fn run_missing_file() {
+    assert_eq!(exit_code(&["run", "does/not/exist.bg"]), 3);
+}
+
+#[test]
+fn run_with_program_arguments() {
+    let file = env::temp_dir().join("berg_exit_codes_run_with_program_arguments.bg");
+    fs::write(&file, "\"Hello, \" + args[1] + \"!\"\n").unwrap();
+    let output = berg(&["run", file.to_str().unwrap(), "there", "world"]);
+    fs::remove_file(&file).unwrap();
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(output.stdout, b"Hello, world!\n");
+}
+
+#[test]
+fn run_without_program_arguments() {
+    let file = env::temp_dir().join("berg_exit_codes_run_without_program_arguments.bg");
+    fs::write(&file, "args\n").unwrap();
+    let output = berg(&["run", file.to_str().unwrap()]);
+    fs::remove_file(&file).unwrap();
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(output.stdout, b"()\n");
+}
+
+#[test]
+fn run_missing_file_with_program_arguments() {
+    assert_eq!(exit_code(&["run", "does/not/exist.bg", "a"]), 3);
+}
+
+#[test]
+fn no_command() {
+    assert_eq!(exit_code(&[]), 4);
+}